@@ -100,7 +100,9 @@ fn find_match_by_sorted_words<'a>(iter_names: Vec<&'a Symbol>, lookup: &str) ->
     })
 }
 
-fn sort_by_words(name: &str) -> String {
+/// The words of the snake case `name` in alphabetical order, so that names made of the same words
+/// in another order compare equal.
+pub fn sort_by_words(name: &str) -> String {
     let mut split_words: Vec<&str> = name.split('_').collect();
     split_words.sort();
     split_words.join("_")
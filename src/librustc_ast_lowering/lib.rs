@@ -56,7 +56,8 @@ use rustc_hir::intravisit;
 use rustc_hir::{ConstArg, GenericArg, ParamName};
 use rustc_index::vec::{Idx, IndexVec};
 use rustc_session::config::nightly_options;
-use rustc_session::lint::{builtin::BARE_TRAIT_OBJECTS, BuiltinLintDiagnostics, LintBuffer};
+use rustc_session::lint::builtin::BARE_TRAIT_OBJECTS;
+use rustc_session::lint::{BareTraitObjectPosition, BuiltinLintDiagnostics, LintBuffer};
 use rustc_session::parse::ParseSess;
 use rustc_session::Session;
 use rustc_span::hygiene::ExpnId;
@@ -130,6 +131,10 @@ struct LoweringContext<'a, 'hir: 'a> {
     is_in_trait_impl: bool,
     is_in_dyn_type: bool,
 
+    /// The type of the function parameter or return type being lowered, if `impl Trait` is
+    /// allowed there. Used to suggest `impl Trait` for a bare trait object in that position.
+    fn_sig_ty: Option<(NodeId, BareTraitObjectPosition)>,
+
     /// What to do when we encounter either an "anonymous lifetime
     /// reference". The term "anonymous" is meant to encompass both
     /// `'_` lifetimes as well as fully elided cases where nothing is
@@ -324,6 +329,7 @@ pub fn lower_crate<'a, 'hir>(
         is_in_loop_condition: false,
         is_in_trait_impl: false,
         is_in_dyn_type: false,
+        fn_sig_ty: None,
         anonymous_lifetime_mode: AnonymousLifetimeMode::PassThrough,
         type_def_lifetime_params: Default::default(),
         current_module: hir::CRATE_HIR_ID,
@@ -1795,6 +1801,8 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
         };
 
         let c_variadic = decl.c_variadic();
+        // Function declarations can be nested in the types being lowered.
+        let prev_fn_sig_ty = self.fn_sig_ty.take();
 
         // Remember how many lifetimes were already around so that we can
         // only look at the lifetime parameters introduced by the arguments.
@@ -1808,6 +1816,7 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
             }
             this.arena.alloc_from_iter(inputs.iter().map(|param| {
                 if let Some((_, ibty)) = &mut in_band_ty_params {
+                    this.fn_sig_ty = Some((param.ty.id, BareTraitObjectPosition::FnParam));
                     this.lower_ty_direct(&param.ty, ImplTraitContext::Universal(ibty))
                 } else {
                     this.lower_ty_direct(&param.ty, ImplTraitContext::disallowed())
@@ -1826,6 +1835,7 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
                 FnRetTy::Ty(ref ty) => {
                     let context = match in_band_ty_params {
                         Some((def_id, _)) if impl_trait_return_allow => {
+                            self.fn_sig_ty = Some((ty.id, BareTraitObjectPosition::FnReturn));
                             ImplTraitContext::ReturnPositionOpaqueTy {
                                 fn_def_id: def_id,
                                 origin: hir::OpaqueTyOrigin::FnReturn,
@@ -1838,6 +1848,7 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
                 FnRetTy::Default(span) => hir::FnRetTy::DefaultReturn(span),
             }
         };
+        self.fn_sig_ty = prev_fn_sig_ty;
 
         self.arena.alloc(hir::FnDecl {
             inputs,
@@ -2751,12 +2762,16 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
             .map(|snippet| snippet.starts_with("#["))
             .unwrap_or(true);
         if !is_macro_callsite {
+            let position = match self.fn_sig_ty {
+                Some((ty_id, position)) if ty_id == id => position,
+                _ => BareTraitObjectPosition::Other,
+            };
             self.resolver.lint_buffer().buffer_lint_with_diagnostic(
                 BARE_TRAIT_OBJECTS,
                 id,
                 span,
                 "trait objects without an explicit `dyn` are deprecated",
-                BuiltinLintDiagnostics::BareTraitObject(span, is_global),
+                BuiltinLintDiagnostics::BareTraitObject(span, is_global, position),
             )
         }
     }
@@ -70,6 +70,19 @@ pub enum Applicability {
     Unspecified,
 }
 
+impl Applicability {
+    /// How certain a suggestion with this applicability is to be what was meant, from least to
+    /// most certain.
+    pub fn certainty(self) -> u8 {
+        match self {
+            Applicability::Unspecified => 0,
+            Applicability::HasPlaceholders => 1,
+            Applicability::MaybeIncorrect => 2,
+            Applicability::MachineApplicable => 3,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, RustcEncodable, RustcDecodable)]
 pub enum SuggestionStyle {
     /// Hide the suggested code when displaying this suggestion inline.
@@ -325,6 +338,7 @@ struct HandlerInner {
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum StashKey {
     ItemNoType,
+    NewOperator,
 }
 
 fn default_track_diagnostic(_: &Diagnostic) {}
@@ -351,6 +365,9 @@ pub struct HandlerFlags {
     pub macro_backtrace: bool,
     /// If true, identical diagnostics are reported only once.
     pub deduplicate_diagnostics: bool,
+    /// If set, suggestions less certain than this are dropped.
+    /// (rustc: see `-Z suggestion-applicability`)
+    pub suggestion_applicability: Option<Applicability>,
 }
 
 impl Drop for HandlerInner {
@@ -740,6 +757,16 @@ impl HandlerInner {
             return;
         }
 
+        if let Some(threshold) = self.flags.suggestion_applicability {
+            let is_uncertain =
+                |sugg: &CodeSuggestion| sugg.applicability.certainty() < threshold.certainty();
+            if diagnostic.suggestions.iter().any(is_uncertain) {
+                let mut diagnostic = diagnostic.clone();
+                diagnostic.suggestions.retain(|sugg| !is_uncertain(sugg));
+                return self.emit_diagnostic(&diagnostic);
+            }
+        }
+
         (*TRACK_DIAGNOSTICS)(diagnostic);
 
         if let Some(ref code) = diagnostic.code {
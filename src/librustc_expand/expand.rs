@@ -704,7 +704,7 @@ impl<'a, 'b> MacroExpander<'a, 'b> {
             InvocationKind::Attr { attr, mut item, derives, after_derive } => match ext {
                 SyntaxExtensionKind::Attr(expander) => {
                     self.gate_proc_macro_input(&item);
-                    self.gate_proc_macro_attr_item(span, &item);
+                    self.gate_proc_macro_attr_item(span, &attr.get_normal_item().path, &item);
                     let tokens = item.into_tokens(self.cx.parse_sess);
                     let attr_item = attr.unwrap_normal_item();
                     if let MacArgs::Eq(..) = attr_item.args {
@@ -787,14 +787,14 @@ impl<'a, 'b> MacroExpander<'a, 'b> {
         })
     }
 
-    fn gate_proc_macro_attr_item(&self, span: Span, item: &Annotatable) {
-        let kind = match item {
+    fn gate_proc_macro_attr_item(&self, span: Span, path: &Path, item: &Annotatable) {
+        let (kind, target) = match item {
             Annotatable::Item(_)
             | Annotatable::TraitItem(_)
             | Annotatable::ImplItem(_)
             | Annotatable::ForeignItem(_) => return,
-            Annotatable::Stmt(_) => "statements",
-            Annotatable::Expr(_) => "expressions",
+            Annotatable::Stmt(_) => ("statements", "a statement"),
+            Annotatable::Expr(_) => ("expressions", "an expression"),
             Annotatable::Arm(..)
             | Annotatable::Field(..)
             | Annotatable::FieldPat(..)
@@ -812,6 +812,10 @@ impl<'a, 'b> MacroExpander<'a, 'b> {
             span,
             &format!("custom attributes cannot be applied to {}", kind),
         )
+        .span_label(
+            span,
+            format!("attribute macro `{}` applied to {}", pprust::path_to_string(path), target),
+        )
         .emit();
     }
 
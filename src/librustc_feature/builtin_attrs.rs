@@ -450,6 +450,10 @@ pub const BUILTIN_ATTRIBUTES: &[BuiltinAttribute] = &[
         ),
         INTERNAL_UNSTABLE
     ),
+    // Adds a note to the errors for names that fail to resolve in a module or crate.
+    rustc_attr!(
+        rustc_on_unresolved, AssumedUsed, template!(NameValueStr: "note"), INTERNAL_UNSTABLE
+    ),
     // Enumerates "identity-like" conversion methods to suggest on type mismatch.
     rustc_attr!(rustc_conversion_suggestion, AssumedUsed, template!(Word), INTERNAL_UNSTABLE),
 
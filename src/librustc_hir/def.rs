@@ -263,6 +263,7 @@ impl PartialRes {
 ///
 /// Therefore, they have a separate universe (namespace).
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[derive(RustcEncodable, RustcDecodable)]
 pub enum Namespace {
     TypeNS,
     ValueNS,
@@ -32,7 +32,8 @@ use rustc_middle::middle::privacy::AccessLevels;
 use rustc_middle::middle::stability;
 use rustc_middle::ty::layout::{LayoutError, TyAndLayout};
 use rustc_middle::ty::{self, print::Printer, subst::GenericArg, Ty, TyCtxt};
-use rustc_session::lint::{add_elided_lifetime_in_path_suggestion, BuiltinLintDiagnostics};
+use rustc_session::lint::{add_elided_lifetime_in_path_suggestion, BareTraitObjectPosition};
+use rustc_session::lint::BuiltinLintDiagnostics;
 use rustc_session::lint::{FutureIncompatibleInfo, Level, Lint, LintBuffer, LintId};
 use rustc_session::Session;
 use rustc_span::{symbol::Symbol, MultiSpan, Span, DUMMY_SP};
@@ -496,15 +497,64 @@ pub trait LintContext: Sized {
             let sess = self.sess();
             match diagnostic {
                 BuiltinLintDiagnostics::Normal => (),
-                BuiltinLintDiagnostics::BareTraitObject(span, is_global) => {
-                    let (sugg, app) = match sess.source_map().span_to_snippet(span) {
-                        Ok(s) if is_global => {
-                            (format!("dyn ({})", s), Applicability::MachineApplicable)
+                BuiltinLintDiagnostics::BareTraitObject(span, is_global, position) => {
+                    let snippet = sess.source_map().span_to_snippet(span);
+                    match (position, snippet) {
+                        // A trait object can't be passed or returned by value, so `dyn` alone
+                        // won't do, suggest the alternatives instead.
+                        (BareTraitObjectPosition::FnParam, Ok(s)) => {
+                            let dyn_sugg = if is_global {
+                                format!("&dyn ({})", s)
+                            } else if s.contains('+') {
+                                format!("&(dyn {})", s)
+                            } else {
+                                format!("&dyn {}", s)
+                            };
+                            db.span_suggestion(
+                                span,
+                                "use `impl` to accept any type that implements the trait",
+                                format!("impl {}", s),
+                                Applicability::MaybeIncorrect,
+                            );
+                            db.span_suggestion(
+                                span,
+                                "use a reference to a trait object",
+                                dyn_sugg,
+                                Applicability::MaybeIncorrect,
+                            );
                         }
-                        Ok(s) => (format!("dyn {}", s), Applicability::MachineApplicable),
-                        Err(_) => ("dyn <type>".to_string(), Applicability::HasPlaceholders),
-                    };
-                    db.span_suggestion(span, "use `dyn`", sugg, app);
+                        (BareTraitObjectPosition::FnReturn, Ok(s)) => {
+                            let dyn_sugg = if is_global {
+                                format!("Box<dyn ({})>", s)
+                            } else {
+                                format!("Box<dyn {}>", s)
+                            };
+                            db.span_suggestion(
+                                span,
+                                "use `impl` to return a single type that implements the trait",
+                                format!("impl {}", s),
+                                Applicability::MaybeIncorrect,
+                            );
+                            db.span_suggestion(
+                                span,
+                                "box the trait object to return different types",
+                                dyn_sugg,
+                                Applicability::MaybeIncorrect,
+                            );
+                        }
+                        (_, snippet) => {
+                            let (sugg, app) = match snippet {
+                                Ok(s) if is_global => {
+                                    (format!("dyn ({})", s), Applicability::MachineApplicable)
+                                }
+                                Ok(s) => (format!("dyn {}", s), Applicability::MachineApplicable),
+                                Err(_) => {
+                                    ("dyn <type>".to_string(), Applicability::HasPlaceholders)
+                                }
+                            };
+                            db.span_suggestion(span, "use `dyn`", sugg, app);
+                        }
+                    }
                 }
                 BuiltinLintDiagnostics::AbsPathWithModule(span) => {
                     let (sugg, app) = match sess.source_map().span_to_snippet(span) {
@@ -577,6 +627,52 @@ pub trait LintContext: Sized {
                     db.help("to document an item produced by a macro, \
                                   the macro must produce the documentation as part of its expansion");
                 }
+                BuiltinLintDiagnostics::BindingSimilarToVariant(span, path) => {
+                    db.note(&format!(
+                        "this creates a new binding; did you mean the variant `{}`?",
+                        path
+                    ));
+                    db.span_suggestion(
+                        span,
+                        "to match on the variant, use its path",
+                        path,
+                        Applicability::MaybeIncorrect,
+                    );
+                }
+                BuiltinLintDiagnostics::SpelledOutSelfType(span) => {
+                    db.span_suggestion(
+                        span,
+                        "use `Self` instead",
+                        "Self".to_string(),
+                        Applicability::MachineApplicable,
+                    );
+                }
+                BuiltinLintDiagnostics::ShadowedMacroRules(ident, used, shadowed, exported) => {
+                    let note = format!("`{}` could refer to the macro defined here", ident);
+                    db.span_note(used, &note);
+                    let note = format!("`{}` could also refer to the macro defined here", ident);
+                    db.span_note(shadowed, &note);
+                    let sugg = format!("crate::{}", ident);
+                    match exported {
+                        Some(true) => db.span_suggestion(
+                            ident.span,
+                            "use a path to refer to this macro unambiguously",
+                            sugg,
+                            Applicability::MachineApplicable,
+                        ),
+                        Some(false) => db.span_suggestion(
+                            ident.span,
+                            "use a path to refer to the shadowed macro",
+                            sugg,
+                            Applicability::MaybeIncorrect,
+                        ),
+                        None => db.help(&format!(
+                            "consider marking the intended macro `#[macro_export]` \
+                             and invoking it as `{}!`",
+                            sugg
+                        )),
+                    };
+                }
             }
             // Rewrap `db`, and pass control to the user.
             decorate(LintDiagnosticBuilder::new(db));
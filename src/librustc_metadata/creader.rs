@@ -955,4 +955,9 @@ impl<'a> CrateLoader<'a> {
     pub fn maybe_process_path_extern(&mut self, name: Symbol, span: Span) -> Option<CrateNum> {
         self.maybe_resolve_crate(name, span, DepKind::Explicit, None).ok()
     }
+
+    /// Like `maybe_process_path_extern`, but only finds crates which are already loaded.
+    pub fn loaded_path_extern(&self, name: Symbol) -> Option<CrateNum> {
+        self.existing_match(name, None, PathKind::Crate)
+    }
 }
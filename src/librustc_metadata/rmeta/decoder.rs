@@ -21,7 +21,7 @@ use rustc_hir::definitions::{DefKey, DefPath, DefPathData, DefPathHash};
 use rustc_hir::lang_items;
 use rustc_index::vec::{Idx, IndexVec};
 use rustc_middle::dep_graph::{self, DepNode, DepNodeExt, DepNodeIndex};
-use rustc_middle::hir::exports::Export;
+use rustc_middle::hir::exports::{Export, ImportCandidate};
 use rustc_middle::middle::cstore::{CrateSource, ExternCrate};
 use rustc_middle::middle::cstore::{ForeignModule, LinkagePreference, NativeLib};
 use rustc_middle::middle::exported_symbols::{ExportedSymbol, SymbolExportLevel};
@@ -80,6 +80,8 @@ crate struct CrateMetadata {
     raw_proc_macros: Option<&'static [ProcMacro]>,
     /// Source maps for code from the crate.
     source_map_import_info: OnceCell<Vec<ImportedSourceFile>>,
+    /// The index of the public items of the crate for import suggestions.
+    import_candidates: OnceCell<Vec<ImportCandidate>>,
     /// Used for decoding interpret::AllocIds in a cached & thread-safe manner.
     alloc_decoding_state: AllocDecodingState,
     /// The `DepNodeIndex` of the `DepNode` representing this upstream crate.
@@ -972,6 +974,19 @@ impl<'a, 'tcx> CrateMetadataRef<'a> {
         }
     }
 
+    /// The index of the public items of the crate, decoded on first use.
+    fn get_import_candidates(&self, sess: &Session) -> Option<&'a [ImportCandidate]> {
+        if self.root.is_proc_macro_crate() {
+            // Proc macro crates only export their macros, and have no index.
+            None
+        } else {
+            let candidates = self.cdata.import_candidates.get_or_init(|| {
+                self.root.import_candidates.decode((self, sess)).collect()
+            });
+            Some(candidates)
+        }
+    }
+
     /// Iterates over each child of the given item.
     fn each_child_of_item<F>(&self, id: DefIndex, mut callback: F, sess: &Session)
     where
@@ -1241,6 +1256,48 @@ impl<'a, 'tcx> CrateMetadataRef<'a> {
             .collect()
     }
 
+    fn get_inherent_fn_param_counts(
+        &self,
+        id: DefIndex,
+        name: Symbol,
+        sess: &Session,
+    ) -> Vec<usize> {
+        let tables = &self.root.tables;
+        tables
+            .inherent_impls
+            .get(self, id)
+            .unwrap_or(Lazy::empty())
+            .decode(self)
+            .flat_map(|index| {
+                tables.children.get(self, index).unwrap_or(Lazy::empty()).decode(self)
+            })
+            .filter(|&index| self.item_ident(index, sess).name == name)
+            .filter_map(|index| match self.kind(index) {
+                EntryKind::AssocFn(data) => Some(data.decode(self)),
+                _ => None,
+            })
+            .filter(|data| !data.has_self)
+            .map(|data| data.fn_data.param_names.meta)
+            .collect()
+    }
+
+    fn has_inherent_method(&self, id: DefIndex, name: Symbol, sess: &Session) -> bool {
+        let tables = &self.root.tables;
+        tables
+            .inherent_impls
+            .get(self, id)
+            .unwrap_or(Lazy::empty())
+            .decode(self)
+            .flat_map(|index| {
+                tables.children.get(self, index).unwrap_or(Lazy::empty()).decode(self)
+            })
+            .filter(|&index| self.item_ident(index, sess).name == name)
+            .any(|index| match self.kind(index) {
+                EntryKind::AssocFn(data) => data.decode(self).has_self,
+                _ => false,
+            })
+    }
+
     fn get_inherent_implementations_for_type(
         &self,
         tcx: TyCtxt<'tcx>,
@@ -1632,6 +1689,7 @@ impl CrateMetadata {
             trait_impls,
             raw_proc_macros,
             source_map_import_info: OnceCell::new(),
+            import_candidates: OnceCell::new(),
             alloc_decoding_state,
             dep_node_index: AtomicCell::new(DepNodeIndex::INVALID),
             cnum,
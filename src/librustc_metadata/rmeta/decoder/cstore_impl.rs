@@ -7,12 +7,14 @@ use crate::rmeta::{self, encoder};
 use rustc_ast::ast;
 use rustc_ast::attr;
 use rustc_ast::expand::allocator::AllocatorKind;
+use rustc_attr::Stability;
 use rustc_data_structures::svh::Svh;
 use rustc_hir as hir;
+use rustc_hir::def::DefKind;
 use rustc_hir::def_id::{CrateNum, DefId, DefIdMap, CRATE_DEF_INDEX, LOCAL_CRATE};
 use rustc_hir::definitions::DefPathTable;
 use rustc_hir::definitions::{DefKey, DefPath, DefPathHash};
-use rustc_middle::hir::exports::Export;
+use rustc_middle::hir::exports::{Export, ImportCandidate};
 use rustc_middle::middle::cstore::{CrateSource, CrateStore, EncodedMetadata};
 use rustc_middle::middle::exported_symbols::ExportedSymbol;
 use rustc_middle::middle::stability::DeprecationEntry;
@@ -386,6 +388,23 @@ impl CStore {
         self.get_crate_data(def.krate).get_struct_field_names(def.index, sess)
     }
 
+    /// Returns the number of parameters of each associated function named `name`, and taking no
+    /// `self`, in the inherent impls of the type `def`.
+    pub fn inherent_fn_param_counts_untracked(
+        &self,
+        def: DefId,
+        name: Symbol,
+        sess: &Session,
+    ) -> Vec<usize> {
+        self.get_crate_data(def.krate).get_inherent_fn_param_counts(def.index, name, sess)
+    }
+
+    /// Returns whether the inherent impls of the type `def` have a method named `name`, taking
+    /// `self`.
+    pub fn has_inherent_method_untracked(&self, def: DefId, name: Symbol, sess: &Session) -> bool {
+        self.get_crate_data(def.krate).has_inherent_method(def.index, name, sess)
+    }
+
     pub fn item_children_untracked(
         &self,
         def_id: DefId,
@@ -400,6 +419,16 @@ impl CStore {
         result
     }
 
+    /// Returns the public items of the crate `cnum`, each with the shortest path naming it, unless
+    /// it is a proc macro crate.
+    pub fn import_candidates_untracked(
+        &self,
+        cnum: CrateNum,
+        sess: &Session,
+    ) -> Option<&[ImportCandidate]> {
+        self.get_crate_data(cnum).get_import_candidates(sess)
+    }
+
     pub fn load_macro_untracked(&self, id: DefId, sess: &Session) -> LoadedMacro {
         let _prof_timer = sess.prof.generic_activity("metadata_load_macro");
 
@@ -450,6 +479,14 @@ impl CStore {
         self.get_crate_data(def_id.krate).get_span(def_id.index, sess)
     }
 
+    pub fn stability_untracked(&self, def_id: DefId) -> Option<Stability> {
+        self.get_crate_data(def_id.krate).get_stability(def_id.index)
+    }
+
+    pub fn def_kind_untracked(&self, def_id: DefId) -> DefKind {
+        self.get_crate_data(def_id.krate).def_kind(def_id.index)
+    }
+
     pub fn item_generics_num_lifetimes(&self, def_id: DefId, sess: &Session) -> usize {
         self.get_crate_data(def_id.krate).get_generics(def_id.index, sess).own_counts().lifetimes
     }
@@ -18,6 +18,7 @@ use rustc_hir::lang_items;
 use rustc_hir::{AnonConst, GenericParamKind};
 use rustc_index::bit_set::GrowableBitSet;
 use rustc_index::vec::Idx;
+use rustc_middle::hir::exports::ImportCandidate;
 use rustc_middle::hir::map::Map;
 use rustc_middle::middle::cstore::{EncodedMetadata, ForeignModule, LinkagePreference, NativeLib};
 use rustc_middle::middle::dependency_format::Linkage;
@@ -498,6 +499,11 @@ impl<'tcx> EncodeContext<'tcx> {
         let diagnostic_items = self.encode_diagnostic_items();
         let diagnostic_item_bytes = self.position() - i;
 
+        // Encode the index of public items for import suggestions.
+        i = self.position();
+        let import_candidates = self.encode_import_candidates();
+        let import_candidate_bytes = self.position() - i;
+
         // Encode the native libraries used
         i = self.position();
         let native_libraries = self.encode_native_libraries();
@@ -609,6 +615,7 @@ impl<'tcx> EncodeContext<'tcx> {
             lib_features,
             lang_items,
             diagnostic_items,
+            import_candidates,
             lang_items_missing,
             native_libraries,
             foreign_modules,
@@ -635,6 +642,7 @@ impl<'tcx> EncodeContext<'tcx> {
             println!("     lib feature bytes: {}", lib_feature_bytes);
             println!("       lang item bytes: {}", lang_item_bytes);
             println!(" diagnostic item bytes: {}", diagnostic_item_bytes);
+            println!("import candidate bytes: {}", import_candidate_bytes);
             println!("          native bytes: {}", native_lib_bytes);
             println!("         source_map bytes: {}", source_map_bytes);
             println!("            impl bytes: {}", impl_bytes);
@@ -1480,6 +1488,16 @@ impl EncodeContext<'tcx> {
         self.lazy(diagnostic_items.iter().map(|(&name, def_id)| (name, def_id.index)))
     }
 
+    fn encode_import_candidates(&mut self) -> Lazy<[ImportCandidate]> {
+        // Proc macro crates only export their macros, which are found without an index, and
+        // the resolver only collects the index of library crates.
+        if self.is_proc_macro() {
+            return Lazy::empty();
+        }
+        let tcx = self.tcx;
+        self.lazy(tcx.import_candidates.iter())
+    }
+
     fn encode_lang_items(&mut self) -> Lazy<[(DefIndex, usize)]> {
         let tcx = self.tcx;
         let lang_items = tcx.lang_items();
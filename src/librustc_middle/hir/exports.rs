@@ -1,10 +1,10 @@
 use crate::ty;
 
 use rustc_data_structures::fx::FxHashMap;
-use rustc_hir::def::Res;
-use rustc_hir::def_id::LocalDefId;
+use rustc_hir::def::{DefKind, Namespace, Res};
+use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_macros::HashStable;
-use rustc_span::symbol::Ident;
+use rustc_span::symbol::{Ident, Symbol};
 use rustc_span::Span;
 
 use std::fmt::Debug;
@@ -31,3 +31,27 @@ impl<Id> Export<Id> {
         Export { ident: self.ident, res: self.res.map_id(map), span: self.span, vis: self.vis }
     }
 }
+
+/// A public item that other crates can name, recorded in the crate metadata for their import
+/// suggestions so that they don't have to walk the modules of the crate.
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub struct ImportCandidate {
+    /// The shortest path from the crate root to a module naming the item.
+    pub module_path: Vec<Symbol>,
+    /// The namespace the module names the item in.
+    pub ns: Namespace,
+    /// The name of the item in that module.
+    pub ident: Ident,
+    /// The kind of the item.
+    pub kind: DefKind,
+    /// The item, which is defined in another crate if the module re-exports it.
+    pub def_id: DefId,
+    /// The span of the item's binding in the module.
+    pub span: Span,
+}
+
+impl ImportCandidate {
+    pub fn res<Id>(&self) -> Res<Id> {
+        Res::Def(self.kind, self.def_id)
+    }
+}
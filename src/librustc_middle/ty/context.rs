@@ -2,7 +2,7 @@
 
 use crate::arena::Arena;
 use crate::dep_graph::{self, DepConstructor, DepGraph};
-use crate::hir::exports::ExportMap;
+use crate::hir::exports::{ExportMap, ImportCandidate};
 use crate::ich::{NodeIdHashingMode, StableHashingContext};
 use crate::infer::canonical::{Canonical, CanonicalVarInfo, CanonicalVarInfos};
 use crate::lint::{struct_lint_level, LintDiagnosticBuilder, LintSource};
@@ -941,6 +941,8 @@ pub struct GlobalCtxt<'tcx> {
     /// Extern prelude entries. The value is `true` if the entry was introduced
     /// via `extern crate` item and not `--extern` option or compiler built-in.
     pub extern_prelude: FxHashMap<Symbol, bool>,
+    /// The public items of the crate, encoded in its metadata for import suggestions.
+    pub import_candidates: Vec<ImportCandidate>,
 
     // Internal caches for metadata decoding. No need to track deps on this.
     pub ty_rcache: Lock<FxHashMap<ty::CReaderCacheKey, Ty<'tcx>>>,
@@ -1130,6 +1132,7 @@ impl<'tcx> TyCtxt<'tcx> {
             maybe_unused_extern_crates: resolutions.maybe_unused_extern_crates,
             glob_map: resolutions.glob_map,
             extern_prelude: resolutions.extern_prelude,
+            import_candidates: resolutions.import_candidates,
             untracked_crate: krate,
             definitions,
             def_path_hash_to_def_id,
@@ -6,7 +6,7 @@ pub use self::BorrowKind::*;
 pub use self::IntVarValue::*;
 pub use self::Variance::*;
 
-use crate::hir::exports::ExportMap;
+use crate::hir::exports::{ExportMap, ImportCandidate};
 use crate::ich::StableHashingContext;
 use crate::infer::canonical::Canonical;
 use crate::middle::cstore::CrateStoreDyn;
@@ -134,6 +134,8 @@ pub struct ResolverOutputs {
     /// Extern prelude entries. The value is `true` if the entry was introduced
     /// via `extern crate` item and not `--extern` option or compiler built-in.
     pub extern_prelude: FxHashMap<Symbol, bool>,
+    /// The public items of the crate, for import suggestions in the crates depending on it.
+    pub import_candidates: Vec<ImportCandidate>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, HashStable)]
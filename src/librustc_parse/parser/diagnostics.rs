@@ -9,9 +9,10 @@ use rustc_ast::util::parser::AssocOp;
 use rustc_ast_pretty::pprust;
 use rustc_data_structures::fx::FxHashSet;
 use rustc_errors::{pluralize, struct_span_err};
-use rustc_errors::{Applicability, DiagnosticBuilder, Handler, PResult};
+use rustc_errors::{Applicability, DiagnosticBuilder, Handler, PResult, StashKey};
+use rustc_session::parse::NewExpr;
 use rustc_span::source_map::Spanned;
-use rustc_span::symbol::{kw, Ident};
+use rustc_span::symbol::{kw, sym, Ident};
 use rustc_span::{MultiSpan, Span, SpanSnippetError, DUMMY_SP};
 
 use log::{debug, trace};
@@ -1057,6 +1058,43 @@ impl<'a> Parser<'a> {
         sp
     }
 
+    /// Checks whether `path`, just parsed as an expression, is the `new` of a `new Type(<args>)`.
+    pub(super) fn is_new_expr(&self, path: &ast::Path) -> bool {
+        match &path.segments[..] {
+            [segment] if segment.ident.name == sym::new && segment.args.is_none() => {}
+            _ => return false,
+        }
+        let starts_path = match self.token.ident() {
+            Some((ident, is_raw)) => {
+                is_raw || !ident.is_reserved() || ident.is_path_segment_keyword()
+            }
+            None => self.token == token::ModSep,
+        };
+        starts_path && !self.sess.source_map().is_multiline(path.span.between(self.token.span))
+    }
+
+    /// Consumes the type and arguments of `new Type(<args>)`, whose `new` is at `new_span`, and
+    /// recovers it as an erroneous expression.
+    ///
+    /// The error is stashed for the resolver, which knows which constructors the type has, along
+    /// with the parts of the expression it needs in `ParseSess::new_exprs`.
+    pub(super) fn recover_new_expr(&mut self, new_span: Span) -> PResult<'a, P<Expr>> {
+        let path = self.parse_path(PathStyle::Expr)?;
+        let arg_count = if self.check(&token::OpenDelim(token::Paren)) {
+            self.parse_paren_comma_seq(|p| p.parse_expr()).map(|(args, _)| args.len())?
+        } else {
+            0
+        };
+        let span = new_span.to(self.prev_token.span);
+
+        let ty_path = path.segments.iter().map(|segment| segment.ident).collect();
+        let new_expr = NewExpr { new_span, ty_path, ty_span: path.span, arg_count };
+        self.sess.new_exprs.borrow_mut().insert(span, new_expr);
+        self.struct_span_err(new_span, "`new` is not an operator")
+            .stash(new_span, StashKey::NewOperator);
+        Ok(self.mk_expr_err(span))
+    }
+
     /// If encountering `future.await()`, consumes and emits an error.
     pub(super) fn recover_from_await_method_call(&mut self) {
         if self.token == token::OpenDelim(token::Paren)
@@ -15,6 +15,7 @@ use rustc_ast::util::literal::LitError;
 use rustc_ast::util::parser::{prec_let_scrutinee_needs_par, AssocOp, Fixity};
 use rustc_ast_pretty::pprust;
 use rustc_errors::{Applicability, DiagnosticBuilder, PResult};
+use rustc_session::parse::TypeAscription;
 use rustc_span::source_map::{self, Span, Spanned};
 use rustc_span::symbol::{kw, sym, Ident, Symbol};
 use std::mem;
@@ -670,11 +671,27 @@ impl<'a> Parser<'a> {
     fn parse_assoc_op_ascribe(&mut self, lhs: P<Expr>, lhs_span: Span) -> PResult<'a, P<Expr>> {
         let maybe_path = self.could_ascription_be_path(&lhs.kind);
         self.last_type_ascription = Some((self.prev_token.span, maybe_path));
+        let (expr_span, colon_span, ty_span) = (lhs.span, self.prev_token.span, self.token.span);
         let lhs = self.parse_assoc_op_cast(lhs, lhs_span, ExprKind::Type)?;
+        self.record_type_ascription(expr_span, colon_span, ty_span);
         self.sess.gated_spans.gate(sym::type_ascription, lhs.span);
         Ok(lhs)
     }
 
+    /// Records the tokens around the `:` of a type ascription that was just parsed, for name
+    /// resolution to tell whether `::`, `;` or a `let` statement was meant if names in it don't
+    /// resolve.
+    fn record_type_ascription(&self, expr_span: Span, colon_span: Span, ty_span: Span) {
+        let sm = self.sess.source_map();
+        let ascription = TypeAscription {
+            colon_span,
+            type_adjacent: colon_span.hi() == ty_span.lo(),
+            type_on_next_line: sm.is_multiline(colon_span.between(ty_span)),
+            followed_by_eq: self.token == token::Eq,
+        };
+        self.sess.type_ascriptions.borrow_mut().insert(expr_span, ascription);
+    }
+
     /// Parse `& mut? <expr>` or `& raw [ const | mut ] <expr>`.
     fn parse_borrow_expr(&mut self, lo: Span) -> PResult<'a, (Span, ExprKind)> {
         self.expect_and()?;
@@ -1145,6 +1162,9 @@ impl<'a> Parser<'a> {
                 prior_type_ascription: self.last_type_ascription,
             };
             (self.prev_token.span, ExprKind::MacCall(mac))
+        } else if self.is_new_expr(&path) {
+            // `new Type(<args>)`, as in Java or C#.
+            return self.recover_new_expr(lo);
         } else if self.check(&token::OpenDelim(token::Brace)) {
             if let Some(expr) = self.maybe_parse_struct_expr(&path, &attrs) {
                 return expr;
@@ -1972,9 +1992,35 @@ impl<'a> Parser<'a> {
             }
             return Some(expr);
         }
+        self.record_banned_struct_literal(path);
         None
     }
 
+    /// Records a path that isn't parsed as the start of a struct literal because struct literals
+    /// aren't allowed here, when the braces after it could still be its fields, as in `{ field }`
+    /// or `{ field: value }`. Empty braces are left alone, they're as likely the block of
+    /// `if Unit {}`. Name resolution suggests parenthesizing the struct literal if the path names
+    /// a struct.
+    fn record_banned_struct_literal(&self, path: &ast::Path) {
+        let could_be_fields = self.look_ahead(1, |t| t.is_ident())
+            && self.look_ahead(2, |t| {
+                t == &token::Colon || t == &token::CloseDelim(token::Brace)
+            });
+        if !could_be_fields {
+            return;
+        }
+        // Nested delimited groups are looked ahead at as a single token, so the first closing
+        // brace is the one of these braces.
+        let mut dist = 2;
+        let close = loop {
+            match self.look_ahead(dist, |t| (t == &token::CloseDelim(token::Brace), t.span)) {
+                (true, span) => break span,
+                (false, _) => dist += 1,
+            }
+        };
+        self.sess.banned_struct_literals.borrow_mut().insert(path.span, path.span.to(close));
+    }
+
     fn error_struct_lit_not_allowed_here(&self, lo: Span, sp: Span) {
         self.struct_span_err(sp, "struct literals are not allowed here")
             .multipart_suggestion(
@@ -1,5 +1,6 @@
 use Context::*;
 
+use rustc_ast::ast::Label;
 use rustc_errors::{struct_span_err, Applicability};
 use rustc_hir as hir;
 use rustc_hir::def_id::LocalDefId;
@@ -144,15 +145,7 @@ impl<'a, 'hir> Visitor<'hir> for CheckLoopVisitor<'a, 'hir> {
                 match destination.target_id {
                     Ok(loop_id) => {
                         if let Node::Block(block) = self.hir_map.find(loop_id).unwrap() {
-                            struct_span_err!(
-                                self.sess,
-                                e.span,
-                                E0696,
-                                "`continue` pointing to a labeled block"
-                            )
-                            .span_label(e.span, "labeled blocks cannot be `continue`'d")
-                            .span_label(block.span, "labeled block the `continue` points to")
-                            .emit();
+                            self.report_continue_to_labeled_block(e, block, destination.label);
                         }
                     }
                     Err(hir::LoopIdError::UnlabeledCfInWhileCondition) => {
@@ -227,6 +220,74 @@ impl<'a, 'hir> CheckLoopVisitor<'a, 'hir> {
         }
         false
     }
+
+    /// Reports `continue 'label` where `'label` is the label of a block rather than of a loop,
+    /// suggesting the label of the enclosing loop, if any, or leaving the block with `break`.
+    fn report_continue_to_labeled_block(
+        &self,
+        e: &hir::Expr<'_>,
+        block: &hir::Block<'_>,
+        label: Option<Label>,
+    ) {
+        let mut err =
+            struct_span_err!(self.sess, e.span, E0696, "`continue` pointing to a labeled block");
+        err.span_label(e.span, "labeled blocks cannot be `continue`'d");
+        let block_label = match self.hir_map.find(self.hir_map.get_parent_node(block.hir_id)) {
+            Some(Node::Expr(hir::Expr { kind: hir::ExprKind::Block(_, Some(label)), .. })) => {
+                label.ident.span
+            }
+            _ => block.span,
+        };
+        err.span_label(block_label, "labeled block the `continue` points to");
+        err.note("`continue` starts the next iteration of a loop, so it needs the label of a loop");
+
+        let label = match label {
+            Some(label) => label,
+            None => return err.emit(),
+        };
+        // The innermost loop the `continue` is in, within the same body.
+        let enclosing_loop = self
+            .hir_map
+            .parent_iter(e.hir_id)
+            .take_while(|(_, node)| match node {
+                Node::Expr(hir::Expr { kind: hir::ExprKind::Closure(..), .. }) => false,
+                Node::Expr(_) | Node::Block(_) | Node::Stmt(_) | Node::Local(_) | Node::Arm(_) => {
+                    true
+                }
+                _ => false,
+            })
+            .find_map(|(_, node)| match node {
+                Node::Expr(hir::Expr { kind: hir::ExprKind::Loop(_, loop_label, _), .. }) => {
+                    Some(*loop_label)
+                }
+                _ => None,
+            });
+        match enclosing_loop {
+            Some(Some(loop_label)) => {
+                err.span_suggestion(
+                    label.ident.span,
+                    "to go to the next iteration of the enclosing loop, use its label",
+                    loop_label.ident.to_string(),
+                    Applicability::MaybeIncorrect,
+                );
+            }
+            Some(None) => {
+                err.help(
+                    "to go to the next iteration of the enclosing loop, give it a label and use \
+                     that label instead",
+                );
+            }
+            None => {}
+        }
+        err.span_suggestion(
+            e.span,
+            "to leave the labeled block instead, use `break`",
+            format!("break {}", label.ident),
+            Applicability::MaybeIncorrect,
+        );
+        err.emit();
+    }
+
     fn emit_unlabled_cf_in_while_condition(&mut self, span: Span, cf_type: &str) {
         struct_span_err!(
             self.sess,
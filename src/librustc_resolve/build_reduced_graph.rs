@@ -13,10 +13,12 @@ use crate::{CrateLint, Determinacy, PathResult, ResolutionError, VisResolutionEr
 use crate::{
     ExternPreludeEntry, ModuleOrUniformRoot, ParentScope, PerNS, Resolver, ResolverArenas,
 };
-use crate::{Module, ModuleData, ModuleKind, NameBinding, NameBindingKind, Segment, ToNameBinding};
+use crate::{LocalImpl, Module, ModuleData, ModuleKind, NameBinding, NameBindingKind, Segment};
+use crate::ToNameBinding;
 
 use rustc_ast::ast::{self, Block, ForeignItem, ForeignItemKind, Item, ItemKind, NodeId};
 use rustc_ast::ast::{AssocItem, AssocItemKind, MetaItemKind, StmtKind};
+use rustc_ast::ptr::P;
 use rustc_ast::token::{self, Token};
 use rustc_ast::visit::{self, AssocCtxt, Visitor};
 use rustc_ast_lowering::ResolverAstLowering;
@@ -202,7 +204,7 @@ impl<'a> AsMut<Resolver<'a>> for BuildReducedGraphVisitor<'a, '_> {
 impl<'a, 'b> BuildReducedGraphVisitor<'a, 'b> {
     fn resolve_visibility(&mut self, vis: &ast::Visibility) -> ty::Visibility {
         self.resolve_visibility_speculative(vis, false).unwrap_or_else(|err| {
-            self.r.report_vis_error(err);
+            self.r.report_vis_error(err, self.parent_scope.module);
             ty::Visibility::Public
         })
     }
@@ -270,7 +272,11 @@ impl<'a, 'b> BuildReducedGraphVisitor<'a, 'b> {
                                 if self.r.is_accessible_from(vis, parent_scope.module) {
                                     Ok(vis)
                                 } else {
-                                    Err(VisResolutionError::AncestorOnly(path.span))
+                                    Err(VisResolutionError::AncestorOnly(
+                                        path.span,
+                                        path,
+                                        res.def_id(),
+                                    ))
                                 }
                             }
                         } else {
@@ -281,8 +287,8 @@ impl<'a, 'b> BuildReducedGraphVisitor<'a, 'b> {
                     PathResult::NonModule(partial_res) => {
                         expected_found_error(partial_res.base_res())
                     }
-                    PathResult::Failed { span, label, suggestion, .. } => {
-                        Err(VisResolutionError::FailedToResolve(span, label, suggestion))
+                    PathResult::Failed { span, failure, suggestion, .. } => {
+                        Err(VisResolutionError::FailedToResolve(span, failure, suggestion, path))
                     }
                     PathResult::Indeterminate => Err(VisResolutionError::Indeterminate(path.span)),
                 }
@@ -711,6 +717,10 @@ impl<'a, 'b> BuildReducedGraphVisitor<'a, 'b> {
                     no_implicit_prelude: parent.no_implicit_prelude || {
                         attr::contains_name(&item.attrs, sym::no_implicit_prelude)
                     },
+                    on_unresolved: attr::first_attr_value_str_by_name(
+                        &item.attrs,
+                        sym::rustc_on_unresolved,
+                    ),
                     ..ModuleData::new(
                         Some(parent),
                         module_kind,
@@ -745,9 +755,29 @@ impl<'a, 'b> BuildReducedGraphVisitor<'a, 'b> {
             }
 
             // These items live in the type namespace.
-            ItemKind::TyAlias(..) => {
-                let res = Res::Def(DefKind::TyAlias, self.r.local_def_id(item.id).to_def_id());
+            ItemKind::TyAlias(_, _, _, ref ty) => {
+                let def_id = self.r.local_def_id(item.id).to_def_id();
+                let res = Res::Def(DefKind::TyAlias, def_id);
                 self.r.define(parent, ident, TypeNS, (res, vis, sp, expansion));
+
+                // Record how `type Alias = dyn Trait;` would be written as a trait alias.
+                if let Some(ty) = ty {
+                    if let ast::TyKind::TraitObject(ref bounds, ast::TraitObjectSyntax::Dyn) =
+                        ty.kind
+                    {
+                        if !sp.from_expansion() && !bounds.is_empty() {
+                            let keyword = match item.vis.node {
+                                ast::VisibilityKind::Inherited => "trait ",
+                                _ => " trait ",
+                            };
+                            let rewrite = vec![
+                                (item.vis.span.between(ident.span), keyword.to_string()),
+                                (ty.span.until(bounds[0].span()), String::new()),
+                            ];
+                            self.r.trait_object_aliases.insert(def_id, rewrite);
+                        }
+                    }
+                }
             }
 
             ItemKind::Enum(_, _) => {
@@ -838,13 +868,54 @@ impl<'a, 'b> BuildReducedGraphVisitor<'a, 'b> {
                 self.parent_scope.module = module;
             }
 
+            ItemKind::Impl { ref generics, ref of_trait, ref self_ty, ref items, .. } => {
+                self.record_local_impl(generics, of_trait.as_ref(), self_ty, items);
+            }
+
             // These items do not add names to modules.
-            ItemKind::Impl { .. } | ItemKind::ForeignMod(..) | ItemKind::GlobalAsm(..) => {}
+            ItemKind::ForeignMod(..) | ItemKind::GlobalAsm(..) => {}
 
             ItemKind::MacroDef(..) | ItemKind::MacCall(_) => unreachable!(),
         }
     }
 
+    /// Records an `impl` for a type named by a path, if it has methods taking `self`, to suggest
+    /// method call syntax where they're called like free functions, even before the `impl`.
+    fn record_local_impl(
+        &mut self,
+        generics: &ast::Generics,
+        of_trait: Option<&ast::TraitRef>,
+        self_ty: &ast::Ty,
+        items: &[P<AssocItem>],
+    ) {
+        let self_ty = match self_ty.kind {
+            ast::TyKind::Path(None, ref path) => path,
+            _ => return,
+        };
+        // The type parameters of the `impl` can't be resolved from its module.
+        if let [segment] = &self_ty.segments[..] {
+            if generics.params.iter().any(|param| param.ident == segment.ident) {
+                return;
+            }
+        }
+        let methods: Vec<_> = items
+            .iter()
+            .filter_map(|item| match item.kind {
+                AssocItemKind::Fn(_, ref sig, ..) if sig.decl.has_self() => Some(item.ident.name),
+                _ => None,
+            })
+            .collect();
+        if methods.is_empty() {
+            return;
+        }
+        self.r.local_impls.push(LocalImpl {
+            module: self.parent_scope.module,
+            self_ty: self_ty.clone(),
+            of_trait: of_trait.map(|trait_ref| trait_ref.path.clone()),
+            methods,
+        });
+    }
+
     /// Constructs the reduced graph for one foreign item.
     fn build_reduced_graph_for_foreign_item(&mut self, item: &ForeignItem) {
         let (res, ns) = match item.kind {
@@ -1254,6 +1325,8 @@ impl<'a, 'b> Visitor<'b> for BuildReducedGraphVisitor<'a, 'b> {
         };
         let orig_current_module = self.parent_scope.module;
         let orig_current_macro_rules_scope = self.parent_scope.macro_rules;
+        let def_id = self.r.local_def_id(item.id);
+        self.r.item_macro_rules_scopes.insert(def_id, self.parent_scope.macro_rules);
         self.build_reduced_graph_for_item(item);
         visit::walk_item(self, item);
         self.parent_scope.module = orig_current_module;
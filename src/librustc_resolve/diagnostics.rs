@@ -1,32 +1,44 @@
-use std::cmp::Reverse;
+use std::cmp::{self, Reverse};
+use std::collections::VecDeque;
+use std::iter;
+use std::mem;
+use std::num::NonZeroU32;
 use std::ptr;
 
 use log::debug;
 use rustc_ast::ast::{self, Path};
-use rustc_ast::util::lev_distance::find_best_match_for_name;
+use rustc_ast::util::lev_distance::{find_best_match_for_name, lev_distance, sort_by_words};
 use rustc_ast_pretty::pprust;
+use rustc_attr::StabilityLevel;
 use rustc_data_structures::fx::FxHashSet;
-use rustc_errors::{struct_span_err, Applicability, DiagnosticBuilder};
+use rustc_errors::{pluralize, struct_span_err, Applicability, DiagnosticBuilder};
 use rustc_feature::BUILTIN_ATTRIBUTES;
 use rustc_hir::def::Namespace::{self, *};
 use rustc_hir::def::{self, CtorKind, CtorOf, DefKind, NonMacroAttrKind};
-use rustc_hir::def_id::{DefId, CRATE_DEF_INDEX, LOCAL_CRATE};
+use rustc_hir::def_id::{CrateNum, DefId, CRATE_DEF_INDEX, LOCAL_CRATE};
 use rustc_middle::bug;
+use rustc_middle::hir::exports::ImportCandidate;
 use rustc_middle::ty::{self, DefIdTree};
+use rustc_session::config::{nightly_options, CrateType};
 use rustc_session::Session;
 use rustc_span::hygiene::MacroKind;
 use rustc_span::source_map::SourceMap;
-use rustc_span::symbol::{kw, Ident, Symbol};
+use rustc_span::symbol::{kw, sym, Ident, Symbol};
 use rustc_span::{BytePos, MultiSpan, Span};
 
+use crate::heuristics::{find_best_typo, is_morphological_variant, typo_applicability};
+use crate::heuristics::{FixMessage, SuggestedFix};
 use crate::imports::{Import, ImportKind, ImportResolver};
-use crate::path_names_to_string;
+use crate::late::NestedFnItem;
+use crate::{names_to_string, path_names_to_string, UsePlacementFinder};
 use crate::{AmbiguityError, AmbiguityErrorMisc, AmbiguityKind};
 use crate::{
-    BindingError, CrateLint, HasGenericParams, MacroRulesScope, Module, ModuleOrUniformRoot,
+    BindingError, CrateLint, HasGenericParams, MacroRulesScope, Module, ModuleKind,
+    ModuleOrUniformRoot,
 };
-use crate::{NameBinding, NameBindingKind, PrivacyError, VisResolutionError};
+use crate::{ImplMethod, NameBinding, NameBindingKind, NewOperatorError, PathFailure};
 use crate::{ParentScope, PathResult, ResolutionError, Resolver, Scope, ScopeSet, Segment};
+use crate::{PrivacyError, VisResolutionError};
 
 type Res = def::Res<ast::NodeId>;
 
@@ -37,23 +49,123 @@ crate type Suggestion = (Vec<(Span, String)>, String, Applicability);
 /// similarly named label and whether or not it is reachable.
 crate type LabelSuggestion = (Ident, bool);
 
-crate struct TypoSuggestion {
+/// The most typo candidates collected from a single module, keeping the closest ones. Only names
+/// close enough to the one being looked up are collected, so this is only reached by modules with
+/// huge glob imports.
+crate const MAX_TYPO_CANDIDATES_PER_SCOPE: usize = 32;
+
+/// Once this many typo candidates have been collected, outer scopes are no longer searched.
+crate const MAX_TYPO_CANDIDATES: usize = 64;
+
+/// A similarly named item suggested in case of resolution failure.
+pub struct TypoSuggestion {
     pub candidate: Symbol,
     pub res: Res,
+    /// How certain the rename is, see `heuristics::typo_applicability`.
+    pub applicability: Applicability,
 }
 
 impl TypoSuggestion {
     crate fn from_res(candidate: Symbol, res: Res) -> TypoSuggestion {
-        TypoSuggestion { candidate, res }
+        TypoSuggestion { candidate, res, applicability: Applicability::MaybeIncorrect }
     }
 }
 
 /// A free importable items suggested in case of resolution failure.
-crate struct ImportSuggestion {
+pub struct ImportSuggestion {
+    /// The suggested item; for constructors, the struct or variant they belong to.
     pub did: Option<DefId>,
     pub descr: &'static str,
     pub path: Path,
+    /// Whether the item can be named from the module the lookup was made from.
     pub accessible: bool,
+    /// The feature gating the item along with its tracking issue, if it is unstable and this
+    /// crate doesn't enable the feature.
+    pub unstable: Option<(Symbol, Option<NonZeroU32>)>,
+}
+
+impl PathFailure {
+    /// The segment of the path that failed to resolve, if the failure is about a single one.
+    pub fn segment(&self) -> Option<Ident> {
+        match *self {
+            PathFailure::NotAModule { segment, .. }
+            | PathFailure::MissingCrate { segment }
+            | PathFailure::UndeclaredTypeOrModule { segment, .. }
+            | PathFailure::NotFoundIn { segment, .. }
+            | PathFailure::FoundInOtherNamespace { segment, .. } => Some(segment),
+            PathFailure::TooManySupers
+            | PathFailure::MisplacedKeyword { .. }
+            | PathFailure::UnresolvedImport
+            | PathFailure::TypeRelative
+            | PathFailure::PartiallyResolvedMacro(_) => None,
+        }
+    }
+
+    /// The namespace the failing segment was looked up in, if it is known.
+    pub fn namespace(&self) -> Option<Namespace> {
+        match *self {
+            PathFailure::NotAModule { .. } | PathFailure::MissingCrate { .. } => Some(TypeNS),
+            PathFailure::UndeclaredTypeOrModule { ns, .. } | PathFailure::NotFoundIn { ns, .. } => {
+                Some(ns)
+            }
+            PathFailure::FoundInOtherNamespace { expected, .. } => Some(expected),
+            PathFailure::PartiallyResolvedMacro(_) => Some(MacroNS),
+            PathFailure::TooManySupers
+            | PathFailure::MisplacedKeyword { .. }
+            | PathFailure::UnresolvedImport
+            | PathFailure::TypeRelative => None,
+        }
+    }
+
+    crate fn message(&self, sess: &Session) -> String {
+        match *self {
+            PathFailure::TooManySupers => msg!(sess, "too-many-supers"),
+            PathFailure::MisplacedKeyword { keyword, global } => {
+                let keyword = if keyword == kw::PathRoot {
+                    msg!(sess, "crate-root-keyword")
+                } else {
+                    format!("`{}`", keyword)
+                };
+                if global {
+                    msg!(sess, "global-path-keyword", keyword = keyword)
+                } else {
+                    msg!(sess, "misplaced-path-keyword", keyword = keyword)
+                }
+            }
+            PathFailure::NotAModule { segment, res } => msg!(
+                sess,
+                "segment-not-a-module",
+                segment = segment,
+                article = res.article(),
+                descr = res.descr(),
+            ),
+            PathFailure::UnresolvedImport => msg!(sess, "unresolved-import"),
+            PathFailure::MissingCrate { segment } => {
+                msg!(sess, "maybe-missing-crate", segment = segment)
+            }
+            PathFailure::UndeclaredTypeOrModule { segment, .. } => {
+                msg!(sess, "undeclared-type-or-module", segment = segment)
+            }
+            PathFailure::NotFoundIn { segment, parent, .. } => {
+                msg!(sess, "not-found-in-parent", segment = segment, parent = parent)
+            }
+            PathFailure::FoundInOtherNamespace { segment, parent, expected, found } => msg!(
+                sess,
+                "found-in-other-namespace",
+                expected = expected.descr(),
+                found = found,
+                segment = segment,
+                parent = parent,
+            ),
+            PathFailure::TypeRelative => msg!(sess, "type-relative-path"),
+            PathFailure::PartiallyResolvedMacro(kind) => msg!(
+                sess,
+                "partially-resolved-macro",
+                article = kind.article(),
+                descr = kind.descr(),
+            ),
+        }
+    }
 }
 
 /// Adjust the impl span so that just the `impl` keyword is taken by removing
@@ -68,21 +180,63 @@ fn reduce_impl_span_to_impl_keyword(sm: &SourceMap, impl_span: Span) -> Span {
     sm.span_until_whitespace(impl_span)
 }
 
+/// Whether `find_best_typo` could pick `candidate` as a typo of `lookup`, so that names
+/// which would be discarded anyway need not be collected.
+crate fn is_typo_candidate(lookup: &str, candidate: Symbol) -> bool {
+    typo_distance(lookup, candidate).is_some()
+}
+
+/// How far `candidate` is from `lookup`, or `None` if it is too far to be picked as a typo.
+/// Names made of the same words in another order, and then inflected words, are matched
+/// regardless of their distance, but only after all the names within it.
+fn typo_distance(lookup: &str, candidate: Symbol) -> Option<usize> {
+    let candidate = candidate.as_str();
+    let max_dist = cmp::max(lookup.len(), 3) / 3;
+    let dist = lev_distance(lookup, &candidate);
+    if dist <= max_dist {
+        Some(dist)
+    } else if sort_by_words(&candidate) == sort_by_words(lookup) {
+        Some(max_dist + 1)
+    } else if is_morphological_variant(lookup, &candidate) {
+        Some(max_dist + 2)
+    } else {
+        None
+    }
+}
+
+/// Sorts typo candidates by name, to make sure error reporting is deterministic. The interned
+/// strings are compared in place rather than cached as keys, and the sort being stable keeps the
+/// candidates from the closest scopes first among those with the same name.
+crate fn sort_typo_candidates(candidates: &mut [TypoSuggestion]) {
+    candidates.sort_by(|a, b| a.candidate.as_str().cmp(&b.candidate.as_str()));
+}
+
 impl<'a> Resolver<'a> {
+    /// Collects the names of `module` that could be typos of `lookup` into `names`. Returns whether
+    /// some were left out, beyond the `MAX_TYPO_CANDIDATES_PER_SCOPE` closest ones.
     crate fn add_module_candidates(
         &mut self,
         module: Module<'a>,
+        lookup: Symbol,
         names: &mut Vec<TypoSuggestion>,
         filter_fn: &impl Fn(Res) -> bool,
-    ) {
+    ) -> bool {
+        let lookup = lookup.as_str();
+        let start = names.len();
         for (key, resolution) in self.resolutions(module).borrow().iter() {
             if let Some(binding) = resolution.borrow().binding {
                 let res = binding.res();
-                if filter_fn(res) {
+                if filter_fn(res) && is_typo_candidate(&lookup, key.ident.name) {
                     names.push(TypoSuggestion::from_res(key.ident.name, res));
                 }
             }
         }
+        if names.len() - start > MAX_TYPO_CANDIDATES_PER_SCOPE {
+            names[start..].sort_by_cached_key(|name| typo_distance(&lookup, name.candidate));
+            names.truncate(start + MAX_TYPO_CANDIDATES_PER_SCOPE);
+            return true;
+        }
+        false
     }
 
     /// Combines an error with provided span and emits it.
@@ -99,7 +253,11 @@ impl<'a> Resolver<'a> {
         resolution_error: ResolutionError<'_>,
     ) -> DiagnosticBuilder<'_> {
         match resolution_error {
-            ResolutionError::GenericParamsFromOuterFunction(outer_res, has_generic_params) => {
+            ResolutionError::GenericParamsFromOuterFunction(
+                outer_res,
+                has_generic_params,
+                bounds,
+            ) => {
                 let mut err = struct_span_err!(
                     self.session,
                     span,
@@ -148,11 +306,26 @@ impl<'a> Resolver<'a> {
                     }
                 }
 
-                if has_generic_params == HasGenericParams::Yes {
-                    // Try to retrieve the span of the function signature and generate a new
-                    // message with a local type or const parameter.
+                err.note(
+                    "nested items are independent from their parent item for everything except \
+                     for privacy and name resolution",
+                );
+
+                if let HasGenericParams::Yes(generics_span) = has_generic_params {
+                    // Declare the parameter on the nested item, or try to retrieve the span of
+                    // the function signature and generate a new message with a local type or
+                    // const parameter.
                     let sugg_msg = "try using a local generic parameter instead";
-                    if let Some((sugg_span, snippet)) = sm.generate_local_type_param_snippet(span) {
+                    let part = self.outer_generic_param_to_local(outer_res, generics_span, &bounds);
+                    if let Some(part) = part {
+                        err.multipart_suggestion(
+                            "try introducing a local generic parameter here",
+                            vec![part],
+                            Applicability::MaybeIncorrect,
+                        );
+                    } else if let Some((sugg_span, snippet)) =
+                        sm.generate_local_type_param_snippet(span)
+                    {
                         // Suggest the modification to the user
                         err.span_suggestion(
                             sugg_span,
@@ -275,7 +448,7 @@ impl<'a> Resolver<'a> {
                 err.span_label(span, "used as parameter more than once");
                 err
             }
-            ResolutionError::IdentifierBoundMoreThanOnceInSamePattern(identifier) => {
+            ResolutionError::IdentifierBoundMoreThanOnceInSamePattern(identifier, rename) => {
                 let mut err = struct_span_err!(
                     self.session,
                     span,
@@ -284,9 +457,22 @@ impl<'a> Resolver<'a> {
                     identifier
                 );
                 err.span_label(span, "used in a pattern more than once");
+                if let Some((field_span, rename)) = rename {
+                    err.span_suggestion(
+                        field_span,
+                        "bind the field to another name",
+                        rename,
+                        Applicability::MaybeIncorrect,
+                    );
+                }
                 err
             }
-            ResolutionError::UndeclaredLabel { name, suggestion } => {
+            ResolutionError::UndeclaredLabel {
+                name,
+                suggestion,
+                enclosing_loop,
+                enclosing_block,
+            } => {
                 let mut err = struct_span_err!(
                     self.session,
                     span,
@@ -315,8 +501,32 @@ impl<'a> Resolver<'a> {
                             "a label with a similar name exists but is unreachable",
                         );
                     }
-                    // No similarly-named labels exist.
-                    None => (),
+                    // No similarly-named labels exist, but the label could be introduced on the
+                    // enclosing loop or block.
+                    None => {
+                        if let Some(loop_span) = enclosing_loop {
+                            err.multipart_suggestion(
+                                "consider labeling the enclosing loop",
+                                vec![(loop_span.shrink_to_lo(), format!("{}: ", name))],
+                                Applicability::MaybeIncorrect,
+                            );
+                        } else if let Some(block_span) = enclosing_block {
+                            if nightly_options::is_nightly_build()
+                                && !self.session.features_untracked().label_break_value
+                            {
+                                err.help(
+                                    "add `#![feature(label_break_value)]` to the crate attributes \
+                                     to enable labeled blocks",
+                                );
+                            }
+                            err.multipart_suggestion(
+                                "consider labeling the enclosing block to break out of it with a \
+                                 value",
+                                vec![(block_span.shrink_to_lo(), format!("{}: ", name))],
+                                Applicability::MaybeIncorrect,
+                            );
+                        }
+                    }
                 }
 
                 err
@@ -376,10 +586,12 @@ impl<'a> Resolver<'a> {
                 err.span_label(span, "can only appear in an import list with a non-empty prefix");
                 err
             }
-            ResolutionError::FailedToResolve { label, suggestion } => {
+            ResolutionError::FailedToResolve { failure, suggestion } => {
+                self.path_failures.borrow_mut().push((span, failure.clone()));
+                let failure = failure.message(self.session);
                 let mut err =
-                    struct_span_err!(self.session, span, E0433, "failed to resolve: {}", &label);
-                err.span_label(span, label);
+                    struct_span_err!(self.session, span, E0433, "failed to resolve: {}", failure);
+                err.span_label(span, failure);
 
                 if let Some((suggestions, msg, applicability)) = suggestion {
                     err.multipart_suggestion(&msg, suggestions, applicability);
@@ -387,7 +599,7 @@ impl<'a> Resolver<'a> {
 
                 err
             }
-            ResolutionError::CannotCaptureDynamicEnvironmentInFnItem => {
+            ResolutionError::CannotCaptureDynamicEnvironmentInFnItem(nested_fn) => {
                 let mut err = struct_span_err!(
                     self.session,
                     span,
@@ -395,10 +607,30 @@ impl<'a> Resolver<'a> {
                     "{}",
                     "can't capture dynamic environment in a fn item"
                 );
-                err.help("use the `|| { ... }` closure form instead");
+                let suggestion = nested_fn.and_then(|nested_fn| self.fn_item_to_closure(nested_fn));
+                match (nested_fn, suggestion) {
+                    (Some(nested_fn), Some(suggestion)) => {
+                        err.note(&format!(
+                            "alternatively, pass the value to `{}` as an explicit parameter",
+                            nested_fn.ident
+                        ));
+                        err.multipart_suggestion(
+                            "use a closure to capture the dynamic environment",
+                            suggestion,
+                            Applicability::MaybeIncorrect,
+                        );
+                    }
+                    _ => {
+                        err.help("use the `|| { ... }` closure form instead");
+                    }
+                }
                 err
             }
-            ResolutionError::AttemptToUseNonConstantValueInConstant => {
+            ResolutionError::AttemptToUseNonConstantValueInConstant {
+                ident,
+                item_kind,
+                candidate,
+            } => {
                 let mut err = struct_span_err!(
                     self.session,
                     span,
@@ -406,6 +638,38 @@ impl<'a> Resolver<'a> {
                     "attempt to use a non-constant value in a constant"
                 );
                 err.span_label(span, "non-constant value");
+                if let Some(item_kind) = item_kind {
+                    err.note(&format!(
+                        "`{}` initializers are evaluated at compile time and can't refer to local \
+                         variables of the enclosing function",
+                        item_kind.as_str(),
+                    ));
+                }
+                if let Some(candidate) = candidate {
+                    let sm = self.session.source_map();
+                    let ty = match candidate.ty_span {
+                        Some(ty_span) => sm.span_to_snippet(ty_span).ok(),
+                        None => candidate.lit_ty.map(|ty| ty.to_string()),
+                    };
+                    // The type of a constant can't be inferred.
+                    let (ty, applicability) = match ty {
+                        Some(ty) => (ty, Applicability::MaybeIncorrect),
+                        None => ("/* Type */".to_string(), Applicability::HasPlaceholders),
+                    };
+                    // The constant keeps the name of the local, whose other uses aren't known.
+                    err.span_suggestion_verbose(
+                        candidate.let_span,
+                        "consider using `const` instead of `let`",
+                        format!("const {}: {} = ", candidate.ident, ty),
+                        applicability,
+                    );
+                } else if item_kind.is_some() && ident.name != kw::SelfLower {
+                    err.help(&format!(
+                        "if the value of `{}` is known at compile time, consider declaring it as a \
+                         `const` or `static` item instead",
+                        ident,
+                    ));
+                }
                 err
             }
             ResolutionError::BindingShadowsSomethingUnacceptable(what_binding, name, binding) => {
@@ -452,7 +716,13 @@ impl<'a> Resolver<'a> {
                 err.span_label(span, "`Self` in type parameter default".to_string());
                 err
             }
-            ResolutionError::UnreachableLabel { name, definition_span, suggestion } => {
+            ResolutionError::UnreachableLabel {
+                name,
+                definition_span,
+                suggestion,
+                boundary,
+                control_flow_span,
+            } => {
                 let mut err = struct_span_err!(
                     self.session,
                     span,
@@ -489,12 +759,38 @@ impl<'a> Resolver<'a> {
                     None => (),
                 }
 
+                // Only closures and async blocks stand between the label and its use, so leaving
+                // the closure could take the place of the `break` or `continue`.
+                if let Some((boundary_span, boundary_kind)) = boundary {
+                    err.span_label(
+                        boundary_span,
+                        format!("the label can't be reached from inside this {}", boundary_kind),
+                    );
+                    let reachable_suggested = matches!(suggestion, Some((_, true)));
+                    if !reachable_suggested && !control_flow_span.from_expansion() {
+                        let msg = format!(
+                            "consider returning a value from the {} instead",
+                            boundary_kind
+                        );
+                        err.span_suggestion_verbose(
+                            control_flow_span,
+                            &msg,
+                            "return".to_string(),
+                            Applicability::MaybeIncorrect,
+                        );
+                    }
+                }
+
                 err
             }
         }
     }
 
-    crate fn report_vis_error(&self, vis_resolution_error: VisResolutionError<'_>) {
+    crate fn report_vis_error(
+        &self,
+        vis_resolution_error: VisResolutionError<'_>,
+        module: Module<'a>,
+    ) {
         match vis_resolution_error {
             VisResolutionError::Relative2018(span, path) => {
                 let mut err = self.session.struct_span_err(
@@ -509,14 +805,23 @@ impl<'a> Resolver<'a> {
                 );
                 err
             }
-            VisResolutionError::AncestorOnly(span) => struct_span_err!(
-                self.session,
-                span,
-                E0742,
-                "visibilities can only be restricted to ancestor modules"
-            ),
-            VisResolutionError::FailedToResolve(span, label, suggestion) => {
-                self.into_struct_error(span, ResolutionError::FailedToResolve { label, suggestion })
+            VisResolutionError::AncestorOnly(span, path, target) => {
+                let mut err = struct_span_err!(
+                    self.session,
+                    span,
+                    E0742,
+                    "visibilities can only be restricted to ancestor modules"
+                );
+                self.suggest_ancestor_modules(&mut err, path, Some(target), module);
+                err
+            }
+            VisResolutionError::FailedToResolve(span, failure, suggestion, path) => {
+                let mut err = self.into_struct_error(
+                    span,
+                    ResolutionError::FailedToResolve { failure, suggestion },
+                );
+                self.suggest_ancestor_modules(&mut err, path, None, module);
+                err
             }
             VisResolutionError::ExpectedFound(span, path_str, res) => {
                 let mut err = struct_span_err!(
@@ -543,15 +848,153 @@ impl<'a> Resolver<'a> {
         .emit()
     }
 
+    /// Lists the modules that a visibility written in `module` can be restricted to, and suggests
+    /// one of them in place of `path`. If `path` resolved to the module `target`, that's the
+    /// deepest ancestor containing it, otherwise the one whose path is closest to `path`.
+    fn suggest_ancestor_modules(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        path: &ast::Path,
+        target: Option<DefId>,
+        module: Module<'a>,
+    ) {
+        // Modules nested in a block can't be named from the crate root, so every block drops the
+        // ancestors found inside it, and only those outside of the outermost block are listed.
+        let mut modules = Vec::new();
+        let mut module = Some(module);
+        while let Some(m) = module {
+            match m.kind {
+                ModuleKind::Block(..) => modules.clear(),
+                ModuleKind::Def(DefKind::Mod, def_id, name) => modules.push((def_id, name)),
+                ModuleKind::Def(..) => {}
+            }
+            module = m.parent;
+        }
+        let names = modules.iter().rev().skip(1).map(|&(_, name)| name).collect::<Vec<_>>();
+
+        // Innermost first, without the leading `crate` which all of them share.
+        let ancestors = (0..=names.len()).rev().map(|len| &names[..len]).collect::<Vec<_>>();
+        let ancestor_path = |names: &[Symbol]| {
+            names_to_string(&iter::once(kw::Crate).chain(names.iter().copied()).collect::<Vec<_>>())
+        };
+
+        if let [ancestor] = &ancestors[..] {
+            err.note(&format!(
+                "visibilities here can only be restricted to `{}`",
+                ancestor_path(ancestor)
+            ));
+        } else {
+            let (last, rest) = ancestors.split_last().unwrap();
+            err.note(&format!(
+                "visibilities here can be restricted to {} or `{}`",
+                rest.iter()
+                    .map(|names| format!("`{}`", ancestor_path(names)))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                ancestor_path(last),
+            ));
+        }
+
+        let containing = target.and_then(|target| {
+            modules.iter().position(|&(def_id, _)| self.is_descendant_of(target, def_id))
+        });
+        if let Some(index) = containing {
+            err.span_suggestion_verbose(
+                path.span,
+                &format!(
+                    "try restricting it to the closest ancestor module containing `{}`",
+                    pprust::path_to_string(path),
+                ),
+                ancestor_path(ancestors[index]),
+                Applicability::MaybeIncorrect,
+            );
+            return;
+        }
+
+        let written = names_to_string(
+            &path
+                .segments
+                .iter()
+                .map(|seg| seg.ident.name)
+                .filter(|name| *name != kw::Crate)
+                .collect::<Vec<_>>(),
+        );
+        let closest = ancestors
+            .iter()
+            .min_by_key(|names| lev_distance(&written, &names_to_string(names)))
+            .unwrap();
+        err.span_suggestion_verbose(
+            path.span,
+            "try restricting it to an ancestor module",
+            ancestor_path(closest),
+            Applicability::MaybeIncorrect,
+        );
+    }
+
+    /// Rewrites `fn name(params) -> Ret { .. }` into `let name = |params| -> Ret { .. };`.
+    fn fn_item_to_closure(&self, nested_fn: NestedFnItem) -> Option<Vec<(Span, String)>> {
+        let sm = self.session.source_map();
+        let params = match nested_fn.params_span {
+            Some(span) => sm.span_to_snippet(span).ok()?,
+            None => String::new(),
+        };
+        let ret_ty = match nested_fn.ret_ty_span {
+            Some(span) => format!(" -> {}", sm.span_to_snippet(span).ok()?),
+            None => String::new(),
+        };
+        Some(vec![
+            (nested_fn.header_span, format!("let {} = |{}|{} ", nested_fn.ident, params, ret_ty)),
+            (nested_fn.body_span.shrink_to_hi(), ";".to_string()),
+        ])
+    }
+
+    /// Declares the outer generic parameter `outer_res`, along with its `bounds`, in the generics
+    /// of a nested item, given the span of those generics.
+    fn outer_generic_param_to_local(
+        &self,
+        outer_res: Res,
+        generics_span: Span,
+        bounds: &[Span],
+    ) -> Option<(Span, String)> {
+        let (def_kind, def_id) = match outer_res {
+            Res::Def(def_kind, def_id) => (def_kind, def_id.as_local()?),
+            _ => return None,
+        };
+        if generics_span.is_dummy() || generics_span.from_expansion() {
+            return None;
+        }
+        let sm = self.session.source_map();
+        let name = sm.span_to_snippet(self.def_id_to_span[def_id]).ok()?;
+        let bounds: Vec<String> =
+            bounds.iter().map(|&span| sm.span_to_snippet(span).ok()).collect::<Option<_>>()?;
+        let param = match def_kind {
+            DefKind::ConstParam => format!("const {}: {}", name, bounds.join("")),
+            _ if bounds.is_empty() => name,
+            _ => format!("{}: {}", name, bounds.join(" + ")),
+        };
+        if generics_span.lo() == generics_span.hi() {
+            return Some((generics_span, format!("<{}>", param)));
+        }
+        // Append the parameter to the existing ones, right before the closing `>`.
+        let snippet = sm.span_to_snippet(generics_span).ok()?;
+        if snippet.ends_with(",>") || snippet == "<>" {
+            return None;
+        }
+        let close = generics_span.with_lo(generics_span.hi() - BytePos(1)).shrink_to_lo();
+        Some((close, format!(", {}", param)))
+    }
+
     /// Lookup typo candidate in scope for a macro or import.
-    fn early_lookup_typo_candidate(
+    crate fn early_lookup_typo_candidate(
         &mut self,
         scope_set: ScopeSet,
         parent_scope: &ParentScope<'a>,
         ident: Ident,
         filter_fn: &impl Fn(Res) -> bool,
     ) -> Option<TypoSuggestion> {
+        let _prof_timer = self.session.prof.generic_activity("resolve_early_lookup_typo_candidate");
         let mut suggestions = Vec::new();
+        let mut truncated = false;
         self.visit_scopes(scope_set, parent_scope, ident, |this, scope, use_prelude, _| {
             match scope {
                 Scope::DeriveHelpers(expn_id) => {
@@ -599,10 +1042,16 @@ impl<'a> Resolver<'a> {
                 Scope::CrateRoot => {
                     let root_ident = Ident::new(kw::PathRoot, ident.span);
                     let root_module = this.resolve_crate_root(root_ident);
-                    this.add_module_candidates(root_module, &mut suggestions, filter_fn);
+                    truncated |= this.add_module_candidates(
+                        root_module,
+                        ident.name,
+                        &mut suggestions,
+                        filter_fn,
+                    );
                 }
                 Scope::Module(module) => {
-                    this.add_module_candidates(module, &mut suggestions, filter_fn);
+                    truncated |=
+                        this.add_module_candidates(module, ident.name, &mut suggestions, filter_fn);
                 }
                 Scope::RegisteredAttrs => {
                     let res = Res::NonMacroAttr(NonMacroAttrKind::Registered);
@@ -649,7 +1098,12 @@ impl<'a> Resolver<'a> {
                 Scope::StdLibPrelude => {
                     if let Some(prelude) = this.prelude {
                         let mut tmp_suggestions = Vec::new();
-                        this.add_module_candidates(prelude, &mut tmp_suggestions, filter_fn);
+                        truncated |= this.add_module_candidates(
+                            prelude,
+                            ident.name,
+                            &mut tmp_suggestions,
+                            filter_fn,
+                        );
                         suggestions.extend(
                             tmp_suggestions
                                 .into_iter()
@@ -669,31 +1123,205 @@ impl<'a> Resolver<'a> {
             None::<()>
         });
 
-        // Make sure error reporting is deterministic.
-        suggestions.sort_by_cached_key(|suggestion| suggestion.candidate.as_str());
+        sort_typo_candidates(&mut suggestions);
 
-        match find_best_match_for_name(
-            suggestions.iter().map(|suggestion| &suggestion.candidate),
-            &ident.as_str(),
-            None,
-        ) {
+        let lookup = ident.as_str();
+        let candidates = || suggestions.iter().map(|suggestion| &suggestion.candidate);
+        match find_best_typo(candidates(), &lookup) {
             Some(found) if found != ident.name => {
-                suggestions.into_iter().find(|suggestion| suggestion.candidate == found)
+                let applicability = typo_applicability(candidates(), &lookup, found, truncated);
+                let suggestion = suggestions.into_iter().find(|s| s.candidate == found)?;
+                Some(TypoSuggestion { applicability, ..suggestion })
             }
             _ => None,
         }
     }
 
-    fn lookup_import_candidates_from_module<FilterFn>(
+    /// Returns the position right after the item at `item_span`, at the start of the following
+    /// line, along with the indentation to give an item inserted there.
+    crate fn insertion_point_after_item(&self, item_span: Span) -> (Span, String) {
+        let sm = self.session.source_map();
+        let lo = sm.lookup_char_pos(item_span.lo());
+        let indent: String = lo
+            .file
+            .get_line(lo.line - 1)
+            .map_or(String::new(), |line| line.chars().take_while(|c| c.is_whitespace()).collect());
+        let hi = sm.lookup_char_pos(item_span.hi());
+        match hi.file.lines.get(hi.line) {
+            Some(&next_line) => (item_span.with_lo(next_line).with_hi(next_line), indent),
+            // The item ends the file, start a new line after it.
+            None => (item_span.shrink_to_hi(), format!("\n{}", indent)),
+        }
+    }
+
+    /// Whether the paths of `candidates` resolve from the module `def_id`, where their `use` items
+    /// are placed. Paths written from the crate root don't always, e.g. when naming a crate that
+    /// isn't in the extern prelude.
+    crate fn import_candidates_resolve(
+        &mut self,
+        def_id: DefId,
+        candidates: &[ImportSuggestion],
+    ) -> bool {
+        let parent_scope = &ParentScope::module(self.get_module(def_id));
+        candidates.iter().all(|candidate| {
+            let path = Segment::from_path(&candidate.path);
+            let span = candidate.path.span;
+            [TypeNS, ValueNS, MacroNS].iter().any(|&ns| {
+                self.speculative_resolve_path(&path, ns, parent_scope, span, None, false).is_some()
+            })
+        })
+    }
+
+    /// Returns where to insert a new `use` item in the module `def_id` along with the text to
+    /// precede it with, and whether it goes after an existing `use` item.
+    crate fn use_placement(
+        &self,
+        krate: &ast::Crate,
+        def_id: DefId,
+    ) -> (Option<(Span, String)>, bool) {
+        let (span, found_use) = match def_id.as_local() {
+            Some(def_id) => UsePlacementFinder::check(krate, self.def_id_to_node_id[def_id]),
+            None => (None, false),
+        };
+        // New `use` items go on their own line after the last existing one, or right before
+        // the first item of the module.
+        let use_placement = span.map(|span| {
+            if found_use { self.insertion_point_after_item(span) } else { (span, String::new()) }
+        });
+        (use_placement, found_use)
+    }
+
+    /// Collects the public items of the crate, each with the shortest path to a module naming it,
+    /// for the import suggestions of the crates depending on this one. Only libraries can be
+    /// depended on, and proc macro crates only export their macros.
+    crate fn public_import_candidates(&self) -> Vec<ImportCandidate> {
+        let mut candidates = Vec::new();
+        let crate_types = self.session.crate_types();
+        if !crate_types.iter().any(|ty| matches!(ty, CrateType::Rlib | CrateType::Dylib))
+            || crate_types.contains(&CrateType::ProcMacro)
+        {
+            return candidates;
+        }
+        let mut seen_items = FxHashSet::default();
+        let mut seen_modules = FxHashSet::default();
+        // As when looking up candidates, modules reached through imports are explored last so
+        // that paths to definitions are preferred over paths to re-exports.
+        let mut worklist = VecDeque::new();
+        let mut worklist_via_import = VecDeque::new();
+        worklist.push_back((self.graph_root, Vec::new()));
+
+        while let Some((module, module_path)) =
+            worklist.pop_front().or_else(|| worklist_via_import.pop_front())
+        {
+            for (key, resolution) in module.lazy_resolutions.borrow().iter() {
+                let binding = match resolution.borrow().binding {
+                    Some(binding) => binding,
+                    None => continue,
+                };
+                if binding.pseudo_vis() != ty::Visibility::Public
+                    || !binding.is_importable()
+                    || binding.is_ambiguity()
+                {
+                    continue;
+                }
+                let via_import = binding.is_import() && !binding.is_extern_crate();
+                // Variants are only suggested through their enum, see
+                // `lookup_import_candidates_from_module`.
+                if via_import && binding.is_possibly_imported_variant() {
+                    continue;
+                }
+                let (kind, def_id) = match binding.res() {
+                    Res::Def(kind, def_id) => (kind, def_id),
+                    _ => continue,
+                };
+                // Breadth-first, the first path found to an item is one of the shortest.
+                if seen_items.insert((key.ident.name, key.ns, def_id)) {
+                    candidates.push(ImportCandidate {
+                        module_path: module_path.clone(),
+                        ns: key.ns,
+                        ident: key.ident,
+                        kind,
+                        def_id,
+                        span: binding.span,
+                    });
+                }
+
+                if let Some(child) = binding.module() {
+                    let def_id = child.def_id().unwrap();
+                    let is_local = def_id.is_local() && !binding.is_extern_crate();
+                    if is_local && seen_modules.insert(def_id) {
+                        let mut child_path = module_path.clone();
+                        child_path.push(key.ident.name);
+                        if via_import { &mut worklist_via_import } else { &mut worklist }
+                            .push_back((child, child_path));
+                    }
+                }
+            }
+        }
+
+        candidates
+    }
+
+    /// Looks up candidates in the index of public items recorded in the metadata of the extern
+    /// crate `crate_id`, rather than walking its modules. Returns `None` if the crate has no index.
+    fn lookup_import_candidates_from_index<NameFn, FilterFn>(
+        &mut self,
+        namespace: Namespace,
+        crate_id: CrateNum,
+        crate_name: Ident,
+        name_matches: NameFn,
+        filter_fn: FilterFn,
+    ) -> Option<Vec<ImportSuggestion>>
+    where
+        NameFn: Fn(Symbol) -> bool,
+        FilterFn: Fn(Res) -> bool,
+    {
+        let mut candidates: Vec<ImportSuggestion> = Vec::new();
+        let index = self.cstore().import_candidates_untracked(crate_id, self.session)?;
+        for candidate in index {
+            if candidate.ns != namespace || !name_matches(candidate.ident.name) {
+                continue;
+            }
+            let res = candidate.res();
+            if !filter_fn(res) {
+                continue;
+            }
+            let did = match res {
+                Res::Def(DefKind::Ctor(..), did) => self.parent(did),
+                _ => res.opt_def_id(),
+            };
+            if candidates.iter().any(|candidate| candidate.did == did) {
+                continue;
+            }
+            let segments = iter::once(crate_name)
+                .chain(candidate.module_path.iter().copied().map(Ident::with_dummy_span))
+                .chain(iter::once(candidate.ident))
+                .map(ast::PathSegment::from_ident)
+                .collect();
+            let path = Path { span: candidate.span, segments };
+            candidates.push(ImportSuggestion {
+                did,
+                descr: res.descr(),
+                path,
+                accessible: true,
+                unstable: None,
+            });
+        }
+        Some(candidates)
+    }
+
+    fn lookup_import_candidates_from_module<NameFn, FilterFn>(
         &mut self,
         lookup_ident: Ident,
         namespace: Namespace,
         parent_scope: &ParentScope<'a>,
         start_module: Module<'a>,
         crate_name: Ident,
+        name_matches: NameFn,
         filter_fn: FilterFn,
     ) -> Vec<ImportSuggestion>
     where
+        NameFn: Fn(Symbol) -> bool,
         FilterFn: Fn(Res) -> bool,
     {
         let mut candidates = Vec::new();
@@ -738,7 +1366,7 @@ impl<'a> Resolver<'a> {
 
                 // collect results based on the filter function
                 // avoid suggesting anything from the same module in which we are resolving
-                if ident.name == lookup_ident.name
+                if name_matches(ident.name)
                     && ns == namespace
                     && !ptr::eq(in_module, parent_scope.module)
                 {
@@ -775,6 +1403,7 @@ impl<'a> Resolver<'a> {
                                 descr: res.descr(),
                                 path,
                                 accessible: child_accessible,
+                                unstable: None,
                             });
                         }
                     }
@@ -826,12 +1455,60 @@ impl<'a> Resolver<'a> {
     where
         FilterFn: Fn(Res) -> bool,
     {
+        self.lookup_import_candidates_matching(
+            lookup_ident,
+            namespace,
+            parent_scope,
+            |name| name == lookup_ident.name,
+            filter_fn,
+        )
+    }
+
+    /// Like `lookup_import_candidates`, but accepts any entity whose name is a likely typo of
+    /// `lookup_ident` instead of only those with the exact same name.
+    crate fn lookup_similar_import_candidates<FilterFn>(
+        &mut self,
+        lookup_ident: Ident,
+        namespace: Namespace,
+        parent_scope: &ParentScope<'a>,
+        filter_fn: FilterFn,
+    ) -> Vec<ImportSuggestion>
+    where
+        FilterFn: Fn(Res) -> bool,
+    {
+        let lookup_str = lookup_ident.as_str();
+        let max_dist = cmp::max(lookup_str.len(), 3) / 3;
+        self.lookup_import_candidates_matching(
+            lookup_ident,
+            namespace,
+            parent_scope,
+            |name| {
+                name != lookup_ident.name && lev_distance(&lookup_str, &name.as_str()) <= max_dist
+            },
+            filter_fn,
+        )
+    }
+
+    fn lookup_import_candidates_matching<NameFn, FilterFn>(
+        &mut self,
+        lookup_ident: Ident,
+        namespace: Namespace,
+        parent_scope: &ParentScope<'a>,
+        name_matches: NameFn,
+        filter_fn: FilterFn,
+    ) -> Vec<ImportSuggestion>
+    where
+        NameFn: Fn(Symbol) -> bool,
+        FilterFn: Fn(Res) -> bool,
+    {
+        let _prof_timer = self.session.prof.generic_activity("resolve_lookup_import_candidates");
         let mut suggestions = self.lookup_import_candidates_from_module(
             lookup_ident,
             namespace,
             parent_scope,
             self.graph_root,
             Ident::with_dummy_span(kw::Crate),
+            &name_matches,
             &filter_fn,
         );
 
@@ -846,9 +1523,18 @@ impl<'a> Resolver<'a> {
                     // otherwise cause duplicate suggestions.
                     continue;
                 }
-                if let Some(crate_id) =
-                    self.crate_loader.maybe_process_path_extern(ident.name, ident.span)
-                {
+                if let Some(crate_id) = self.extern_prelude_crate_for_suggestions(ident) {
+                    if let Some(candidates) = self.lookup_import_candidates_from_index(
+                        namespace,
+                        crate_id,
+                        ident,
+                        &name_matches,
+                        &filter_fn,
+                    ) {
+                        suggestions.extend(candidates);
+                        continue;
+                    }
+                    // Proc macro crates have no index, their macros are found by walking them.
                     let crate_root =
                         self.get_module(DefId { krate: crate_id, index: CRATE_DEF_INDEX });
                     suggestions.extend(self.lookup_import_candidates_from_module(
@@ -857,15 +1543,40 @@ impl<'a> Resolver<'a> {
                         parent_scope,
                         crate_root,
                         ident,
+                        &name_matches,
                         &filter_fn,
                     ));
                 }
             }
         }
 
+        // Items gated behind a feature this crate doesn't enable are only worth mentioning when
+        // nothing stable has the name.
+        for suggestion in &mut suggestions {
+            suggestion.unstable = self.unstable_feature(suggestion.did);
+        }
+        if suggestions.iter().any(|suggestion| suggestion.unstable.is_none()) {
+            suggestions.retain(|suggestion| suggestion.unstable.is_none());
+        }
         suggestions
     }
 
+    /// The feature gating the item `did` of another crate along with its tracking issue, if the
+    /// item is unstable and this crate doesn't enable the feature.
+    fn unstable_feature(&self, did: Option<DefId>) -> Option<(Symbol, Option<NonZeroU32>)> {
+        let did = did.filter(|did| !did.is_local())?;
+        let stability = self.cstore().stability_untracked(did)?;
+        let enabled = &self.session.features_untracked().declared_lib_features;
+        match stability.level {
+            StabilityLevel::Unstable { issue, .. }
+                if !enabled.iter().any(|&(feature, _)| feature == stability.feature) =>
+            {
+                Some((stability.feature, issue))
+            }
+            _ => None,
+        }
+    }
+
     crate fn unresolved_macro_suggestions(
         &mut self,
         err: &mut DiagnosticBuilder<'a>,
@@ -892,28 +1603,192 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    /// Looks up the bang macros named `ident` that can be reached by path, such as `pub macro`
+    /// items, for an unresolved single-segment macro invocation. If there is exactly one, its
+    /// qualified invocation is suggested as well.
+    crate fn unresolved_macro_import_candidates(
+        &mut self,
+        err: &mut DiagnosticBuilder<'a>,
+        macro_kind: MacroKind,
+        parent_scope: &ParentScope<'a>,
+        ident: Ident,
+    ) -> Vec<ImportSuggestion> {
+        // Modules without the prelude can't refer to other crates by a relative path.
+        if macro_kind != MacroKind::Bang
+            || ident.span.from_expansion()
+            || parent_scope.module.no_implicit_prelude
+        {
+            return Vec::new();
+        }
+        let is_expected = |res: Res| res.macro_kind() == Some(macro_kind);
+        let candidates = self.lookup_import_candidates(ident, MacroNS, parent_scope, is_expected);
+
+        if let [candidate] = &candidates[..] {
+            if candidate.accessible {
+                let path = path_names_to_string(&candidate.path);
+                let is_local = candidate.did.map_or(false, |did| did.is_local());
+                let path = if is_local && !path.starts_with("crate::") {
+                    format!("crate::{}", path)
+                } else if !is_local && ident.span.rust_2015() {
+                    format!("::{}", path)
+                } else {
+                    path
+                };
+                err.span_suggestion(
+                    ident.span,
+                    "invoke the macro by its path",
+                    path,
+                    Applicability::MachineApplicable,
+                );
+            }
+        }
+        candidates
+    }
+
+    /// Looks for a macro of the given kind with a name similar to the last segment of a
+    /// multi-segment macro `path`, in the module that the other segments resolve to.
+    crate fn macro_path_typo_suggestion(
+        &mut self,
+        path: &[Segment],
+        macro_kind: MacroKind,
+        parent_scope: &ParentScope<'a>,
+    ) -> Option<Suggestion> {
+        let (ident, module_path) = match path.split_last() {
+            Some((segment, module_path)) if !module_path.is_empty() => (segment.ident, module_path),
+            _ => return None,
+        };
+        let module = match self.resolve_path(
+            module_path,
+            Some(TypeNS),
+            parent_scope,
+            false,
+            ident.span,
+            CrateLint::No,
+        ) {
+            PathResult::Module(ModuleOrUniformRoot::Module(module)) => module,
+            _ => return None,
+        };
+
+        let mut candidates = Vec::new();
+        let is_expected = &|res: Res| res.macro_kind() == Some(macro_kind);
+        let truncated =
+            self.add_module_candidates(module, ident.name, &mut candidates, is_expected);
+        sort_typo_candidates(&mut candidates);
+
+        let lookup = ident.as_str();
+        let names = || candidates.iter().map(|candidate| &candidate.candidate);
+        let found = find_best_typo(names(), &lookup).filter(|&found| found != ident.name)?;
+        let applicability = typo_applicability(names(), &lookup, found, truncated);
+        let candidate = candidates.into_iter().find(|candidate| candidate.candidate == found)?;
+        let msg = format!(
+            "{} {} with a similar name exists",
+            candidate.res.article(),
+            candidate.res.descr()
+        );
+        Some((vec![(ident.span, found.to_string())], msg, applicability))
+    }
+
+    /// Lists the registered tools when the first segment of an attribute path like `#[tool::attr]`
+    /// could not be resolved, and either suggests a similarly named tool or explains how to
+    /// register a new one.
+    crate fn add_tool_attribute_help(&self, err: &mut DiagnosticBuilder<'_>, ident: Ident) {
+        if ident.is_path_segment_keyword() || self.registered_tools.contains(&ident) {
+            return;
+        }
+
+        let mut tools = self.registered_tools.iter().map(|tool| tool.name).collect::<Vec<_>>();
+        tools.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+        if let Some((last, rest)) = tools.split_last() {
+            let tools = match rest {
+                [] => format!("tool is `{}`", last),
+                _ => format!(
+                    "tools are {} and `{}`",
+                    rest.iter().map(|tool| format!("`{}`", tool)).collect::<Vec<_>>().join(", "),
+                    last
+                ),
+            };
+            err.note(&format!("the registered {}", tools));
+        }
+
+        let lookup = ident.as_str();
+        match find_best_match_for_name(tools.iter(), &lookup, None) {
+            Some(found) if found != ident.name => {
+                err.span_suggestion(
+                    ident.span,
+                    "a tool with a similar name exists",
+                    found.to_string(),
+                    typo_applicability(tools.iter(), &lookup, found, false),
+                );
+            }
+            _ if nightly_options::is_nightly_build() => {
+                let attrs = if self.session.features_untracked().register_tool {
+                    format!("`#![register_tool({})]`", ident)
+                } else {
+                    format!("`#![feature(register_tool)]` and `#![register_tool({})]`", ident)
+                };
+                err.help(&format!(
+                    "add {} to the crate attributes to use `{}` as a tool",
+                    attrs, ident
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    /// The span at which to suggest replacing the tokens at `span`. Tokens that a macro got from
+    /// its invocation but which were respanned into its expansion are moved back to where they
+    /// were written, as rustfix can't apply suggestions on spans from expansions. Returns `None`
+    /// if the replacement would edit the body of a macro from another crate.
+    crate fn suggestion_span(&self, mut span: Span) -> Option<Span> {
+        while span.from_expansion() {
+            let call_site = span.ctxt().outer_expn_data().call_site;
+            let located = span.with_ctxt(call_site.ctxt());
+            // A token made up by the macro may be located at its entire invocation.
+            if located == call_site || !call_site.contains(located) {
+                break;
+            }
+            span = located;
+        }
+        if self.session.source_map().is_imported(span) { None } else { Some(span) }
+    }
+
     crate fn add_typo_suggestion(
         &self,
         err: &mut DiagnosticBuilder<'_>,
         suggestion: Option<TypoSuggestion>,
         span: Span,
     ) -> bool {
+        match self.typo_fix(suggestion, span) {
+            Some(fix) => {
+                fix.add_to(err, self.session);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The fix replacing the name at `span` with a similarly named item, pointing at the
+    /// definition of that item.
+    crate fn typo_fix(
+        &self,
+        suggestion: Option<TypoSuggestion>,
+        span: Span,
+    ) -> Option<SuggestedFix> {
         let suggestion = match suggestion {
-            None => return false,
+            None => return None,
             // We shouldn't suggest underscore.
-            Some(suggestion) if suggestion.candidate == kw::Underscore => return false,
+            Some(suggestion) if suggestion.candidate == kw::Underscore => return None,
             Some(suggestion) => suggestion,
         };
-        let msg = format!(
-            "{} {} with a similar name exists",
-            suggestion.res.article(),
-            suggestion.res.descr()
-        );
-        err.span_suggestion(
+        let span = self.suggestion_span(span)?;
+        let msg = FixMessage::new("similar-item-exists")
+            .arg("article", suggestion.res.article())
+            .arg("descr", suggestion.res.descr());
+        let fix = SuggestedFix::new(
             span,
-            &msg,
+            msg,
             suggestion.candidate.to_string(),
-            Applicability::MaybeIncorrect,
+            suggestion.applicability,
         );
         let def_span = suggestion.res.opt_def_id().and_then(|def_id| match def_id.krate {
             LOCAL_CRATE => self.opt_span(def_id),
@@ -923,17 +1798,15 @@ impl<'a> Resolver<'a> {
                     .guess_head_span(self.cstore().get_span_untracked(def_id, self.session)),
             ),
         });
-        if let Some(span) = def_span {
-            err.span_label(
-                self.session.source_map().guess_head_span(span),
-                &format!(
-                    "similarly named {} `{}` defined here",
-                    suggestion.res.descr(),
-                    suggestion.candidate.as_str(),
-                ),
-            );
-        }
-        true
+        Some(match def_span {
+            Some(def_span) => fix.with_def_label(
+                self.session.source_map().guess_head_span(def_span),
+                FixMessage::new("similar-item-defined-here")
+                    .arg("descr", suggestion.res.descr())
+                    .arg("name", suggestion.candidate),
+            ),
+            None => fix,
+        })
     }
 
     fn binding_description(&self, b: &NameBinding<'_>, ident: Ident, from_prelude: bool) -> String {
@@ -1042,6 +1915,9 @@ impl<'a> Resolver<'a> {
 
         could_refer_to(b1, misc1, "");
         could_refer_to(b2, misc2, " also");
+        if let Some(precedence) = kind.glob_precedence() {
+            err.note(precedence);
+        }
         err.emit();
     }
 
@@ -1062,8 +1938,119 @@ impl<'a> Resolver<'a> {
         None
     }
 
-    crate fn report_privacy_error(&self, privacy_error: &PrivacyError<'_>) {
-        let PrivacyError { ident, binding, .. } = *privacy_error;
+    /// Resolves the paths of the local `impl`s with methods taking `self` into `impl_methods`, so
+    /// that late resolution can suggest method call syntax before it reaches the `impl`s.
+    crate fn resolve_local_impls(&mut self) {
+        for local_impl in mem::take(&mut self.local_impls) {
+            let parent_scope = ParentScope::module(local_impl.module);
+            let mut resolve = |path: &Path| {
+                let segments = Segment::from_path(path);
+                match self.resolve_path(
+                    &segments,
+                    Some(TypeNS),
+                    &parent_scope,
+                    false,
+                    path.span,
+                    CrateLint::No,
+                ) {
+                    PathResult::NonModule(res) if res.unresolved_segments() == 0 => {
+                        res.base_res().opt_def_id()
+                    }
+                    PathResult::Module(ModuleOrUniformRoot::Module(module)) => module.def_id(),
+                    _ => None,
+                }
+            };
+            let self_ty = match resolve(&local_impl.self_ty) {
+                Some(self_ty) => self_ty,
+                None => continue,
+            };
+            let of_trait = match local_impl.of_trait {
+                Some(ref path) => match resolve(path) {
+                    Some(trait_did) => Some(trait_did),
+                    None => continue,
+                },
+                None => None,
+            };
+            for name in local_impl.methods {
+                let methods = self.impl_methods.entry(name).or_default();
+                methods.push(ImplMethod { self_ty, of_trait });
+            }
+        }
+    }
+
+    /// Reports `new Type(<args>)`, suggesting an associated `new` function of the type, or a
+    /// struct literal.
+    crate fn report_new_operator_error(&self, error: NewOperatorError<'a>) {
+        let NewOperatorError { mut err, new_span, ty_span, call_span, ty_res, arg_count } = error;
+        let sess = self.session;
+        let def_id = match ty_res {
+            Some(Res::Def(_, def_id)) => def_id,
+            _ => {
+                err.note(&msg!(sess, "new-operator-note"));
+                err.emit();
+                return;
+            }
+        };
+        let path = match sess.source_map().span_to_snippet(ty_span) {
+            Ok(path) => path,
+            Err(_) => {
+                err.emit();
+                return;
+            }
+        };
+
+        let param_counts = if def_id.is_local() {
+            self.new_fn_param_counts.get(&def_id).cloned().unwrap_or_default()
+        } else {
+            self.cstore().inherent_fn_param_counts_untracked(def_id, sym::new, sess)
+        };
+        let field_names = match ty_res {
+            // Only local fields are known to be accessible.
+            Some(Res::Def(DefKind::Struct, _))
+                if def_id.is_local() && !self.struct_constructors.contains_key(&def_id) =>
+            {
+                self.field_names.get(&def_id)
+            }
+            _ => None,
+        };
+        if param_counts.contains(&arg_count) {
+            err.span_suggestion_verbose(
+                new_span.to(ty_span),
+                &msg!(sess, "new-operator-call-new", path = path),
+                format!("{}::new", path),
+                Applicability::MaybeIncorrect,
+            );
+        } else if let Some(&count) = param_counts.first() {
+            let args = vec!["/* value */"; count].join(", ");
+            err.span_suggestion_verbose(
+                call_span,
+                &msg!(
+                    sess,
+                    "new-operator-new-arity",
+                    path = path,
+                    count = count,
+                    s = pluralize!(count),
+                ),
+                format!("{}::new({})", path, args),
+                Applicability::HasPlaceholders,
+            );
+        } else if let (Some(field_names), 0) = (field_names, arg_count) {
+            let fields: Vec<_> =
+                field_names.iter().map(|field| format!("{}: /* value */", field.node)).collect();
+            err.span_suggestion_verbose(
+                call_span,
+                &msg!(sess, "new-operator-struct-literal"),
+                format!("{} {{ {} }}", path, fields.join(", ")),
+                Applicability::HasPlaceholders,
+            );
+        } else {
+            err.note(&msg!(sess, "new-operator-note"));
+        }
+        err.emit();
+    }
+
+    crate fn report_privacy_error(&self, krate: &ast::Crate, privacy_error: &PrivacyError<'a>) {
+        let PrivacyError { ident, binding, module, ref reexport_path, .. } = *privacy_error;
 
         let res = binding.res();
         let ctor_fields_span = self.ctor_fields_span(binding);
@@ -1083,27 +2070,29 @@ impl<'a> Resolver<'a> {
             err.span_label(span, "a constructor is private if any of the fields is private");
         }
 
-        // Print the whole import chain to make it easier to see what happens.
-        let first_binding = binding;
-        let mut next_binding = Some(binding);
-        let mut next_ident = ident;
-        while let Some(binding) = next_binding {
-            let name = next_ident;
-            next_binding = match binding.kind {
+        // Collect the whole import chain to make it easier to see what happens.
+        let mut chain = vec![(ident, binding)];
+        while let Some(&(name, binding)) = chain.last() {
+            let next = match binding.kind {
                 _ if res == Res::Err => None,
                 NameBindingKind::Import { binding, import, .. } => match import.kind {
                     _ if binding.span.is_dummy() => None,
-                    ImportKind::Single { source, .. } => {
-                        next_ident = source;
-                        Some(binding)
-                    }
-                    ImportKind::Glob { .. } | ImportKind::MacroUse => Some(binding),
+                    ImportKind::Single { source, .. } => Some((source, binding)),
+                    ImportKind::Glob { .. } | ImportKind::MacroUse => Some((name, binding)),
                     ImportKind::ExternCrate { .. } => None,
                 },
                 _ => None,
             };
+            match next {
+                Some(next) => chain.push(next),
+                None => break,
+            }
+        }
 
-            let first = ptr::eq(binding, first_binding);
+        // Print it, pointing out every link of a re-export chain that has to become more
+        // visible for the use site to work.
+        for (i, &(name, binding)) in chain.iter().enumerate() {
+            let first = i == 0;
             let descr = get_descr(binding);
             let msg = format!(
                 "{and_refers_to}the {item} `{name}`{which} is defined here{dots}",
@@ -1111,16 +2100,57 @@ impl<'a> Resolver<'a> {
                 item = descr,
                 name = name,
                 which = if first { "" } else { " which" },
-                dots = if next_binding.is_some() { "..." } else { "" },
+                dots = if i + 1 < chain.len() { "..." } else { "" },
             );
             let def_span = self.session.source_map().guess_head_span(binding.span);
             let mut note_span = MultiSpan::from_span(def_span);
+            // An import that is already visible enough is only held back by what it imports.
+            let import_visible = match binding.kind {
+                NameBindingKind::Import { import, .. } => {
+                    self.is_accessible_from(import.vis.get(), module)
+                }
+                _ => false,
+            };
             if !first && binding.vis == ty::Visibility::Public {
                 note_span.push_span_label(def_span, "consider importing it directly".into());
+            } else if chain.len() > 1
+                && !import_visible
+                && !self.is_accessible_from(binding.vis, module)
+            {
+                note_span.push_span_label(
+                    def_span,
+                    "consider making this visible to the use site".into(),
+                );
             }
             err.span_note(note_span, &msg);
         }
 
+        // The item may be public and only the module on the way to it private, in which case
+        // the module's parent can re-export it.
+        let parent = binding.module().and_then(|m| m.parent).and_then(|parent| parent.def_id());
+        if let (Some(reexport_path), Some(parent)) = (reexport_path, parent) {
+            if let (Some((span, prefix)), found_use) = self.use_placement(krate, parent) {
+                let item = reexport_path.last().unwrap();
+                let path = names_to_string(
+                    &iter::once(kw::SelfLower)
+                        .chain(iter::once(ident.name))
+                        .chain(reexport_path.iter().map(|ident| ident.name))
+                        .collect::<Vec<_>>(),
+                );
+                let additional_newline = if found_use { "" } else { "\n" };
+                err.span_suggestion(
+                    span,
+                    &format!(
+                        "consider re-exporting `{}` from the parent module instead of making `{}` \
+                         public",
+                        item, ident
+                    ),
+                    format!("{}pub use {};\n{}", prefix, path, additional_newline),
+                    Applicability::MaybeIncorrect,
+                );
+            }
+        }
+
         err.emit();
     }
 }
@@ -1509,6 +2539,61 @@ pub(crate) fn extend_span_to_previous_binding(sess: &Session, binding_span: Span
     )))
 }
 
+/// Given the `removal_span` of a binding that is being removed from a use statement with a single
+/// nested group:
+///
+/// ```
+/// use foo::{a, b};
+///           ^^^
+/// ```
+///
+/// then, if only one binding would be left in the group, return the spans from the braces to
+/// the remaining binding so that removing both collapses the group:
+///
+/// ```
+/// use foo::{a, b};
+///          ^^^^ ^
+/// ```
+pub(crate) fn find_spans_collapsing_nested_use(
+    sess: &Session,
+    removal_span: Span,
+    use_span: Span,
+) -> Option<Vec<Span>> {
+    let snippet = sess.source_map().span_to_snippet(use_span).ok()?;
+    if snippet.matches('{').count() != 1 || snippet.matches('}').count() != 1 {
+        return None;
+    }
+    let open = snippet.find('{')?;
+    let close = snippet.find('}')?;
+
+    let lo = use_span.lo().0;
+    let removal_start = removal_span.lo().0.checked_sub(lo)? as usize;
+    let removal_end = removal_span.hi().0.checked_sub(lo)? as usize;
+    if removal_start <= open || removal_end > close {
+        return None;
+    }
+
+    // Whatever is left of the group must be exactly one binding, which is either before or
+    // after the removed one.
+    let (before, after) = (&snippet[open + 1..removal_start], &snippet[removal_end..close]);
+    let (remaining_start, remaining) = match (before.trim().is_empty(), after.trim().is_empty()) {
+        (true, false) => (removal_end, after),
+        (false, true) => (open + 1, before),
+        _ => return None,
+    };
+    let binding = remaining.trim();
+    if binding.contains(',') || binding == "self" {
+        return None;
+    }
+
+    let binding_start = remaining_start + (remaining.len() - remaining.trim_start().len());
+    let binding_end = binding_start + binding.len();
+    let span_of = |start: usize, end: usize| {
+        use_span.with_lo(BytePos(lo + start as u32)).with_hi(BytePos(lo + end as u32))
+    };
+    Some(vec![span_of(open, binding_start), span_of(binding_end, close + 1)])
+}
+
 /// Given a `use_span` of a binding within a use statement, returns the highlighted span and if
 /// it is a nested use tree.
 ///
@@ -1570,12 +2655,16 @@ fn find_span_immediately_after_crate_name(
 /// entities with that name in all crates. This method allows outputting the
 /// results of this search in a programmer-friendly way
 crate fn show_candidates(
+    sess: &Session,
     err: &mut DiagnosticBuilder<'_>,
-    // This is `None` if all placement locations are inside expansions
-    use_placement_span: Option<Span>,
+    // This is `None` if all placement locations are inside expansions, otherwise it is the
+    // position to insert the `use` item at along with the text to precede it with.
+    use_placement: Option<(Span, String)>,
     candidates: &[ImportSuggestion],
     instead: bool,
     found_use: bool,
+    // Whether the paths of the candidates resolve from the module the `use` items are placed in.
+    paths_resolve: bool,
 ) {
     if candidates.is_empty() {
         return;
@@ -1598,15 +2687,24 @@ crate fn show_candidates(
     let instead = if instead { " instead" } else { "" };
     let mut msg = format!("consider importing {} {}{}", determiner, kind, instead);
 
-    if let Some(span) = use_placement_span {
+    if let Some((span, prefix)) = use_placement {
         for candidate in &mut path_strings {
             // produce an additional newline to separate the new use statement
             // from the directly following item.
             let additional_newline = if found_use { "" } else { "\n" };
-            *candidate = format!("use {};\n{}", candidate, additional_newline);
+            *candidate = format!("{}use {};\n{}", prefix, candidate, additional_newline);
         }
 
-        err.span_suggestions(span, &msg, path_strings.into_iter(), Applicability::Unspecified);
+        // A single accessible candidate is what the user meant to import.
+        let applicability = if path_strings.len() == 1
+            && candidates.iter().all(|c| c.accessible)
+            && paths_resolve
+        {
+            Applicability::MachineApplicable
+        } else {
+            Applicability::MaybeIncorrect
+        };
+        err.span_suggestions(span, &msg, path_strings.into_iter(), applicability);
     } else {
         msg.push(':');
 
@@ -1617,4 +2715,16 @@ crate fn show_candidates(
 
         err.note(&msg);
     }
+
+    // A lone unstable candidate can only be imported once its feature is enabled.
+    if let [ImportSuggestion { descr, path, unstable: Some((feature, issue)), .. }] = candidates {
+        let path = path_names_to_string(path);
+        err.note(&msg!(sess, "unstable-candidate", descr = descr, path = path));
+        if let Some(issue) = issue {
+            err.note(&msg!(sess, "tracking-issue", issue = issue));
+        }
+        if nightly_options::is_nightly_build() {
+            err.help(&msg!(sess, "enable-feature", feature = feature));
+        }
+    }
 }
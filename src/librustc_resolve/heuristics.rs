@@ -0,0 +1,741 @@
+//! The heuristics proposing replacements for a path that failed to resolve.
+//!
+//! Each heuristic is a function of a `PathSnapshot`, the parts of the resolver's state at the path
+//! that the diagnostics look at, and of whatever it needs to know besides, and returns the fix it
+//! proposes as a `SuggestedFix`. None of them touch the resolver or the diagnostic being built, so
+//! they can be tested on their own, and `rank_fixes` decides which of the fixes are offered.
+//!
+//! What a heuristic needs from the resolver or the source map is looked up by the visitor, and
+//! passed as a closure when it is only needed once the path turns out to have the right shape.
+//! The notes explaining the error without proposing a fix are added by the visitor as well.
+
+use crate::late::PathSource;
+use crate::messages::format_message;
+use crate::Segment;
+
+use rustc_ast::ast::{BinOpKind, BindingMode, Expr, ExprKind, Item, NodeId, PatKind, Stmt};
+use rustc_ast::ast::StmtKind;
+use rustc_ast::util::parser::PREC_POSTFIX;
+use rustc_ast::visit::{self, Visitor};
+use rustc_ast::util::lev_distance::{find_best_match_for_name, lev_distance};
+use rustc_data_structures::fx::FxHashSet;
+use rustc_errors::{Applicability, DiagnosticBuilder};
+use rustc_hir::def::{self, CtorKind, CtorOf, DefKind, Namespace};
+use rustc_session::Session;
+use rustc_span::source_map::Spanned;
+use rustc_span::symbol::{Ident, Symbol};
+use rustc_span::Span;
+
+use std::cmp;
+use std::fmt;
+
+#[cfg(test)]
+mod tests;
+
+type Res = def::Res<NodeId>;
+
+/// The most fixes offered as alternatives for a single path.
+crate const MAX_PATH_FIXES: usize = 3;
+
+/// Names other languages give to primitive types, and the Rust type closest to each.
+static FOREIGN_TYPE_NAMES: &[(&str, &str)] = &[
+    ("boolean", "bool"),
+    ("byte", "u8"),
+    ("double", "f64"),
+    ("float", "f32"),
+    ("float32", "f32"),
+    ("float64", "f64"),
+    ("int", "i32"),
+    ("int8", "i8"),
+    ("int16", "i16"),
+    ("int32", "i32"),
+    ("int64", "i64"),
+    ("integer", "i32"),
+    ("intptr_t", "isize"),
+    ("long", "i64"),
+    ("rune", "char"),
+    ("short", "i16"),
+    ("size_t", "usize"),
+    ("ssize_t", "isize"),
+    ("string", "String"),
+    ("uint", "u32"),
+    ("uint8", "u8"),
+    ("uint16", "u16"),
+    ("uint32", "u32"),
+    ("uint64", "u64"),
+    ("uintptr_t", "usize"),
+];
+
+/// A field or associated item from self type suggested in case of resolution failure.
+#[derive(Clone, Copy, PartialEq, Debug)]
+crate enum AssocSuggestion {
+    Field,
+    MethodWithSelf,
+    AssocItem,
+}
+
+/// A message of the resolver, kept as its slug and arguments until it is added to a diagnostic.
+#[derive(Clone, PartialEq, Debug)]
+crate struct FixMessage {
+    crate slug: &'static str,
+    crate args: Vec<(&'static str, String)>,
+}
+
+impl FixMessage {
+    crate fn new(slug: &'static str) -> Self {
+        FixMessage { slug, args: Vec::new() }
+    }
+
+    crate fn arg(mut self, name: &'static str, value: impl fmt::Display) -> Self {
+        self.args.push((name, value.to_string()));
+        self
+    }
+
+    crate fn render(&self, sess: &Session) -> String {
+        let args = self
+            .args
+            .iter()
+            .map(|(name, value)| (*name, value as &dyn fmt::Display))
+            .collect::<Vec<_>>();
+        format_message(sess, self.slug, &args)
+    }
+}
+
+/// A replacement for a path that failed to resolve, proposed by one of the heuristics along with
+/// the label pointing at what the replacement refers to, if any.
+#[derive(Clone, PartialEq, Debug)]
+crate struct SuggestedFix {
+    crate span: Span,
+    crate msg: FixMessage,
+    crate replacement: String,
+    crate applicability: Applicability,
+    crate def_label: Option<(Span, FixMessage)>,
+    /// A note explaining what the fix corrects, only added to the diagnostic if it is offered.
+    crate note: Option<FixMessage>,
+    /// The edits the fix makes elsewhere than at `span`, e.g. removing a statement it moves.
+    crate other_parts: Vec<(Span, String)>,
+    /// Whether the replacement is shown in the context of the code it changes even on its own.
+    crate verbose: bool,
+    /// Whether the label saying the path wasn't found is kept, because the fix changes the code
+    /// around the path rather than saying what it was meant to name.
+    crate keeps_fallback_label: bool,
+}
+
+impl SuggestedFix {
+    crate fn new(
+        span: Span,
+        msg: FixMessage,
+        replacement: String,
+        applicability: Applicability,
+    ) -> Self {
+        SuggestedFix {
+            span,
+            msg,
+            replacement,
+            applicability,
+            def_label: None,
+            note: None,
+            other_parts: Vec::new(),
+            verbose: false,
+            keeps_fallback_label: false,
+        }
+    }
+
+    crate fn with_def_label(mut self, span: Span, label: FixMessage) -> Self {
+        self.def_label = Some((span, label));
+        self
+    }
+
+    crate fn with_note(mut self, note: FixMessage) -> Self {
+        self.note = Some(note);
+        self
+    }
+
+    crate fn with_other_parts(mut self, other_parts: Vec<(Span, String)>) -> Self {
+        self.other_parts = other_parts;
+        self
+    }
+
+    crate fn verbose(mut self) -> Self {
+        self.verbose = true;
+        self
+    }
+
+    crate fn keep_fallback_label(mut self) -> Self {
+        self.keeps_fallback_label = true;
+        self
+    }
+
+    crate fn add_to(self, err: &mut DiagnosticBuilder<'_>, sess: &Session) {
+        let msg = self.msg.render(sess);
+        if !self.other_parts.is_empty() {
+            let mut parts = vec![(self.span, self.replacement)];
+            parts.extend(self.other_parts);
+            err.multipart_suggestion(&msg, parts, self.applicability);
+        } else if self.verbose {
+            err.span_suggestion_verbose(self.span, &msg, self.replacement, self.applicability);
+        } else {
+            err.span_suggestion(self.span, &msg, self.replacement, self.applicability);
+        }
+        if let Some((span, label)) = self.def_label {
+            err.span_label(span, label.render(sess));
+        }
+        if let Some(note) = self.note {
+            err.note(&note.render(sess));
+        }
+    }
+}
+
+/// What the heuristics know about the path that failed to resolve.
+crate struct PathSnapshot<'s> {
+    crate path: &'s [Segment],
+    crate source: PathSource<'s>,
+    /// What the path resolved to, if it resolved to something unexpected.
+    crate res: Option<Res>,
+    /// The span of the whole path.
+    crate span: Span,
+    /// The span at which the path can be replaced, if any, see `Resolver::suggestion_span`.
+    crate suggestion_span: Option<Span>,
+    /// The lifetime parameters in scope, innermost last.
+    crate lifetime_params: &'s [Ident],
+    /// Whether `self` can be used as a value at the path.
+    crate self_value_available: bool,
+}
+
+impl PathSnapshot<'_> {
+    crate fn ns(&self) -> Namespace {
+        self.source.namespace()
+    }
+
+    /// The identifier of a path made of a single segment which resolved to nothing.
+    crate fn unresolved_ident(&self) -> Option<Ident> {
+        match (self.res, self.path) {
+            (None, [segment]) => Some(segment.ident),
+            _ => None,
+        }
+    }
+
+    fn path_str(&self) -> String {
+        Segment::names_to_string(self.path)
+    }
+}
+
+/// The fixes to offer out of those proposed: the ones as certain as the most certain one, in the
+/// order of the heuristics that proposed them, and without repeating a replacement.
+crate fn rank_fixes(mut fixes: Vec<SuggestedFix>) -> Vec<SuggestedFix> {
+    let best = match fixes.iter().map(|fix| fix.applicability.certainty()).max() {
+        Some(best) => best,
+        None => return fixes,
+    };
+    fixes.retain(|fix| fix.applicability.certainty() == best);
+    let mut offered = FxHashSet::default();
+    fixes.retain(|fix| {
+        offered.len() < MAX_PATH_FIXES && offered.insert((fix.span, fix.replacement.clone()))
+    });
+    fixes
+}
+
+/// How certain renaming `lookup` to `found`, the typo `find_best_typo` picked among
+/// `candidates`, is to be right. The rename can be applied automatically when `found` is within
+/// typo distance of `lookup` and no other candidate is as close to it, a case-insensitive match
+/// being the closest a name can be. It can't if `truncated`, when the search stopped before
+/// looking at every name in scope, as the names it skipped could be as close.
+crate fn typo_applicability<'c>(
+    mut candidates: impl Iterator<Item = &'c Symbol>,
+    lookup: &str,
+    found: Symbol,
+    truncated: bool,
+) -> Applicability {
+    let distance = |name: Symbol| {
+        let name = name.as_str();
+        if name.to_uppercase() == lookup.to_uppercase() { 0 } else { lev_distance(lookup, &name) }
+    };
+    let found_distance = distance(found);
+    if truncated
+        || found_distance > cmp::max(lookup.len(), 3) / 3
+        || candidates.any(|&name| name != found && distance(name) <= found_distance)
+    {
+        Applicability::MaybeIncorrect
+    } else {
+        Applicability::MachineApplicable
+    }
+}
+
+/// The typo of `lookup` to suggest among `candidates`: the one `find_best_match_for_name` picks,
+/// or else a name only differing from it in the inflection of its words, even though that's
+/// further than typos go.
+crate fn find_best_typo<'c, I>(candidates: I, lookup: &str) -> Option<Symbol>
+where
+    I: Iterator<Item = &'c Symbol> + Clone,
+{
+    find_best_match_for_name(candidates.clone(), lookup, None).or_else(|| {
+        candidates.copied().find(|name| is_morphological_variant(lookup, &name.as_str()))
+    })
+}
+
+/// Whether `a` and `b` only differ in the inflection of their words, e.g. `get_value` and
+/// `get_values`, `Error` and `Errors`, `parse_item` and `parsed_item`, or `type` and `type_`.
+crate fn is_morphological_variant(a: &str, b: &str) -> bool {
+    a != b && stem_words(a) == stem_words(b)
+}
+
+fn stem_words(name: &str) -> String {
+    name.trim_end_matches('_').split('_').map(stem_word).collect::<Vec<_>>().join("_")
+}
+
+/// Strips the plural or verb ending of a single word, along with a silent `e` and a doubled final
+/// letter, so that `value`, `values`, `valued` and `valuing` all end up as `valu`. Stems shorter
+/// than three letters are left alone, to keep words like `is` or `as` from matching anything.
+fn stem_word(word: &str) -> String {
+    const SUFFIXES: &[(&str, &str)] =
+        &[("ies", "y"), ("ing", ""), ("ed", ""), ("es", ""), ("s", ""), ("e", "")];
+    let mut stem = word.to_string();
+    for &(suffix, replacement) in SUFFIXES {
+        if word.ends_with(suffix)
+            && word.len() - suffix.len() + replacement.len() >= 3
+            && !(suffix == "s" && word.ends_with("ss"))
+        {
+            stem.truncate(word.len() - suffix.len());
+            stem.push_str(replacement);
+            break;
+        }
+    }
+    let mut chars = stem.chars().rev();
+    if let (Some(last), Some(prev)) = (chars.next(), chars.next()) {
+        if last == prev && stem.len() > 3 {
+            stem.pop();
+        }
+    }
+    stem
+}
+
+/// The fix prefixing the path with `self.` or `Self::` when it names a field or an associated
+/// item of the self type. A field can't be suggested where `self` isn't available.
+crate fn assoc_item_fix(cx: &PathSnapshot<'_>, candidate: AssocSuggestion) -> Option<SuggestedFix> {
+    let path_str = cx.path_str();
+    let (msg, replacement) = match candidate {
+        AssocSuggestion::Field if cx.self_value_available => {
+            (FixMessage::new("use-available-field"), format!("self.{}", path_str))
+        }
+        AssocSuggestion::Field => return None,
+        AssocSuggestion::MethodWithSelf if cx.self_value_available => {
+            (FixMessage::new("try"), format!("self.{}", path_str))
+        }
+        AssocSuggestion::MethodWithSelf | AssocSuggestion::AssocItem => {
+            (FixMessage::new("try"), format!("Self::{}", path_str))
+        }
+    };
+    Some(SuggestedFix::new(cx.span, msg, replacement, Applicability::MachineApplicable))
+}
+
+/// The fix turning a call at `call_span` that passes `self` as its first argument into a method
+/// call, with `args` being the source of the remaining arguments.
+crate fn self_arg_call_fix(cx: &PathSnapshot<'_>, call_span: Span, args: &str) -> SuggestedFix {
+    let ident = cx.path.last().unwrap().ident;
+    SuggestedFix::new(
+        call_span,
+        FixMessage::new("call-as-method").arg("name", ident),
+        format!("self.{}({})", cx.path_str(), args),
+        Applicability::MachineApplicable,
+    )
+}
+
+/// The fix turning a call at `call_span` that passes `receiver` as its first argument into a
+/// method call on it, with `args` being the source of the remaining arguments.
+crate fn binding_method_call_fix(
+    cx: &PathSnapshot<'_>,
+    receiver: Ident,
+    call_span: Span,
+    args: &str,
+) -> SuggestedFix {
+    let ident = cx.path.last().unwrap().ident;
+    SuggestedFix::new(
+        call_span,
+        FixMessage::new("call-binding-method").arg("name", ident).arg("binding", receiver),
+        format!("{}.{}({})", receiver, ident, args),
+        Applicability::MaybeIncorrect,
+    )
+}
+
+/// The fix replacing the path with `binding.field` when it names a field of the type that one
+/// of `typed_bindings` is written to have. The innermost such binding, the last one, wins.
+crate fn binding_field_fix<'f>(
+    cx: &PathSnapshot<'_>,
+    typed_bindings: impl Iterator<Item = (Ident, &'f [Spanned<Symbol>])>,
+) -> Option<SuggestedFix> {
+    let ident = cx.unresolved_ident()?;
+    let binding = typed_bindings
+        .filter(|(_, fields)| fields.iter().any(|field| field.node == ident.name))
+        .map(|(binding, _)| binding)
+        .last()?;
+    Some(SuggestedFix::new(
+        cx.span,
+        FixMessage::new("use-binding-field").arg("binding", binding),
+        format!("{}.{}", binding, ident),
+        Applicability::MaybeIncorrect,
+    ))
+}
+
+/// The fix replacing the path with the variant among `variants`, those of the enum at `enum_path`
+/// that is being matched on, with a name similar to the path.
+crate fn scrutinee_variant_fix(
+    cx: &PathSnapshot<'_>,
+    enum_path: &str,
+    variants: &[Symbol],
+) -> Option<SuggestedFix> {
+    let ident = cx.path.last()?.ident;
+    let name = find_best_match_for_name(variants.iter(), &ident.as_str(), None)?;
+    Some(SuggestedFix::new(
+        cx.suggestion_span?,
+        FixMessage::new("similar-scrutinee-variant").arg("enum_path", enum_path),
+        format!("{}::{}", enum_path, name),
+        Applicability::MaybeIncorrect,
+    ))
+}
+
+/// The fix replacing the last segment of a pattern path with the item among `items`, those of the
+/// kind expected in the module the path was looked up in, that is closest to it, ignoring case
+/// and within half its length, which is further than typos go.
+crate fn closest_pattern_item_fix(
+    cx: &PathSnapshot<'_>,
+    items: Vec<(Symbol, Res)>,
+) -> Option<SuggestedFix> {
+    let (ident, mod_path) = cx.path.split_last()?;
+    let lookup = ident.ident.as_str().to_lowercase();
+    let (_, name, res) = items
+        .into_iter()
+        .map(|(name, res)| (lev_distance(&lookup, &name.as_str().to_lowercase()), name, res))
+        .filter(|&(dist, ..)| dist <= lookup.len() / 2)
+        .min_by_key(|&(dist, name, _)| (dist, name.to_string()))?;
+    Some(SuggestedFix::new(
+        ident.ident.span,
+        FixMessage::new("closest-pattern-item")
+            .arg("descr", res.descr())
+            .arg("module", Segment::names_to_string(mod_path)),
+        name.to_string(),
+        Applicability::MaybeIncorrect,
+    ))
+}
+
+/// The fix replacing a type named like a primitive type of another language, e.g. `int` or
+/// `size_t`, with the corresponding Rust type, if that type is what `resolve` finds under its name
+/// here.
+crate fn foreign_type_fix(
+    cx: &PathSnapshot<'_>,
+    resolve: impl FnOnce(&str) -> Option<Res>,
+) -> Option<SuggestedFix> {
+    if cx.ns() != Namespace::TypeNS {
+        return None;
+    }
+    let name = cx.unresolved_ident()?.as_str();
+    let (_, rust_name) = FOREIGN_TYPE_NAMES.iter().find(|(foreign, _)| *foreign == &*name)?;
+    let msg = match resolve(rust_name).filter(|&res| cx.source.is_expected(res))? {
+        Res::PrimTy(_) => FixMessage::new("foreign-primitive"),
+        _ => FixMessage::new("foreign-type"),
+    };
+    Some(SuggestedFix::new(
+        cx.suggestion_span?,
+        msg,
+        rust_name.to_string(),
+        Applicability::MaybeIncorrect,
+    ))
+}
+
+/// The fix adding the apostrophe to a type named like a lifetime in scope without it, e.g.
+/// `Ref<a, T>` for `Ref<'a, T>`, pointing at the declaration of that lifetime.
+crate fn lifetime_fix(cx: &PathSnapshot<'_>) -> Option<SuggestedFix> {
+    if !matches!(cx.source, PathSource::Type) {
+        return None;
+    }
+    let lifetime = format!("'{}", cx.unresolved_ident()?);
+    let param = cx.lifetime_params.iter().rev().find(|param| &*param.as_str() == lifetime)?;
+    let fix = SuggestedFix::new(
+        cx.suggestion_span?,
+        FixMessage::new("add-lifetime-apostrophe"),
+        lifetime.clone(),
+        Applicability::MaybeIncorrect,
+    );
+    Some(fix.with_def_label(
+        param.span,
+        FixMessage::new("lifetime-declared-here").arg("lifetime", lifetime),
+    ))
+}
+
+/// The fix writing `break 'label` when the value of a `break` is a name that matches one of the
+/// reachable `labels` but for its leading tick.
+crate fn label_tick_fix(
+    cx: &PathSnapshot<'_>,
+    mut labels: impl Iterator<Item = Ident>,
+) -> Option<SuggestedFix> {
+    match cx.source {
+        PathSource::Expr(Some(Expr { kind: ExprKind::Break(None, Some(_)), .. })) => {}
+        _ => return None,
+    }
+    let ident = cx.unresolved_ident()?;
+    let label = labels.find(|label| {
+        label.span.ctxt() == ident.span.ctxt() && label.as_str().get(1..) == Some(&*ident.as_str())
+    })?;
+    let fix = SuggestedFix::new(
+        ident.span,
+        FixMessage::new("label-tick"),
+        label.to_string(),
+        Applicability::MachineApplicable,
+    );
+    let fix = fix.with_def_label(label.span, FixMessage::new("reachable-label"));
+    Some(fix.keep_fallback_label())
+}
+
+/// Collects the single-segment paths mentioned and assigned to in the visited code.
+#[derive(Default)]
+struct LocalPaths {
+    mentioned: Vec<Ident>,
+    assigned: Vec<Ident>,
+}
+
+impl<'ast> Visitor<'ast> for LocalPaths {
+    // Paths in nested items can't refer to the block's bindings.
+    fn visit_item(&mut self, _: &'ast Item) {}
+
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        let local_path = |expr: &Expr| match expr.kind {
+            ExprKind::Path(None, ref path) if path.segments.len() == 1 => {
+                Some(path.segments[0].ident)
+            }
+            _ => None,
+        };
+        if let Some(ident) = local_path(expr) {
+            self.mentioned.push(ident);
+        }
+        match expr.kind {
+            ExprKind::Assign(ref lhs, ..) | ExprKind::AssignOp(_, ref lhs, _) => {
+                self.assigned.extend(local_path(lhs));
+            }
+            _ => {}
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+/// The fix moving the statement at `let_index` of `stmts`, a `let` declaring the path, before the
+/// one at the earlier `use_index` using it. It is only proposed for a plain `let ident = init;`
+/// that the statements in between neither assign to nor are needed by. The moved statement takes
+/// along the whitespace separating it from the one preceding it, `snippet` giving the source of a
+/// span.
+crate fn later_binding_fix(
+    cx: &PathSnapshot<'_>,
+    stmts: &[Stmt],
+    use_index: usize,
+    let_index: usize,
+    snippet: impl Fn(Span) -> Option<String>,
+) -> Option<SuggestedFix> {
+    let ident = cx.unresolved_ident()?;
+    let local = match stmts[let_index].kind {
+        StmtKind::Local(ref local) => local,
+        _ => return None,
+    };
+    match local.pat.kind {
+        PatKind::Ident(BindingMode::ByValue(_), binding, None) if binding == ident => {}
+        _ => return None,
+    }
+    let init = local.init.as_ref()?;
+    let mut between_paths = LocalPaths::default();
+    let mut between_bindings = Vec::new();
+    for stmt in &stmts[use_index..let_index] {
+        between_paths.visit_stmt(stmt);
+        if let StmtKind::Local(ref between_local) = stmt.kind {
+            between_local.pat.walk(&mut |pat| {
+                if let PatKind::Ident(_, binding, _) = pat.kind {
+                    between_bindings.push(binding);
+                }
+                true
+            });
+        }
+    }
+    let mut init_paths = LocalPaths::default();
+    init_paths.visit_expr(init);
+    if between_paths.assigned.contains(&ident)
+        || init_paths.mentioned.iter().any(|name| between_bindings.contains(name))
+    {
+        return None;
+    }
+
+    let stmt = stmts[let_index].span;
+    let prev = stmts[let_index - 1].span.source_callsite();
+    let before = stmts[use_index].span.source_callsite();
+    if stmt.from_expansion() || prev.from_expansion() || before.from_expansion() {
+        return None;
+    }
+    let gap = prev.between(stmt);
+    let separator = snippet(gap)?;
+    if !separator.chars().all(char::is_whitespace) {
+        return None;
+    }
+    let fix = SuggestedFix::new(
+        before.shrink_to_lo(),
+        FixMessage::new("declare-before-use").arg("name", ident),
+        snippet(stmt)? + &separator,
+        Applicability::MaybeIncorrect,
+    );
+    Some(fix.with_other_parts(vec![(gap.to(stmt), String::new())]).keep_fallback_label())
+}
+
+/// The fix adding the turbofish when a type is used as a value on the right hand side of
+/// `path < Type`, which is how `foo<A, B>(x)` gets parsed in argument lists and tuples where the
+/// parser can't recover.
+crate fn turbofish_fix(cx: &PathSnapshot<'_>) -> Option<SuggestedFix> {
+    let lhs = match cx.source {
+        PathSource::Expr(Some(Expr { kind: ExprKind::Binary(op, lhs, rhs), .. }))
+            if op.node == BinOpKind::Lt && rhs.span == cx.span =>
+        {
+            lhs
+        }
+        _ => return None,
+    };
+    if !matches!(lhs.kind, ExprKind::Path(None, _)) || lhs.span.from_expansion() {
+        return None;
+    }
+    match cx.res {
+        Some(Res::Def(
+            DefKind::Struct
+            | DefKind::Union
+            | DefKind::Enum
+            | DefKind::TyAlias
+            | DefKind::ForeignTy
+            | DefKind::TyParam
+            | DefKind::AssocTy,
+            _,
+        ))
+        | Some(Res::PrimTy(..))
+        | Some(Res::SelfTy(..)) => {}
+        _ => return None,
+    }
+    let fix = SuggestedFix::new(
+        lhs.span.shrink_to_hi(),
+        FixMessage::new("use-turbofish"),
+        "::".to_string(),
+        Applicability::MaybeIncorrect,
+    );
+    Some(fix.with_note(FixMessage::new("parsed-as-comparison")).verbose().keep_fallback_label())
+}
+
+/// The fix adding `!` when the path is the callee of a call expression and `is_bang_macro` finds
+/// a bang macro under its name.
+crate fn invoke_macro_fix(
+    cx: &PathSnapshot<'_>,
+    is_bang_macro: impl FnOnce() -> bool,
+) -> Option<SuggestedFix> {
+    match cx.source {
+        PathSource::Expr(Some(Expr { kind: ExprKind::Call(callee, _), .. }))
+            if callee.span == cx.span => {}
+        _ => return None,
+    }
+    if cx.res.is_some() || !is_bang_macro() {
+        return None;
+    }
+    let fix = SuggestedFix::new(
+        cx.span.shrink_to_hi(),
+        FixMessage::new("invoke-macro"),
+        "!".to_string(),
+        Applicability::MaybeIncorrect,
+    );
+    Some(fix.verbose().keep_fallback_label())
+}
+
+/// The fix rewriting `await(fut)` into `fut.await`, `snippet` giving the source of `fut`.
+crate fn postfix_await_fix(
+    cx: &PathSnapshot<'_>,
+    snippet: impl FnOnce(Span) -> Option<String>,
+) -> Option<SuggestedFix> {
+    let (call, arg) = match cx.source {
+        PathSource::Expr(Some(call)) => match call.kind {
+            ExprKind::Call(ref callee, ref args) if callee.span == cx.span && args.len() == 1 => {
+                (call, &args[0])
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+    if call.span.from_expansion() {
+        return None;
+    }
+    let snippet = snippet(arg.span)?;
+    let sugg = if arg.precedence().order() < PREC_POSTFIX {
+        format!("({}).await", snippet)
+    } else {
+        format!("{}.await", snippet)
+    };
+    let fix = SuggestedFix::new(
+        call.span,
+        FixMessage::new("use-postfix-await"),
+        sugg,
+        Applicability::MaybeIncorrect,
+    );
+    Some(fix.verbose().keep_fallback_label())
+}
+
+/// The fix giving a pattern the shape expected by the variant's constructor the path resolved to,
+/// e.g. `Some(..)` for `Some` or `None` for `None(..)`, pointing at the variant at `def_span`.
+/// `snippet` gives the source of a span. The fix is certain unless it drops subpatterns that may
+/// bind something or leaves the fields of a struct variant to fill in.
+crate fn variant_pat_shape_fix(
+    cx: &PathSnapshot<'_>,
+    def_span: Option<Span>,
+    snippet: impl Fn(Span) -> Option<String>,
+) -> Option<SuggestedFix> {
+    let ctor_kind = match cx.res? {
+        Res::Def(DefKind::Ctor(CtorOf::Variant, ctor_kind), _) => ctor_kind,
+        _ => return None,
+    };
+    let span = cx.span;
+    if span.from_expansion() {
+        return None;
+    }
+    let path = snippet(span)?;
+    let mut applicability = Applicability::MachineApplicable;
+    let (sugg_span, sugg, descr) = match (ctor_kind, cx.source) {
+        (CtorKind::Fn, PathSource::Pat) => (span.shrink_to_hi(), "(..)".to_string(), "tuple"),
+        (CtorKind::Fictive, PathSource::Pat) => {
+            (span.shrink_to_hi(), " { .. }".to_string(), "struct")
+        }
+        (CtorKind::Const, PathSource::TupleStruct(pat_span)) => {
+            let args = snippet(span.between(pat_span.shrink_to_hi()));
+            let only_wildcards = args.map_or(false, |args| {
+                args.trim_start_matches('(')
+                    .trim_end_matches(')')
+                    .split(',')
+                    .all(|arg| matches!(arg.trim(), "" | "_" | ".."))
+            });
+            if !only_wildcards {
+                applicability = Applicability::MaybeIncorrect;
+            }
+            (pat_span, path, "unit")
+        }
+        (CtorKind::Fictive, PathSource::TupleStruct(pat_span)) => {
+            // Only an empty tuple pattern can be rewritten without knowing the field names.
+            let args = snippet(span.between(pat_span.shrink_to_hi()));
+            let fields = if args.map_or(false, |args| args == "()" || args == "(..)") {
+                ".."
+            } else {
+                applicability = Applicability::HasPlaceholders;
+                "/* fields */"
+            };
+            (pat_span, format!("{} {{ {} }}", path, fields), "struct")
+        }
+        _ => return None,
+    };
+
+    let fix = SuggestedFix::new(
+        sugg_span,
+        FixMessage::new("use-variant-pat-syntax").arg("kind", descr),
+        sugg,
+        applicability,
+    );
+    let fix = match def_span {
+        Some(def_span) => fix.with_def_label(
+            def_span,
+            FixMessage::new("defined-here").arg("path", cx.path_str()),
+        ),
+        None => fix,
+    };
+    Some(fix.verbose().keep_fallback_label())
+}
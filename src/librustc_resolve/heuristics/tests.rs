@@ -0,0 +1,300 @@
+use super::*;
+
+use rustc_ast::ast::{AttrVec, Path, DUMMY_NODE_ID};
+use rustc_ast::ptr::P;
+use rustc_ast::with_default_session_globals;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::{DefId, CRATE_DEF_INDEX};
+use rustc_hir::PrimTy;
+use rustc_span::source_map::respan;
+use rustc_span::BytePos;
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+fn segments(path: &str) -> Vec<Segment> {
+    path.split("::").map(|name| Segment::from_ident(Ident::from_str(name))).collect()
+}
+
+fn snapshot<'s>(path: &'s [Segment], source: PathSource<'s>) -> PathSnapshot<'s> {
+    PathSnapshot {
+        path,
+        source,
+        res: None,
+        span: span(0, 4),
+        suggestion_span: Some(span(0, 4)),
+        lifetime_params: &[],
+        self_value_available: false,
+    }
+}
+
+fn expr(kind: ExprKind, span: Span) -> P<Expr> {
+    P(Expr { id: DUMMY_NODE_ID, kind, span, attrs: AttrVec::new(), tokens: None })
+}
+
+fn path_expr(name: &str, span: Span) -> P<Expr> {
+    expr(ExprKind::Path(None, Path::from_ident(Ident::from_str(name))), span)
+}
+
+fn fix(replacement: &str, applicability: Applicability) -> SuggestedFix {
+    SuggestedFix::new(span(0, 4), FixMessage::new("try"), replacement.to_string(), applicability)
+}
+
+fn symbols(names: &[&str]) -> Vec<Symbol> {
+    names.iter().map(|name| Symbol::intern(name)).collect()
+}
+
+fn replacements(fixes: &[SuggestedFix]) -> Vec<&str> {
+    fixes.iter().map(|fix| &fix.replacement[..]).collect()
+}
+
+#[test]
+fn rank_fixes_offers_most_certain() {
+    let fixes = vec![
+        fix("a", Applicability::MaybeIncorrect),
+        fix("b", Applicability::MachineApplicable),
+        fix("c", Applicability::MaybeIncorrect),
+        fix("d", Applicability::MachineApplicable),
+    ];
+    assert_eq!(replacements(&rank_fixes(fixes)), ["b", "d"]);
+}
+
+#[test]
+fn rank_fixes_drops_repeated_and_extra_fixes() {
+    let fixes = vec![
+        fix("a", Applicability::MaybeIncorrect),
+        fix("a", Applicability::MaybeIncorrect),
+        fix("b", Applicability::MaybeIncorrect),
+        fix("c", Applicability::MaybeIncorrect),
+        fix("d", Applicability::MaybeIncorrect),
+    ];
+    assert_eq!(replacements(&rank_fixes(fixes)), ["a", "b", "c"]);
+    assert!(rank_fixes(Vec::new()).is_empty());
+}
+
+#[test]
+fn typo_applicability_requires_a_single_closest_name() {
+    with_default_session_globals(|| {
+        let candidates = symbols(&["counter", "counted", "Counter"]);
+        let applicability = typo_applicability(candidates.iter(), "countr", candidates[0], false);
+        assert_eq!(applicability, Applicability::MachineApplicable);
+
+        let candidates = symbols(&["total", "totes"]);
+        let applicability = typo_applicability(candidates.iter(), "totel", candidates[0], false);
+        assert_eq!(applicability, Applicability::MaybeIncorrect);
+
+        // A case-insensitive match beats any typo, but not another case-insensitive match.
+        let candidates = symbols(&["String", "strings"]);
+        let applicability = typo_applicability(candidates.iter(), "string", candidates[0], false);
+        assert_eq!(applicability, Applicability::MachineApplicable);
+        let candidates = symbols(&["Limit", "LIMIT"]);
+        let applicability = typo_applicability(candidates.iter(), "limit", candidates[1], false);
+        assert_eq!(applicability, Applicability::MaybeIncorrect);
+
+        // Names matched by their words rather than by their spelling are never certain.
+        let candidates = symbols(&["value_get"]);
+        let applicability =
+            typo_applicability(candidates.iter(), "get_value", candidates[0], false);
+        assert_eq!(applicability, Applicability::MaybeIncorrect);
+
+        // A closer name could be among the ones left out of a truncated search.
+        let candidates = symbols(&["counter", "counted", "Counter"]);
+        let applicability = typo_applicability(candidates.iter(), "countr", candidates[0], true);
+        assert_eq!(applicability, Applicability::MaybeIncorrect);
+    })
+}
+
+#[test]
+fn assoc_item_fix_depends_on_self() {
+    with_default_session_globals(|| {
+        let path = segments("len");
+        let mut cx = snapshot(&path, PathSource::Expr(None));
+        assert_eq!(assoc_item_fix(&cx, AssocSuggestion::Field), None);
+        let fix = assoc_item_fix(&cx, AssocSuggestion::MethodWithSelf).unwrap();
+        assert_eq!(fix.replacement, "Self::len");
+
+        cx.self_value_available = true;
+        let fix = assoc_item_fix(&cx, AssocSuggestion::Field).unwrap();
+        assert_eq!(fix.replacement, "self.len");
+        assert_eq!(fix.msg, FixMessage::new("use-available-field"));
+        assert_eq!(fix.applicability, Applicability::MachineApplicable);
+    })
+}
+
+#[test]
+fn binding_field_fix_prefers_innermost_binding() {
+    with_default_session_globals(|| {
+        let path = segments("width");
+        let cx = snapshot(&path, PathSource::Expr(None));
+        let outer = vec![respan(span(0, 0), Symbol::intern("width"))];
+        let inner = vec![respan(span(0, 0), Symbol::intern("height"))];
+        let bindings = vec![
+            (Ident::from_str("rect"), &outer[..]),
+            (Ident::from_str("size"), &outer[..]),
+            (Ident::from_str("other"), &inner[..]),
+        ];
+        let fix = binding_field_fix(&cx, bindings.into_iter()).unwrap();
+        assert_eq!(fix.replacement, "size.width");
+        assert_eq!(fix.msg, FixMessage::new("use-binding-field").arg("binding", "size"));
+    })
+}
+
+#[test]
+fn scrutinee_variant_fix_uses_enum_path() {
+    with_default_session_globals(|| {
+        let path = segments("Sone");
+        let cx = snapshot(&path, PathSource::Pat);
+        let variants = [Symbol::intern("Some"), Symbol::intern("None")];
+        let fix = scrutinee_variant_fix(&cx, "Option", &variants).unwrap();
+        assert_eq!(fix.replacement, "Option::Some");
+        assert_eq!(scrutinee_variant_fix(&cx, "Option", &[]), None);
+    })
+}
+
+#[test]
+fn closest_pattern_item_fix_ignores_case() {
+    with_default_session_globals(|| {
+        let path = segments("limits::Max");
+        let cx = snapshot(&path, PathSource::Pat);
+        let res = Res::Def(DefKind::Const, DefId::local(CRATE_DEF_INDEX));
+        let items = vec![(Symbol::intern("MIN"), res), (Symbol::intern("MAX"), res)];
+        let fix = closest_pattern_item_fix(&cx, items).unwrap();
+        assert_eq!(fix.replacement, "MAX");
+        assert_eq!(fix.span, path[1].ident.span);
+        assert_eq!(fix.msg.args, [("descr", "constant".to_string()), ("module", "limits".into())]);
+
+        let items = vec![(Symbol::intern("UNRELATED"), res)];
+        assert_eq!(closest_pattern_item_fix(&cx, items), None);
+    })
+}
+
+#[test]
+fn foreign_type_fix_checks_resolution() {
+    with_default_session_globals(|| {
+        let path = segments("boolean");
+        let cx = snapshot(&path, PathSource::Type);
+        let fix = foreign_type_fix(&cx, |_| Some(Res::PrimTy(PrimTy::Bool))).unwrap();
+        assert_eq!(fix.replacement, "bool");
+        assert_eq!(fix.msg, FixMessage::new("foreign-primitive"));
+        assert_eq!(foreign_type_fix(&cx, |_| None), None);
+
+        let path = segments("string");
+        let cx = snapshot(&path, PathSource::Type);
+        let res = Res::Def(DefKind::Struct, DefId::local(CRATE_DEF_INDEX));
+        let fix = foreign_type_fix(&cx, |name| {
+            assert_eq!(name, "String");
+            Some(res)
+        });
+        assert_eq!(fix.unwrap().msg, FixMessage::new("foreign-type"));
+
+        let cx = snapshot(&path, PathSource::Expr(None));
+        assert_eq!(foreign_type_fix(&cx, |_| Some(res)), None);
+    })
+}
+
+#[test]
+fn typos_fall_back_to_inflected_names() {
+    with_default_session_globals(|| {
+        let names = [Symbol::intern("Box"), Symbol::intern("Bag")];
+        assert_eq!(find_best_typo(names.iter(), "Boxes"), Some(Symbol::intern("Box")));
+        assert_eq!(find_best_typo(names.iter(), "Bags"), Some(Symbol::intern("Bag")));
+
+        let names = [Symbol::intern("get_entry")];
+        assert_eq!(find_best_typo(names.iter(), "get_entries"), Some(names[0]));
+        assert_eq!(find_best_typo(names.iter(), "set_entries"), None);
+    })
+}
+
+#[test]
+fn morphological_variants() {
+    assert!(is_morphological_variant("get_value", "get_values"));
+    assert!(is_morphological_variant("Errors", "Error"));
+    assert!(is_morphological_variant("type_", "type"));
+    assert!(is_morphological_variant("parse_items", "parsed_item"));
+    assert!(is_morphological_variant("stopping", "stop"));
+    assert!(is_morphological_variant("classes", "class"));
+    assert!(!is_morphological_variant("class", "class"));
+    assert!(!is_morphological_variant("as", "a"));
+    assert!(!is_morphological_variant("get_value", "set_value"));
+}
+
+#[test]
+fn lifetime_fix_points_at_innermost_lifetime() {
+    with_default_session_globals(|| {
+        let path = segments("a");
+        let params = [
+            Ident::from_str_and_span("'a", span(10, 12)),
+            Ident::from_str_and_span("'b", span(14, 16)),
+            Ident::from_str_and_span("'a", span(20, 22)),
+        ];
+        let mut cx = snapshot(&path, PathSource::Type);
+        assert_eq!(lifetime_fix(&cx), None);
+
+        cx.lifetime_params = &params;
+        let fix = lifetime_fix(&cx).unwrap();
+        assert_eq!(fix.replacement, "'a");
+        assert_eq!(fix.def_label.map(|(span, _)| span), Some(span(20, 22)));
+
+        cx.source = PathSource::Expr(None);
+        assert_eq!(lifetime_fix(&cx), None);
+    })
+}
+
+#[test]
+fn label_tick_fix_only_applies_to_break_values() {
+    with_default_session_globals(|| {
+        let path = segments("outer");
+        let labels = [Ident::from_str("'inner"), Ident::from_str_and_span("'outer", span(8, 14))];
+        let value = path_expr("outer", span(0, 4));
+        let brk = expr(ExprKind::Break(None, Some(value)), span(0, 10));
+        let cx = snapshot(&path, PathSource::Expr(Some(&*brk)));
+        let fix = label_tick_fix(&cx, labels.iter().copied()).unwrap();
+        assert_eq!(fix.replacement, "'outer");
+        assert_eq!(fix.def_label.map(|(span, _)| span), Some(span(8, 14)));
+
+        let cx = snapshot(&path, PathSource::Expr(None));
+        assert_eq!(label_tick_fix(&cx, labels.iter().copied()), None);
+    })
+}
+
+#[test]
+fn turbofish_fix_needs_a_type_compared_with_a_path() {
+    with_default_session_globals(|| {
+        let path = segments("Vec");
+        let lhs = path_expr("collect", span(10, 17));
+        let lt = respan(span(17, 18), BinOpKind::Lt);
+        let cmp = expr(ExprKind::Binary(lt, lhs, path_expr("Vec", span(0, 4))), span(10, 22));
+        let mut cx = snapshot(&path, PathSource::Expr(Some(&*cmp)));
+        assert_eq!(turbofish_fix(&cx), None);
+
+        cx.res = Some(Res::Def(DefKind::Struct, DefId::local(CRATE_DEF_INDEX)));
+        let fix = turbofish_fix(&cx).unwrap();
+        assert_eq!(fix.replacement, "::");
+        assert_eq!(fix.span, span(17, 17));
+        assert_eq!(fix.note, Some(FixMessage::new("parsed-as-comparison")));
+    })
+}
+
+#[test]
+fn postfix_await_fix_parenthesizes_operators() {
+    with_default_session_globals(|| {
+        let path = segments("await");
+        let callee = path_expr("await", span(0, 4));
+        let call = expr(ExprKind::Call(callee, vec![path_expr("fut", span(5, 8))]), span(0, 9));
+        let cx = snapshot(&path, PathSource::Expr(Some(&*call)));
+        let fix = postfix_await_fix(&cx, |_| Some("fut".to_string())).unwrap();
+        assert_eq!(fix.replacement, "fut.await");
+        assert_eq!(fix.span, span(0, 9));
+
+        let add = respan(span(7, 8), BinOpKind::Add);
+        let (a, b) = (path_expr("a", span(5, 6)), path_expr("b", span(9, 10)));
+        let arg = expr(ExprKind::Binary(add, a, b), span(5, 10));
+        let callee = path_expr("await", span(0, 4));
+        let call = expr(ExprKind::Call(callee, vec![arg]), span(0, 11));
+        let cx = snapshot(&path, PathSource::Expr(Some(&*call)));
+        let fix = postfix_await_fix(&cx, |_| Some("a + b".to_string())).unwrap();
+        assert_eq!(fix.replacement, "(a + b).await");
+        assert_eq!(postfix_await_fix(&cx, |_| None), None);
+    })
+}
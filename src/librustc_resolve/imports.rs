@@ -1,13 +1,14 @@
 //! A bunch of methods and structures more or less related to resolving imports.
 
 use crate::diagnostics::Suggestion;
+use crate::heuristics::typo_applicability;
 use crate::Determinacy::{self, *};
 use crate::Namespace::{self, MacroNS, TypeNS};
 use crate::{module_to_string, names_to_string};
 use crate::{AmbiguityError, AmbiguityErrorMisc, AmbiguityKind};
 use crate::{BindingKey, ModuleKind, ResolutionError, Resolver, Segment};
 use crate::{CrateLint, Module, ModuleOrUniformRoot, ParentScope, PerNS, ScopeSet, Weak};
-use crate::{NameBinding, NameBindingKind, PathResult, PrivacyError, ToNameBinding};
+use crate::{NameBinding, NameBindingKind, PathFailure, PathResult, PrivacyError, ToNameBinding};
 
 use rustc_ast::ast::NodeId;
 use rustc_ast::unwrap_or;
@@ -140,7 +141,7 @@ pub struct NameResolution<'a> {
     single_imports: FxHashSet<PtrKey<'a, Import<'a>>>,
     /// The least shadowable known binding for this name, or None if there are no known bindings.
     pub binding: Option<&'a NameBinding<'a>>,
-    shadowed_glob: Option<&'a NameBinding<'a>>,
+    pub(crate) shadowed_glob: Option<&'a NameBinding<'a>>,
 }
 
 impl<'a> NameResolution<'a> {
@@ -250,11 +251,22 @@ impl<'a> Resolver<'a> {
         };
 
         let key = self.new_key(ident, ns);
-        let resolution =
-            self.resolution(module, key).try_borrow_mut().map_err(|_| (Determined, Weak::No))?; // This happens when there is a cycle of imports.
+        let resolution = if self.speculative_resolution.is_some() {
+            // Probing for a name must not add an entry for it to the module.
+            let arenas = self.arenas;
+            let resolution = self.resolutions(module).borrow().get(&key).copied();
+            resolution.unwrap_or_else(|| arenas.alloc_name_resolution())
+        } else {
+            self.resolution(module, key)
+        };
+        // This happens when there is a cycle of imports.
+        let resolution = resolution.try_borrow_mut().map_err(|_| (Determined, Weak::No))?;
 
         if let Some(binding) = resolution.binding {
-            if !restricted_shadowing && binding.expansion != ExpnId::root() {
+            if !restricted_shadowing
+                && binding.expansion != ExpnId::root()
+                && self.speculative_resolution.is_none()
+            {
                 if let NameBindingKind::Res(_, true) = binding.kind {
                     self.macro_expanded_macro_export_errors.insert((path_span, binding.span));
                 }
@@ -320,7 +332,9 @@ impl<'a> Resolver<'a> {
                             self.privacy_errors.push(PrivacyError {
                                 ident,
                                 binding,
+                                module: parent_scope.module,
                                 dedup_span: path_span,
+                                reexport_path: None,
                             });
                         }
 
@@ -617,6 +631,8 @@ impl<'a> Resolver<'a> {
 struct UnresolvedImportError {
     span: Span,
     label: Option<String>,
+    /// Why the path of the import failed to resolve, which labels the span unless `label` does.
+    failure: Option<PathFailure>,
     note: Vec<String>,
     suggestion: Option<Suggestion>,
 }
@@ -713,6 +729,7 @@ impl<'a, 'b> ImportResolver<'a, 'b> {
                 let err = UnresolvedImportError {
                     span: import.span,
                     label: None,
+                    failure: None,
                     note: Vec::new(),
                     suggestion: None,
                 };
@@ -753,8 +770,13 @@ impl<'a, 'b> ImportResolver<'a, 'b> {
             }
         }
 
+        let session = self.r.session;
+        self.r.path_failures.borrow_mut().extend(
+            errors.iter().filter_map(|(_, err)| Some((err.span, err.failure.clone()?))),
+        );
         for (_, err) in errors.into_iter().take(MAX_LABEL_COUNT) {
-            if let Some(label) = err.label {
+            let label = err.label.or_else(|| err.failure.map(|failure| failure.message(session)));
+            if let Some(label) = label {
                 diag.span_label(err.span, label);
             }
 
@@ -882,6 +904,7 @@ impl<'a, 'b> ImportResolver<'a, 'b> {
             _ => None,
         };
         let prev_ambiguity_errors_len = self.r.ambiguity_errors.len();
+        let prev_privacy_errors_len = self.r.privacy_errors.len();
         let path_res = self.r.resolve_path(
             &import.module_path,
             None,
@@ -916,15 +939,15 @@ impl<'a, 'b> ImportResolver<'a, 'b> {
 
                 module
             }
-            PathResult::Failed { is_error_from_last_segment: false, span, label, suggestion } => {
+            PathResult::Failed { is_error_from_last_segment: false, span, failure, suggestion } => {
                 if no_ambiguity {
                     assert!(import.imported_module.get().is_none());
-                    self.r
-                        .report_error(span, ResolutionError::FailedToResolve { label, suggestion });
+                    let error = ResolutionError::FailedToResolve { failure, suggestion };
+                    self.r.report_error(span, error);
                 }
                 return None;
             }
-            PathResult::Failed { is_error_from_last_segment: true, span, label, suggestion } => {
+            PathResult::Failed { is_error_from_last_segment: true, span, failure, suggestion } => {
                 if no_ambiguity {
                     assert!(import.imported_module.get().is_none());
                     let err = match self.make_path_suggestion(
@@ -935,6 +958,7 @@ impl<'a, 'b> ImportResolver<'a, 'b> {
                         Some((suggestion, note)) => UnresolvedImportError {
                             span,
                             label: None,
+                            failure: None,
                             note,
                             suggestion: Some((
                                 vec![(span, Segment::names_to_string(&suggestion))],
@@ -944,7 +968,8 @@ impl<'a, 'b> ImportResolver<'a, 'b> {
                         },
                         None => UnresolvedImportError {
                             span,
-                            label: Some(label),
+                            label: None,
+                            failure: Some(failure),
                             note: Vec::new(),
                             suggestion,
                         },
@@ -992,6 +1017,7 @@ impl<'a, 'b> ImportResolver<'a, 'b> {
                         return Some(UnresolvedImportError {
                             span: import.span,
                             label: Some(String::from("cannot glob-import a module into itself")),
+                            failure: None,
                             note: Vec::new(),
                             suggestion: None,
                         });
@@ -1030,6 +1056,12 @@ impl<'a, 'b> ImportResolver<'a, 'b> {
 
                 match binding {
                     Ok(binding) => {
+                        this.record_reexport_path(
+                            prev_privacy_errors_len,
+                            &import.module_path,
+                            ident,
+                            binding,
+                        );
                         // Consistency checks, analogous to `finalize_macro_resolutions`.
                         let initial_res = source_bindings[ns].get().map(|initial_binding| {
                             all_ns_err = false;
@@ -1131,13 +1163,15 @@ impl<'a, 'b> ImportResolver<'a, 'b> {
                         _ => Some(&i.name),
                     }
                 });
+                let names = names.collect::<Vec<_>>();
 
-                let lev_suggestion =
-                    find_best_match_for_name(names, &ident.as_str(), None).map(|suggestion| {
+                let lookup = ident.as_str();
+                let lev_suggestion = find_best_match_for_name(names.iter().copied(), &lookup, None)
+                    .map(|suggestion| {
                         (
                             vec![(ident.span, suggestion.to_string())],
                             String::from("a similar name exists in the module"),
-                            Applicability::MaybeIncorrect,
+                            typo_applicability(names.iter().copied(), &lookup, suggestion, false),
                         )
                     });
 
@@ -1170,6 +1204,7 @@ impl<'a, 'b> ImportResolver<'a, 'b> {
                 Some(UnresolvedImportError {
                     span: import.span,
                     label: Some(label),
+                    failure: None,
                     note,
                     suggestion,
                 })
@@ -7,9 +7,10 @@
 
 use RibKind::*;
 
+use crate::diagnostics::TypoSuggestion;
 use crate::{path_names_to_string, BindingError, CrateLint, LexicalScopeBinding};
 use crate::{Module, ModuleOrUniformRoot, NameBindingKind, ParentScope, PathResult};
-use crate::{ResolutionError, Resolver, Segment, UseError};
+use crate::{ResolutionError, Resolver, RibSnapshot, ScopeSnapshot, Segment, UseError};
 
 use rustc_ast::ast::*;
 use rustc_ast::ptr::P;
@@ -23,7 +24,9 @@ use rustc_hir::def::{self, CtorKind, DefKind, PartialRes, PerNS};
 use rustc_hir::def_id::{DefId, CRATE_DEF_INDEX};
 use rustc_hir::TraitCandidate;
 use rustc_middle::{bug, span_bug};
+use rustc_session::config::nightly_options;
 use rustc_session::lint;
+use rustc_session::Session;
 use rustc_span::def_id::LocalDefId;
 use rustc_span::symbol::{kw, sym, Ident, Symbol};
 use rustc_span::Span;
@@ -83,10 +86,57 @@ enum PatBoundCtx {
 /// Does this the item (from the item rib scope) allow generic parameters?
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 crate enum HasGenericParams {
-    Yes,
+    /// Carries the span of the item's generics, which is empty when it has none.
+    Yes(Span),
     No,
 }
 
+/// The parts of a nested `fn` item needed to suggest rewriting it as a closure that can capture
+/// its environment.
+#[derive(Copy, Clone, Debug)]
+crate struct NestedFnItem {
+    /// The name of the function.
+    crate ident: Ident,
+    /// The signature, from the `fn` keyword up to the body.
+    crate header_span: Span,
+    /// The parameters, without the enclosing parentheses.
+    crate params_span: Option<Span>,
+    /// The explicit return type, if any.
+    crate ret_ty_span: Option<Span>,
+    /// The body, after which the closure's `;` goes.
+    crate body_span: Span,
+}
+
+/// A `let` binding whose initializer could just as well be evaluated at compile time, used to
+/// suggest turning it into a `const` item when it is referred to from a constant.
+#[derive(Copy, Clone, Debug)]
+crate struct ConstCandidateLocal {
+    /// The name of the binding.
+    crate ident: Ident,
+    /// From the `let` keyword up to the initializer.
+    crate let_span: Span,
+    /// The explicit type annotation, if any.
+    crate ty_span: Option<Span>,
+    /// The type implied by a suffixed or non-numeric literal initializer, e.g. `u32` for `42u32`.
+    crate lit_ty: Option<&'static str>,
+}
+
+/// The kind of item whose initializer is covered by a `ConstantItemRibKind` rib.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+crate enum ConstantItemKind {
+    Const,
+    Static,
+}
+
+impl ConstantItemKind {
+    crate fn as_str(&self) -> &'static str {
+        match self {
+            ConstantItemKind::Const => "const",
+            ConstantItemKind::Static => "static",
+        }
+    }
+}
+
 /// The rib kind restricts certain accesses,
 /// e.g. to a `Res::Local` of an outer item.
 #[derive(Copy, Clone, Debug)]
@@ -105,13 +155,15 @@ crate enum RibKind<'a> {
 
     /// We passed through a function definition. Disallow upvars.
     /// Permit only those const parameters that are specified in the function's generics.
-    FnItemRibKind,
+    /// Carries the function's signature when it could be turned into a closure instead.
+    FnItemRibKind(Option<NestedFnItem>),
 
     /// We passed through an item scope. Disallow upvars.
     ItemRibKind(HasGenericParams),
 
     /// We're in a constant item. Can't refer to dynamic stuff.
-    ConstantItemRibKind,
+    /// Carries the kind of item unless this is an anonymous constant or enum discriminant.
+    ConstantItemRibKind(Option<ConstantItemKind>),
 
     /// We passed through a module.
     ModuleRibKind(Module<'a>),
@@ -132,13 +184,28 @@ impl RibKind<'_> {
         match self {
             NormalRibKind
             | ClosureOrAsyncRibKind
-            | FnItemRibKind
-            | ConstantItemRibKind
+            | FnItemRibKind(_)
+            | ConstantItemRibKind(_)
             | ModuleRibKind(_)
             | MacroDefinition(_) => false,
             AssocItemRibKind | ItemRibKind(_) | ForwardTyParamBanRibKind => true,
         }
     }
+
+    /// What the names of this rib kind are bound by, for `-Z track-resolution`.
+    crate fn descr(&self, sess: &Session) -> String {
+        match self {
+            NormalRibKind => msg!(sess, "scope-local"),
+            AssocItemRibKind => msg!(sess, "scope-assoc-item-generics"),
+            ClosureOrAsyncRibKind => msg!(sess, "scope-closure-params"),
+            FnItemRibKind(_) => msg!(sess, "scope-fn-params"),
+            ItemRibKind(_) => msg!(sess, "scope-item-generics"),
+            ConstantItemRibKind(_) => msg!(sess, "scope-constant"),
+            ModuleRibKind(_) => msg!(sess, "scope-module-items"),
+            MacroDefinition(_) => msg!(sess, "scope-macro-rules"),
+            ForwardTyParamBanRibKind => msg!(sess, "scope-later-ty-params"),
+        }
+    }
 }
 
 /// A single local scope.
@@ -183,17 +250,17 @@ crate enum PathSource<'a> {
     Pat,
     // Paths in struct expressions and patterns `Path { .. }`.
     Struct,
-    // Paths in tuple struct patterns `Path(..)`.
-    TupleStruct,
+    // Paths in tuple struct patterns `Path(..)`, with the span of the whole pattern.
+    TupleStruct(Span),
     // `m::A::B` in `<T as m::A>::B::C`.
     TraitItem(Namespace),
 }
 
 impl<'a> PathSource<'a> {
-    fn namespace(self) -> Namespace {
+    crate fn namespace(self) -> Namespace {
         match self {
             PathSource::Type | PathSource::Trait(_) | PathSource::Struct => TypeNS,
-            PathSource::Expr(..) | PathSource::Pat | PathSource::TupleStruct => ValueNS,
+            PathSource::Expr(..) | PathSource::Pat | PathSource::TupleStruct(_) => ValueNS,
             PathSource::TraitItem(ns) => ns,
         }
     }
@@ -204,7 +271,7 @@ impl<'a> PathSource<'a> {
             | PathSource::Expr(..)
             | PathSource::Pat
             | PathSource::Struct
-            | PathSource::TupleStruct => true,
+            | PathSource::TupleStruct(_) => true,
             PathSource::Trait(_) | PathSource::TraitItem(..) => false,
         }
     }
@@ -215,7 +282,7 @@ impl<'a> PathSource<'a> {
             PathSource::Trait(_) => "trait",
             PathSource::Pat => "unit struct, unit variant or constant",
             PathSource::Struct => "struct, variant or union type",
-            PathSource::TupleStruct => "tuple struct or tuple variant",
+            PathSource::TupleStruct(_) => "tuple struct or tuple variant",
             PathSource::TraitItem(ns) => match ns {
                 TypeNS => "associated type",
                 ValueNS => "method or associated constant",
@@ -301,7 +368,7 @@ impl<'a> PathSource<'a> {
                 | Res::SelfCtor(..) => true,
                 _ => false,
             },
-            PathSource::TupleStruct => match res {
+            PathSource::TupleStruct(_) => match res {
                 Res::Def(DefKind::Ctor(_, CtorKind::Fn), _) | Res::SelfCtor(..) => true,
                 _ => false,
             },
@@ -336,8 +403,8 @@ impl<'a> PathSource<'a> {
             (PathSource::Struct, false) => error_code!(E0422),
             (PathSource::Expr(..), true) => error_code!(E0423),
             (PathSource::Expr(..), false) => error_code!(E0425),
-            (PathSource::Pat | PathSource::TupleStruct, true) => error_code!(E0532),
-            (PathSource::Pat | PathSource::TupleStruct, false) => error_code!(E0531),
+            (PathSource::Pat | PathSource::TupleStruct(_), true) => error_code!(E0532),
+            (PathSource::Pat | PathSource::TupleStruct(_), false) => error_code!(E0531),
             (PathSource::TraitItem(..), true) => error_code!(E0575),
             (PathSource::TraitItem(..), false) => error_code!(E0576),
         }
@@ -346,8 +413,9 @@ impl<'a> PathSource<'a> {
 
 #[derive(Default)]
 struct DiagnosticMetadata<'ast> {
-    /// The current trait's associated types' ident, used for diagnostic suggestions.
-    current_trait_assoc_types: Vec<Ident>,
+    /// The associated types' idents of the current trait or impl, used for diagnostic
+    /// suggestions.
+    current_assoc_types: Vec<Ident>,
 
     /// The current self type if inside an impl (used for better errors).
     current_self_type: Option<Ty>,
@@ -374,6 +442,39 @@ struct DiagnosticMetadata<'ast> {
 
     /// Only used for better errors on `let <pat>: <expr, not type>;`.
     current_let_binding: Option<(Span, Option<Span>, Option<Span>)>,
+
+    /// The assignment whose left-hand side is being resolved, used to suggest introducing a new
+    /// binding with `let` when the assigned name doesn't resolve.
+    in_assignment: Option<&'ast Expr>,
+
+    /// The blocks of the current function body enclosing the path being resolved, innermost last.
+    /// Used to point at bindings that are only declared after their use.
+    enclosing_blocks: Vec<&'ast Block>,
+
+    /// The loops and block expressions enclosing the expression being resolved, innermost last,
+    /// along with the number of label ribs in scope at each of them. Used to suggest labeling one
+    /// of them for a `break` or `continue` naming an undeclared label.
+    enclosing_breakables: Vec<(usize, &'ast Expr)>,
+
+    /// The closures and async blocks enclosing the expression being resolved, innermost last.
+    /// Used to point at what makes a label unreachable.
+    enclosing_closures: Vec<&'ast Expr>,
+
+    /// The scrutinee of the `match` whose arm pattern is being resolved, used to suggest the
+    /// variants of its enum first.
+    current_match_scrutinee: Option<&'ast Expr>,
+
+    /// The lifetime parameters in scope, innermost last, used to suggest one of them for a type
+    /// written like it without the apostrophe.
+    current_lifetime_params: Vec<Ident>,
+
+    /// The generics in scope, innermost last, used to copy the bounds of an outer item's
+    /// generic parameter when suggesting to declare it on a nested item.
+    current_generics: Vec<&'ast Generics>,
+
+    /// The nested items' generics and outer generic parameters already suggested to declare
+    /// there, to only suggest each once.
+    suggested_outer_generic_params: FxHashSet<(Span, Res)>,
 }
 
 struct LateResolutionVisitor<'a, 'b, 'ast> {
@@ -394,6 +495,10 @@ struct LateResolutionVisitor<'a, 'b, 'ast> {
 
     /// Fields used to add information to diagnostic errors.
     diagnostic_metadata: DiagnosticMetadata<'ast>,
+
+    /// Scratch buffer for the names collected by `lookup_typo_candidate`, kept around so that
+    /// crates with many resolution failures do not allocate it anew for each of them.
+    typo_candidates: Vec<TypoSuggestion>,
 }
 
 /// Walks the whole crate in DFS order, visiting each item, resolving names as it goes.
@@ -411,7 +516,7 @@ impl<'a, 'ast> Visitor<'ast> for LateResolutionVisitor<'a, '_, 'ast> {
     }
     fn visit_anon_const(&mut self, constant: &'ast AnonConst) {
         debug!("visit_anon_const {:?}", constant);
-        self.with_constant_rib(|this| {
+        self.with_constant_rib(None, |this| {
             visit::walk_anon_const(this, constant);
         });
     }
@@ -461,7 +566,8 @@ impl<'a, 'ast> Visitor<'ast> for LateResolutionVisitor<'a, '_, 'ast> {
         match foreign_item.kind {
             ForeignItemKind::Fn(_, _, ref generics, _)
             | ForeignItemKind::TyAlias(_, ref generics, ..) => {
-                self.with_generic_param_rib(generics, ItemRibKind(HasGenericParams::Yes), |this| {
+                let kind = ItemRibKind(HasGenericParams::Yes(generics.span));
+                self.with_generic_param_rib(generics, kind, |this| {
                     visit::walk_foreign_item(this, foreign_item);
                 });
             }
@@ -479,12 +585,20 @@ impl<'a, 'ast> Visitor<'ast> for LateResolutionVisitor<'a, '_, 'ast> {
         let rib_kind = match fn_kind {
             // Bail if there's no body.
             FnKind::Fn(.., None) => return visit::walk_fn(self, fn_kind, sp),
-            FnKind::Fn(FnCtxt::Free | FnCtxt::Foreign, ..) => FnItemRibKind,
+            FnKind::Fn(FnCtxt::Free, _, sig, _, Some(body)) => {
+                FnItemRibKind(self.nested_fn_item(sig, body, sp))
+            }
+            FnKind::Fn(FnCtxt::Foreign, ..) => FnItemRibKind(None),
             FnKind::Fn(FnCtxt::Assoc(_), ..) => NormalRibKind,
             FnKind::Closure(..) => ClosureOrAsyncRibKind,
         };
         let previous_value =
             replace(&mut self.diagnostic_metadata.current_function, Some((fn_kind, sp)));
+        // Blocks of an enclosing function body are not visible from within a nested function.
+        let previous_blocks = match fn_kind {
+            FnKind::Fn(..) => Some(take(&mut self.diagnostic_metadata.enclosing_blocks)),
+            FnKind::Closure(..) => None,
+        };
         debug!("(resolving function) entering function");
         let declaration = fn_kind.decl();
 
@@ -507,6 +621,9 @@ impl<'a, 'ast> Visitor<'ast> for LateResolutionVisitor<'a, '_, 'ast> {
             })
         });
         self.diagnostic_metadata.current_function = previous_value;
+        if let Some(previous_blocks) = previous_blocks {
+            self.diagnostic_metadata.enclosing_blocks = previous_blocks;
+        }
     }
 
     fn visit_generics(&mut self, generics: &'ast Generics) {
@@ -597,7 +714,7 @@ impl<'a, 'ast> Visitor<'ast> for LateResolutionVisitor<'a, '_, 'ast> {
                         if !check_ns(TypeNS) && check_ns(ValueNS) {
                             // This must be equivalent to `visit_anon_const`, but we cannot call it
                             // directly due to visitor lifetimes so we have to copy-paste some code.
-                            self.with_constant_rib(|this| {
+                            self.with_constant_rib(None, |this| {
                                 this.smart_resolve_path(
                                     ty.id,
                                     qself.as_ref(),
@@ -644,6 +761,7 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
             label_ribs: Vec::new(),
             current_trait_ref: None,
             diagnostic_metadata: DiagnosticMetadata::default(),
+            typo_candidates: Vec::new(),
         }
     }
 
@@ -654,14 +772,16 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
         record_used_id: Option<NodeId>,
         path_span: Span,
     ) -> Option<LexicalScopeBinding<'a>> {
-        self.r.resolve_ident_in_lexical_scope(
+        let binding = self.r.resolve_ident_in_lexical_scope(
             ident,
             ns,
             &self.parent_scope,
             record_used_id,
             path_span,
             &self.ribs[ns],
-        )
+        );
+        self.report_outer_generic_param_uses();
+        binding
     }
 
     fn resolve_path(
@@ -672,7 +792,7 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
         path_span: Span,
         crate_lint: CrateLint,
     ) -> PathResult<'a> {
-        self.r.resolve_path_with_ribs(
+        let result = self.r.resolve_path_with_ribs(
             path,
             opt_ns,
             &self.parent_scope,
@@ -680,7 +800,9 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
             path_span,
             crate_lint,
             Some(&self.ribs),
-        )
+        );
+        self.report_outer_generic_param_uses();
+        result
     }
 
     // AST resolution
@@ -734,7 +856,7 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
 
     /// Searches the current set of local scopes for labels. Returns the `NodeId` of the resolved
     /// label and reports an error if the label is not found or is unreachable.
-    fn resolve_label(&self, mut label: Ident) -> Option<NodeId> {
+    fn resolve_label(&self, mut label: Ident, expr: &Expr) -> Option<NodeId> {
         let mut suggestion = None;
 
         // Preserve the original span so that errors contain "in this macro invocation"
@@ -763,6 +885,8 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
                             name: &label.name.as_str(),
                             definition_span: ident.span,
                             suggestion,
+                            boundary: self.closure_boundary_after_rib(i),
+                            control_flow_span: expr.span.with_hi(original_span.hi()),
                         },
                     );
 
@@ -775,13 +899,90 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
             suggestion = suggestion.or_else(|| self.suggestion_for_label_in_rib(i, label));
         }
 
+        // Diagnostics: Without a similarly named label, the label could be introduced on the
+        // nearest enclosing loop or, for a `break` with a value outside of any loop, on the nearest
+        // enclosing block if labeled blocks can be enabled. Only `loop` can break with a value.
+        let is_value_break = matches!(expr.kind, ExprKind::Break(_, Some(_)));
+        let labeled_blocks = self.r.session.features_untracked().label_break_value
+            || nightly_options::is_nightly_build();
+        let (mut enclosing_loop, mut enclosing_block) = (None, None);
+        if suggestion.is_none() {
+            match self.reachable_enclosing_breakable(|kind| !matches!(kind, ExprKind::Block(..))) {
+                Some(expr) if !is_value_break || matches!(expr.kind, ExprKind::Loop(..)) => {
+                    enclosing_loop = self.unlabeled_breakable_span(expr, original_span);
+                }
+                Some(_) => {}
+                None if is_value_break && labeled_blocks => {
+                    let block = self.reachable_enclosing_breakable(|kind| {
+                        matches!(kind, ExprKind::Block(..))
+                    });
+                    enclosing_block =
+                        block.and_then(|expr| self.unlabeled_breakable_span(expr, original_span));
+                }
+                None => {}
+            }
+        }
         self.r.report_error(
             original_span,
-            ResolutionError::UndeclaredLabel { name: &label.name.as_str(), suggestion },
+            ResolutionError::UndeclaredLabel {
+                name: &label.name.as_str(),
+                suggestion,
+                enclosing_loop,
+                enclosing_block,
+            },
         );
         None
     }
 
+    /// Returns the innermost enclosing loop or block expression of the given kind, if it is
+    /// reachable by labels from the current position.
+    fn reachable_enclosing_breakable(
+        &self,
+        is_kind: impl Fn(&ExprKind) -> bool,
+    ) -> Option<&'ast Expr> {
+        let &(label_depth, expr) = self
+            .diagnostic_metadata
+            .enclosing_breakables
+            .iter()
+            .rev()
+            .find(|(_, expr)| is_kind(&expr.kind))?;
+        let reachable = self.label_ribs[label_depth..]
+            .iter()
+            .all(|rib| matches!(rib.kind, NormalRibKind | MacroDefinition(..)));
+        if reachable { Some(expr) } else { None }
+    }
+
+    /// Returns the span of a loop or block expression if it has no label of its own and a label
+    /// used at `label_span` could be introduced on it.
+    fn unlabeled_breakable_span(&self, expr: &Expr, label_span: Span) -> Option<Span> {
+        let unlabeled = match expr.kind {
+            ExprKind::Loop(_, None)
+            | ExprKind::While(_, _, None)
+            | ExprKind::ForLoop(_, _, _, None) => true,
+            // Only plain blocks can be labeled, not `unsafe` ones.
+            ExprKind::Block(ref block, None) => block.rules == BlockCheckMode::Default,
+            _ => false,
+        };
+        if unlabeled && expr.span.ctxt() == label_span.ctxt() { Some(expr.span) } else { None }
+    }
+
+    /// Returns the span and description of the innermost closure or async block making a label
+    /// from the `rib_index`th label rib unreachable, if nothing but closures and async blocks stand
+    /// in the way.
+    fn closure_boundary_after_rib(&self, rib_index: usize) -> Option<(Span, &'static str)> {
+        let only_closures = self.label_ribs[rib_index + 1..].iter().all(|rib| {
+            matches!(rib.kind, NormalRibKind | MacroDefinition(..) | ClosureOrAsyncRibKind)
+        });
+        if !only_closures {
+            return None;
+        }
+        let expr = self.diagnostic_metadata.enclosing_closures.last()?;
+        Some(match expr.kind {
+            ExprKind::Closure(.., fn_decl_span) => (fn_decl_span, "closure"),
+            _ => (self.r.session.source_map().span_until_whitespace(expr.span), "`async` block"),
+        })
+    }
+
     /// Determine whether or not a label from the `rib_index`th label rib is reachable.
     fn is_label_valid_from_rib(&self, rib_index: usize) -> bool {
         let ribs = &self.label_ribs[rib_index + 1..];
@@ -794,9 +995,9 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
 
                 AssocItemRibKind
                 | ClosureOrAsyncRibKind
-                | FnItemRibKind
+                | FnItemRibKind(..)
                 | ItemRibKind(..)
-                | ConstantItemRibKind
+                | ConstantItemRibKind(_)
                 | ModuleRibKind(..)
                 | ForwardTyParamBanRibKind => {
                     return false;
@@ -810,7 +1011,8 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
     fn resolve_adt(&mut self, item: &'ast Item, generics: &'ast Generics) {
         debug!("resolve_adt");
         self.with_current_self_item(item, |this| {
-            this.with_generic_param_rib(generics, ItemRibKind(HasGenericParams::Yes), |this| {
+            let kind = ItemRibKind(HasGenericParams::Yes(generics.span));
+            this.with_generic_param_rib(generics, kind, |this| {
                 let item_def_id = this.r.local_def_id(item.id).to_def_id();
                 this.with_self_rib(Res::SelfTy(None, Some(item_def_id)), |this| {
                     visit::walk_item(this, item);
@@ -838,9 +1040,11 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
 
             for &ns in nss {
                 match self.resolve_ident_in_lexical_scope(ident, ns, None, use_tree.prefix.span) {
-                    Some(LexicalScopeBinding::Res(..)) => {
+                    // `Res::Err` placeholders for already reported names are not locals.
+                    Some(LexicalScopeBinding::Res(res)) if res != Res::Err => {
                         report_error(self, ns);
                     }
+                    Some(LexicalScopeBinding::Res(..)) => {}
                     Some(LexicalScopeBinding::Item(binding)) => {
                         let orig_unusable_binding =
                             replace(&mut self.r.unusable_binding, Some(binding));
@@ -867,7 +1071,8 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
 
         match item.kind {
             ItemKind::TyAlias(_, ref generics, _, _) | ItemKind::Fn(_, _, ref generics, _) => {
-                self.with_generic_param_rib(generics, ItemRibKind(HasGenericParams::Yes), |this| {
+                let kind = ItemRibKind(HasGenericParams::Yes(generics.span));
+                self.with_generic_param_rib(generics, kind, |this| {
                     visit::walk_item(this, item)
                 });
             }
@@ -885,12 +1090,15 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
                 items: ref impl_items,
                 ..
             } => {
-                self.resolve_implementation(generics, of_trait, &self_ty, item.id, impl_items);
+                self.with_assoc_types(impl_items, |this| {
+                    this.resolve_implementation(generics, of_trait, &self_ty, item.id, impl_items)
+                });
             }
 
             ItemKind::Trait(.., ref generics, ref bounds, ref trait_items) => {
                 // Create a new rib for the trait-wide type parameters.
-                self.with_generic_param_rib(generics, ItemRibKind(HasGenericParams::Yes), |this| {
+                let kind = ItemRibKind(HasGenericParams::Yes(generics.span));
+                self.with_generic_param_rib(generics, kind, |this| {
                     let local_def_id = this.r.local_def_id(item.id).to_def_id();
                     this.with_self_rib(Res::SelfTy(Some(local_def_id), None), |this| {
                         this.visit_generics(generics);
@@ -903,14 +1111,17 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
                         };
 
                         for item in trait_items {
-                            this.with_trait_items(trait_items, |this| {
+                            this.with_assoc_types(trait_items, |this| {
                                 match &item.kind {
                                     AssocItemKind::Const(_, ty, default) => {
                                         this.visit_ty(ty);
                                         // Only impose the restrictions of `ConstRibKind` for an
                                         // actual constant expression in a provided default.
                                         if let Some(expr) = default {
-                                            this.with_constant_rib(|this| this.visit_expr(expr));
+                                            this.with_constant_rib(
+                                                Some(ConstantItemKind::Const),
+                                                |this| this.visit_expr(expr),
+                                            );
                                         }
                                     }
                                     AssocItemKind::Fn(_, _, generics, _) => {
@@ -931,7 +1142,8 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
 
             ItemKind::TraitAlias(ref generics, ref bounds) => {
                 // Create a new rib for the trait-wide type parameters.
-                self.with_generic_param_rib(generics, ItemRibKind(HasGenericParams::Yes), |this| {
+                let kind = ItemRibKind(HasGenericParams::Yes(generics.span));
+                self.with_generic_param_rib(generics, kind, |this| {
                     let local_def_id = this.r.local_def_id(item.id).to_def_id();
                     this.with_self_rib(Res::SelfTy(Some(local_def_id), None), |this| {
                         this.visit_generics(generics);
@@ -948,10 +1160,14 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
 
             ItemKind::Static(ref ty, _, ref expr) | ItemKind::Const(_, ref ty, ref expr) => {
                 debug!("resolve_item ItemKind::Const");
+                let kind = match item.kind {
+                    ItemKind::Static(..) => ConstantItemKind::Static,
+                    _ => ConstantItemKind::Const,
+                };
                 self.with_item_rib(HasGenericParams::No, |this| {
                     this.visit_ty(ty);
                     if let Some(expr) = expr {
-                        this.with_constant_rib(|this| this.visit_expr(expr));
+                        this.with_constant_rib(Some(kind), |this| this.visit_expr(expr));
                     }
                 });
             }
@@ -968,7 +1184,7 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
         }
     }
 
-    fn with_generic_param_rib<'c, F>(&'c mut self, generics: &'c Generics, kind: RibKind<'a>, f: F)
+    fn with_generic_param_rib<F>(&mut self, generics: &'ast Generics, kind: RibKind<'a>, f: F)
     where
         F: FnOnce(&mut Self),
     {
@@ -976,6 +1192,12 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
         let mut function_type_rib = Rib::new(kind);
         let mut function_value_rib = Rib::new(kind);
         let mut seen_bindings = FxHashMap::default();
+        // The lifetimes of an enclosing item can't be used in a nested one.
+        let outer_lifetime_params = match kind {
+            ItemRibKind(_) => take(&mut self.diagnostic_metadata.current_lifetime_params),
+            _ => Vec::new(),
+        };
+        let lifetime_params_len = self.diagnostic_metadata.current_lifetime_params.len();
 
         // We also can't shadow bindings from the parent item
         if let AssocItemRibKind = kind {
@@ -993,6 +1215,7 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
 
         for param in &generics.params {
             if let GenericParamKind::Lifetime { .. } = param.kind {
+                self.diagnostic_metadata.current_lifetime_params.push(param.ident);
                 continue;
             }
 
@@ -1030,11 +1253,17 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
 
         self.ribs[ValueNS].push(function_value_rib);
         self.ribs[TypeNS].push(function_type_rib);
+        self.diagnostic_metadata.current_generics.push(generics);
 
         f(self);
 
+        self.diagnostic_metadata.current_generics.pop();
         self.ribs[TypeNS].pop();
         self.ribs[ValueNS].pop();
+        self.diagnostic_metadata.current_lifetime_params.truncate(lifetime_params_len);
+        if let ItemRibKind(_) = kind {
+            self.diagnostic_metadata.current_lifetime_params = outer_lifetime_params;
+        }
     }
 
     fn with_label_rib(&mut self, kind: RibKind<'a>, f: impl FnOnce(&mut Self)) {
@@ -1048,10 +1277,10 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
         self.with_rib(ValueNS, kind, |this| this.with_rib(TypeNS, kind, f))
     }
 
-    fn with_constant_rib(&mut self, f: impl FnOnce(&mut Self)) {
+    fn with_constant_rib(&mut self, kind: Option<ConstantItemKind>, f: impl FnOnce(&mut Self)) {
         debug!("with_constant_rib");
-        self.with_rib(ValueNS, ConstantItemRibKind, |this| {
-            this.with_label_rib(ConstantItemRibKind, f);
+        self.with_rib(ValueNS, ConstantItemRibKind(kind), |this| {
+            this.with_label_rib(ConstantItemRibKind(kind), f);
         });
     }
 
@@ -1072,15 +1301,16 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
         result
     }
 
-    /// When evaluating a `trait` use its associated types' idents for suggestionsa in E0412.
-    fn with_trait_items<T>(
+    /// When evaluating a `trait` or an `impl` use its associated types' idents for suggestions
+    /// in E0412.
+    fn with_assoc_types<T>(
         &mut self,
-        trait_items: &Vec<P<AssocItem>>,
+        items: &[P<AssocItem>],
         f: impl FnOnce(&mut Self) -> T,
     ) -> T {
-        let trait_assoc_types = replace(
-            &mut self.diagnostic_metadata.current_trait_assoc_types,
-            trait_items
+        let assoc_types = replace(
+            &mut self.diagnostic_metadata.current_assoc_types,
+            items
                 .iter()
                 .filter_map(|item| match &item.kind {
                     AssocItemKind::TyAlias(_, _, bounds, _) if bounds.is_empty() => {
@@ -1091,7 +1321,7 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
                 .collect(),
         );
         let result = f(self);
-        self.diagnostic_metadata.current_trait_assoc_types = trait_assoc_types;
+        self.diagnostic_metadata.current_assoc_types = assoc_types;
         result
     }
 
@@ -1158,7 +1388,8 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
     ) {
         debug!("resolve_implementation");
         // If applicable, create a rib for the type parameters.
-        self.with_generic_param_rib(generics, ItemRibKind(HasGenericParams::Yes), |this| {
+        let kind = ItemRibKind(HasGenericParams::Yes(generics.span));
+        self.with_generic_param_rib(generics, kind, |this| {
             // Dummy self type for better errors if `Self` is used in the trait path.
             this.with_self_rib(Res::SelfTy(None, None), |this| {
                 // Resolve the trait reference, if necessary.
@@ -1171,6 +1402,9 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
                         }
                         // Resolve the self type.
                         this.visit_ty(self_type);
+                        if opt_trait_reference.is_none() {
+                            this.record_new_fns(self_type, impl_items);
+                        }
                         // Resolve the generic parameters.
                         this.visit_generics(generics);
                         // Resolve the items within the impl.
@@ -1191,9 +1425,16 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
                                                 |n, s| ConstNotMemberOfTrait(n, s),
                                             );
 
-                                            this.with_constant_rib(|this| {
-                                                visit::walk_assoc_item(this, item, AssocCtxt::Impl)
-                                            });
+                                            this.with_constant_rib(
+                                                Some(ConstantItemKind::Const),
+                                                |this| {
+                                                    visit::walk_assoc_item(
+                                                        this,
+                                                        item,
+                                                        AssocCtxt::Impl,
+                                                    )
+                                                },
+                                            );
                                         }
                                         AssocItemKind::Fn(_, _, generics, _) => {
                                             // We also need a new scope for the impl item type parameters.
@@ -1297,6 +1538,66 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
 
         // Resolve the pattern.
         self.resolve_pattern_top(&local.pat, PatternSource::Let);
+
+        if let Some(candidate) = self.const_candidate_local(local) {
+            self.r.const_candidate_locals.insert(local.pat.id, candidate);
+        }
+    }
+
+    /// Returns the binding introduced by `local` if it is a plain immutable binding whose
+    /// initializer only consists of literals, operators and constants.
+    fn const_candidate_local(&self, local: &Local) -> Option<ConstCandidateLocal> {
+        let ident = match local.pat.kind {
+            PatKind::Ident(BindingMode::ByValue(Mutability::Not), ident, None) => ident,
+            _ => return None,
+        };
+        let init = local.init.as_ref()?;
+        if local.span.from_expansion() || !local.attrs.is_empty() || !self.is_const_expr(init) {
+            return None;
+        }
+        let lit_ty = match init.kind {
+            ExprKind::Lit(ref lit) => match lit.kind {
+                LitKind::Int(_, LitIntType::Signed(ty)) => Some(ty.name_str()),
+                LitKind::Int(_, LitIntType::Unsigned(ty)) => Some(ty.name_str()),
+                LitKind::Float(_, LitFloatType::Suffixed(ty)) => Some(ty.name_str()),
+                LitKind::Bool(_) => Some("bool"),
+                LitKind::Char(_) => Some("char"),
+                LitKind::Byte(_) => Some("u8"),
+                LitKind::Str(..) => Some("&str"),
+                _ => None,
+            },
+            _ => None,
+        };
+        Some(ConstCandidateLocal {
+            ident,
+            let_span: local.span.with_hi(init.span.lo()),
+            ty_span: local.ty.as_ref().map(|ty| ty.span),
+            lit_ty,
+        })
+    }
+
+    /// Whether `expr` could be evaluated at compile time, judging only by its shape and by what
+    /// the paths in it resolved to.
+    fn is_const_expr(&self, expr: &Expr) -> bool {
+        match expr.kind {
+            ExprKind::Lit(_) => true,
+            ExprKind::Paren(ref inner) | ExprKind::Unary(UnOp::Neg | UnOp::Not, ref inner) => {
+                self.is_const_expr(inner)
+            }
+            ExprKind::Binary(_, ref lhs, ref rhs) => {
+                self.is_const_expr(lhs) && self.is_const_expr(rhs)
+            }
+            ExprKind::Tup(ref exprs) | ExprKind::Array(ref exprs) => {
+                exprs.iter().all(|expr| self.is_const_expr(expr))
+            }
+            ExprKind::Path(None, _) => match self.r.partial_res_map.get(&expr.id) {
+                Some(partial_res) if partial_res.unresolved_segments() == 0 => {
+                    matches!(partial_res.base_res(), Res::Def(DefKind::Const, _))
+                }
+                _ => false,
+            },
+            _ => false,
+        }
     }
 
     /// build a map from pattern identifiers to binding-info's.
@@ -1421,8 +1722,19 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
     fn resolve_arm(&mut self, arm: &'ast Arm) {
         self.with_rib(ValueNS, NormalRibKind, |this| {
             this.resolve_pattern_top(&arm.pat, PatternSource::Match);
+            if let PatKind::Ident(BindingMode::ByValue(Mutability::Not), ident, None) =
+                arm.pat.kind
+            {
+                if let Some(Res::Local(_)) =
+                    this.r.partial_res_map.get(&arm.pat.id).map(|res| res.base_res())
+                {
+                    this.lint_binding_similar_to_variant(ident, arm.pat.id);
+                }
+            }
+            let scrutinee = this.diagnostic_metadata.current_match_scrutinee.take();
             walk_list!(this, visit_expr, &arm.guard);
             this.visit_expr(&arm.body);
+            this.diagnostic_metadata.current_match_scrutinee = scrutinee;
         });
     }
 
@@ -1469,6 +1781,9 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
         pat_src: PatternSource,
         bindings: &mut SmallVec<[(PatBoundCtx, FxHashSet<Ident>); 1]>,
     ) {
+        // The shorthand field patterns seen so far, by the span of their identifier, in case a
+        // binding they introduce is bound again and one of them should be renamed.
+        let mut shorthand_fields = FxHashMap::default();
         // Visit all direct subpatterns of this pattern.
         pat.walk(&mut |pat| {
             debug!("resolve_pattern pat={:?} node={:?}", pat, pat.kind);
@@ -1479,17 +1794,27 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
                     let has_sub = sub.is_some();
                     let res = self
                         .try_resolve_as_non_binding(pat_src, pat, bmode, ident, has_sub)
-                        .unwrap_or_else(|| self.fresh_binding(ident, pat.id, pat_src, bindings));
+                        .unwrap_or_else(|| {
+                            self.fresh_binding(ident, pat.id, pat_src, bindings, &shorthand_fields)
+                        });
                     self.r.record_partial_res(pat.id, PartialRes::new(res));
                 }
                 PatKind::TupleStruct(ref path, ..) => {
-                    self.smart_resolve_path(pat.id, None, path, PathSource::TupleStruct);
+                    let source = PathSource::TupleStruct(pat.span);
+                    self.smart_resolve_path(pat.id, None, path, source);
                 }
                 PatKind::Path(ref qself, ref path) => {
                     self.smart_resolve_path(pat.id, qself.as_ref(), path, PathSource::Pat);
                 }
-                PatKind::Struct(ref path, ..) => {
+                PatKind::Struct(ref path, ref fields, has_rest) => {
                     self.smart_resolve_path(pat.id, None, path, PathSource::Struct);
+                    self.check_tuple_variant_struct_pat(pat, path, fields, has_rest);
+                    for field in fields.iter().filter(|field| field.is_shorthand) {
+                        if let PatKind::Ident(bmode, ident, None) = field.pat.kind {
+                            let span = ident.normalize_to_macro_rules().span;
+                            shorthand_fields.insert(span, (field.pat.span, bmode));
+                        }
+                    }
                 }
                 PatKind::Or(ref ps) => {
                     // Add a new set of bindings to the stack. `Or` here records that when a
@@ -1528,6 +1853,7 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
         pat_id: NodeId,
         pat_src: PatternSource,
         bindings: &mut SmallVec<[(PatBoundCtx, FxHashSet<Ident>); 1]>,
+        shorthand_fields: &FxHashMap<Span, (Span, BindingMode)>,
     ) -> Res {
         // Add the binding to the local ribs, if it doesn't already exist in the bindings map.
         // (We must not add it if it's in the bindings map because that breaks the assumptions
@@ -1544,13 +1870,17 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
         if already_bound_and {
             // Overlap in a product pattern somewhere; report an error.
             use ResolutionError::*;
+            let name = ident.as_str();
             let error = match pat_src {
                 // `fn f(a: u8, a: u8)`:
-                PatternSource::FnParam => IdentifierBoundMoreThanOnceInParameterList,
+                PatternSource::FnParam => IdentifierBoundMoreThanOnceInParameterList(&name),
                 // `Variant(a, a)`:
-                _ => IdentifierBoundMoreThanOnceInSamePattern,
+                _ => {
+                    let rename = self.shorthand_field_rename(ident, bindings, shorthand_fields);
+                    IdentifierBoundMoreThanOnceInSamePattern(&name, rename)
+                }
             };
-            self.r.report_error(ident.span, error(&ident.as_str()));
+            self.r.report_error(ident.span, error);
         }
 
         // Record as bound if it's valid:
@@ -1573,6 +1903,37 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
         }
     }
 
+    /// When `ident` is bound again in a product pattern, and either of its bindings is a shorthand
+    /// field pattern, finds the replacement of that field pattern binding the field to a fresh name
+    /// instead, e.g. `a: a_2` for `S { a }`.
+    fn shorthand_field_rename(
+        &self,
+        ident: Ident,
+        bindings: &SmallVec<[(PatBoundCtx, FxHashSet<Ident>); 1]>,
+        shorthand_fields: &FxHashMap<Span, (Span, BindingMode)>,
+    ) -> Option<(Span, String)> {
+        let first = bindings.iter().rev().find_map(|(_, set)| set.get(&ident))?;
+        let (span, bmode) = shorthand_fields
+            .get(&ident.span)
+            .or_else(|| shorthand_fields.get(&first.span))?;
+        let is_bound = |name: Symbol| {
+            bindings.iter().any(|(_, set)| set.iter().any(|binding| binding.name == name))
+                || self.ribs[ValueNS]
+                    .iter()
+                    .any(|rib| rib.bindings.keys().any(|binding| binding.name == name))
+        };
+        let fresh_name = (2..)
+            .map(|i| Symbol::intern(&format!("{}_{}", ident, i)))
+            .find(|&name| !is_bound(name))?;
+        let bmode = match bmode {
+            BindingMode::ByValue(Mutability::Not) => "",
+            BindingMode::ByValue(Mutability::Mut) => "mut ",
+            BindingMode::ByRef(Mutability::Not) => "ref ",
+            BindingMode::ByRef(Mutability::Mut) => "ref mut ",
+        };
+        Some((*span, format!("{}: {}{}", ident, bmode, fresh_name)))
+    }
+
     fn innermost_rib_bindings(&mut self, ns: Namespace) -> &mut IdentMap<Res> {
         &mut self.ribs[ns].last_mut().unwrap().bindings
     }
@@ -1658,7 +2019,7 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
         path: &Path,
         source: PathSource<'ast>,
     ) {
-        self.smart_resolve_path_fragment(
+        let partial_res = self.smart_resolve_path_fragment(
             id,
             qself,
             &Segment::from_path(path),
@@ -1666,6 +2027,59 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
             source,
             CrateLint::SimplePath(id),
         );
+        if qself.is_none() {
+            self.lint_spelled_out_self_type(id, path, partial_res);
+        }
+    }
+
+    /// Records what `path` resolves to in each namespace and the names in scope around it, for
+    /// `-Z record-resolution-scopes`.
+    fn record_scope_snapshot(&mut self, id: NodeId, path: &[Segment], span: Span) {
+        if self.r.scope_snapshots.is_none() || id == DUMMY_NODE_ID {
+            return;
+        }
+        let mut res = PerNS::default();
+        for &ns in &[TypeNS, ValueNS, MacroNS] {
+            let (parent_scope, ribs) = (&self.parent_scope, Some(&self.ribs));
+            res[ns] = self.r.speculative_resolve_path(path, ns, parent_scope, span, ribs, false);
+        }
+        let sess = self.r.session;
+        let snapshot_ribs = |ribs: &[Rib<'_>]| -> Vec<RibSnapshot> {
+            ribs.iter()
+                .rev()
+                .take_while(|rib| !matches!(rib.kind, ModuleRibKind(_)))
+                .map(|rib| {
+                    let mut bindings =
+                        rib.bindings.iter().map(|(&ident, &res)| (ident, res)).collect::<Vec<_>>();
+                    bindings.sort_by_key(|(ident, _)| ident.span.lo());
+                    RibSnapshot { kind: rib.kind.descr(sess), bindings }
+                })
+                .collect()
+        };
+        let ribs = PerNS {
+            type_ns: snapshot_ribs(&self.ribs[TypeNS]),
+            value_ns: snapshot_ribs(&self.ribs[ValueNS]),
+            macro_ns: snapshot_ribs(&self.ribs[MacroNS]),
+        };
+        let mut labels = Vec::new();
+        for (i, rib) in self.label_ribs.iter().enumerate().rev() {
+            if self.is_label_valid_from_rib(i) {
+                let mut rib_labels = rib.bindings.keys().copied().collect::<Vec<_>>();
+                rib_labels.sort_by_key(|label| label.span.lo());
+                labels.extend(rib_labels);
+            }
+        }
+        let snapshot = ScopeSnapshot {
+            span,
+            resolved: None,
+            res,
+            module: self.parent_scope.module.normal_ancestor_id,
+            ribs,
+            labels,
+        };
+        if let Some(snapshots) = &mut self.r.scope_snapshots {
+            snapshots.insert(id, snapshot);
+        }
     }
 
     fn smart_resolve_path_fragment(
@@ -1679,20 +2093,64 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
     ) -> PartialRes {
         let ns = source.namespace();
         let is_expected = &|res| source.is_expected(res);
+        self.record_scope_snapshot(id, path, span);
+        self.r.resolution_trace.clear();
 
         let report_errors = |this: &mut Self, res: Option<Res>| {
-            let (err, candidates) = this.smart_resolve_report_errors(path, span, source, res);
+            let resolution_trace = take(&mut this.r.resolution_trace);
+            let (mut err, mut candidates) =
+                this.smart_resolve_report_errors(path, span, source, res);
+            for note in resolution_trace {
+                err.note(&note);
+            }
+            this.r.record_unresolved_name(path, span, ns, path.len() - 1, &candidates);
 
             let def_id = this.parent_scope.module.normal_ancestor_id;
             let instead = res.is_some();
             let suggestion =
-                if res.is_none() { this.report_missing_type_error(path) } else { None };
+                if res.is_none() { this.report_missing_type_error(path, source) } else { None };
+            let glob_import = if res.is_none() {
+                this.suggest_qualified_variant(&mut err, path, span, source, &candidates)
+            } else {
+                None
+            };
+            let alloc_import = match path {
+                [segment] if res.is_none() && candidates.is_empty() => {
+                    this.unlinked_alloc_item(segment.ident, ns)
+                }
+                _ => None,
+            };
+            let assoc_consts = match path {
+                [_] if res.is_none() && ns == ValueNS => {
+                    Some((span, this.take_primitive_assoc_consts(&mut candidates)))
+                        .filter(|(_, paths)| !paths.is_empty())
+                }
+                _ => None,
+            };
+
+            this.r.use_injections.push(UseError {
+                err,
+                candidates,
+                def_id,
+                instead,
+                suggestion,
+                glob_import,
+                alloc_import,
+                assoc_consts,
+            });
 
-            this.r.use_injections.push(UseError { err, candidates, def_id, instead, suggestion });
+            if res.is_none() {
+                this.record_unresolved_placeholder(path, source);
+            }
 
             PartialRes::new(Res::Err)
         };
 
+        // The segment a failed module path stopped resolving at is the one the error points to.
+        let failing_segment_at = |err_span: Span| {
+            path.iter().position(|segment| segment.ident.span == err_span).unwrap_or(path.len() - 1)
+        };
+
         // For paths originating from calls (like in `HashMap::new()`), tries
         // to enrich the plain `failed to resolve: ...` message with hints
         // about possible missing imports.
@@ -1706,20 +2164,29 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
             // Before we start looking for candidates, we have to get our hands
             // on the type user is trying to perform invocation on; basically:
             // we're transforming `HashMap::new` into just `HashMap`
-            let path = if let Some((_, path)) = path.split_last() {
-                path
+            let ty_path = if let Some((_, ty_path)) = path.split_last() {
+                ty_path
             } else {
                 return Some(parent_err);
             };
 
             let (mut err, candidates) =
-                this.smart_resolve_report_errors(path, span, PathSource::Type, None);
+                this.smart_resolve_report_errors(ty_path, span, PathSource::Type, None);
+            let alloc_import = match ty_path {
+                [segment] if candidates.is_empty() => {
+                    this.unlinked_alloc_item(segment.ident, TypeNS)
+                }
+                _ => None,
+            };
 
-            if candidates.is_empty() {
+            if candidates.is_empty() && alloc_import.is_none() {
                 err.cancel();
                 return Some(parent_err);
             }
 
+            let failing_segment = failing_segment_at(parent_err.span);
+            this.r.record_unresolved_name(path, span, ns, failing_segment, &candidates);
+
             // There are two different error messages user might receive at
             // this point:
             // - E0412 cannot find type `{}` in this scope
@@ -1752,6 +2219,9 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
                 def_id,
                 instead: false,
                 suggestion: None,
+                glob_import: None,
+                alloc_import,
+                assoc_consts: None,
             });
 
             // We don't return `Some(parent_err)` here, because the error will
@@ -1809,6 +2279,8 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
 
             Err(err) => {
                 if let Some(err) = report_errors_for_call(self, err) {
+                    let failing_segment = failing_segment_at(err.span);
+                    self.r.record_unresolved_name(path, span, ns, failing_segment, &[]);
                     self.r.report_error(err.span, err.node);
                 }
 
@@ -1827,6 +2299,64 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
         partial_res
     }
 
+    /// After an unresolved local-like name has been reported, record `Res::Err` for it in the
+    /// innermost value rib, so that later uses of the same name in this scope resolve silently
+    /// to the error placeholder instead of each emitting their own follow-on error.
+    fn record_unresolved_placeholder(&mut self, path: &[Segment], source: PathSource<'_>) {
+        let ident = match (path, source) {
+            ([segment], PathSource::Expr(_))
+                if !source.is_call() && !segment.ident.span.from_expansion() =>
+            {
+                segment.ident.normalize_to_macro_rules()
+            }
+            _ => return,
+        };
+        if let Some(rib) = self.ribs[ValueNS].last_mut() {
+            // Generic parameter ribs are looked up hygienically and must stay untouched.
+            if !rib.kind.contains_params() {
+                rib.bindings.entry(ident).or_insert(Res::Err);
+            }
+        }
+    }
+
+    /// Collects what is needed to suggest turning the free function being visited into a closure,
+    /// unless it has generics, qualifiers, attributes or `impl Trait` types closures can't have.
+    fn nested_fn_item(&self, sig: &FnSig, body: &Block, span: Span) -> Option<NestedFnItem> {
+        let item = self.diagnostic_metadata.current_item?;
+        let generics = match &item.kind {
+            ItemKind::Fn(_, _, generics, _) => generics,
+            _ => return None,
+        };
+        let is_impl_trait = |ty: &P<Ty>| matches!(ty.kind, TyKind::ImplTrait(..));
+        let ret_ty_span = match &sig.decl.output {
+            FnRetTy::Ty(ty) if is_impl_trait(ty) => return None,
+            FnRetTy::Ty(ty) => Some(ty.span),
+            FnRetTy::Default(_) => None,
+        };
+        if span.from_expansion()
+            || !item.attrs.is_empty()
+            || !matches!(item.vis.node, VisibilityKind::Inherited)
+            || !generics.params.is_empty()
+            || !generics.where_clause.predicates.is_empty()
+            || sig.header.has_qualifiers()
+            || sig.decl.c_variadic()
+            || sig.decl.inputs.iter().any(|param| is_impl_trait(&param.ty))
+        {
+            return None;
+        }
+        let params_span = match (sig.decl.inputs.first(), sig.decl.inputs.last()) {
+            (Some(first), Some(last)) => Some(first.span.to(last.span)),
+            _ => None,
+        };
+        Some(NestedFnItem {
+            ident: item.ident,
+            header_span: span.with_hi(body.span.lo()),
+            params_span,
+            ret_ty_span,
+            body_span: body.span,
+        })
+    }
+
     fn self_type_is_available(&mut self, span: Span) -> bool {
         let binding = self.resolve_ident_in_lexical_scope(
             Ident::with_dummy_span(kw::SelfUpper),
@@ -1978,8 +2508,9 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
             PathResult::Module(ModuleOrUniformRoot::Module(module)) => {
                 PartialRes::new(module.res().unwrap())
             }
-            PathResult::Failed { is_error_from_last_segment: false, span, label, suggestion } => {
-                return Err(respan(span, ResolutionError::FailedToResolve { label, suggestion }));
+            PathResult::Failed { is_error_from_last_segment: false, span, failure, suggestion } => {
+                let error = ResolutionError::FailedToResolve { failure, suggestion };
+                return Err(respan(span, error));
             }
             PathResult::Module(..) | PathResult::Failed { .. } => return Ok(None),
             PathResult::Indeterminate => bug!("indeterminate path result in resolve_qpath"),
@@ -2029,6 +2560,19 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
         }
     }
 
+    fn with_closure_or_async_label_rib(&mut self, expr: &'ast Expr, f: impl FnOnce(&mut Self)) {
+        self.diagnostic_metadata.enclosing_closures.push(expr);
+        self.with_label_rib(ClosureOrAsyncRibKind, f);
+        self.diagnostic_metadata.enclosing_closures.pop();
+    }
+
+    fn with_enclosing_breakable(&mut self, expr: &'ast Expr, f: impl FnOnce(&mut Self)) {
+        let label_depth = self.label_ribs.len();
+        self.diagnostic_metadata.enclosing_breakables.push((label_depth, expr));
+        f(self);
+        self.diagnostic_metadata.enclosing_breakables.pop();
+    }
+
     fn resolve_labeled_block(&mut self, label: Option<Label>, id: NodeId, block: &'ast Block) {
         self.with_resolved_label(label, id, |this| this.visit_block(block));
     }
@@ -2049,6 +2593,8 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
             self.ribs[ValueNS].push(Rib::new(NormalRibKind));
         }
 
+        self.diagnostic_metadata.enclosing_blocks.push(block);
+
         // Descend into the block.
         for stmt in &block.stmts {
             if let StmtKind::Item(ref item) = stmt.kind {
@@ -2063,6 +2609,8 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
             self.visit_stmt(stmt);
         }
 
+        self.diagnostic_metadata.enclosing_blocks.pop();
+
         // Move back up.
         self.parent_scope.module = orig_module;
         for _ in 0..num_macro_definition_ribs {
@@ -2095,7 +2643,7 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
             }
 
             ExprKind::Break(Some(label), _) | ExprKind::Continue(Some(label)) => {
-                if let Some(node_id) = self.resolve_label(label.ident) {
+                if let Some(node_id) = self.resolve_label(label.ident, expr) {
                     // Since this res is a label, it is never read.
                     self.r.label_res_map.insert(expr.id, node_id);
                     self.diagnostic_metadata.unused_labels.remove(&node_id);
@@ -2105,11 +2653,23 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
                 visit::walk_expr(self, expr);
             }
 
+            ExprKind::Break(None, Some(ref value)) => {
+                self.resolve_expr(value, Some(expr));
+            }
+
             ExprKind::Let(ref pat, ref scrutinee) => {
                 self.visit_expr(scrutinee);
                 self.resolve_pattern_top(pat, PatternSource::Let);
             }
 
+            ExprKind::Match(ref scrutinee, ref arms) => {
+                self.visit_expr(scrutinee);
+                let prev =
+                    replace(&mut self.diagnostic_metadata.current_match_scrutinee, Some(scrutinee));
+                walk_list!(self, visit_arm, arms);
+                self.diagnostic_metadata.current_match_scrutinee = prev;
+            }
+
             ExprKind::If(ref cond, ref then, ref opt_else) => {
                 self.with_rib(ValueNS, NormalRibKind, |this| {
                     this.visit_expr(cond);
@@ -2120,14 +2680,18 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
                 }
             }
 
-            ExprKind::Loop(ref block, label) => self.resolve_labeled_block(label, expr.id, &block),
+            ExprKind::Loop(ref block, label) => self.with_enclosing_breakable(expr, |this| {
+                this.resolve_labeled_block(label, expr.id, &block);
+            }),
 
             ExprKind::While(ref cond, ref block, label) => {
-                self.with_resolved_label(label, expr.id, |this| {
-                    this.with_rib(ValueNS, NormalRibKind, |this| {
-                        this.visit_expr(cond);
-                        this.visit_block(block);
-                    })
+                self.with_enclosing_breakable(expr, |this| {
+                    this.with_resolved_label(label, expr.id, |this| {
+                        this.with_rib(ValueNS, NormalRibKind, |this| {
+                            this.visit_expr(cond);
+                            this.visit_block(block);
+                        })
+                    });
                 });
             }
 
@@ -2135,11 +2699,15 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
                 self.visit_expr(iter_expr);
                 self.with_rib(ValueNS, NormalRibKind, |this| {
                     this.resolve_pattern_top(pat, PatternSource::For);
-                    this.resolve_labeled_block(label, expr.id, block);
+                    this.with_enclosing_breakable(expr, |this| {
+                        this.resolve_labeled_block(label, expr.id, block);
+                    });
                 });
             }
 
-            ExprKind::Block(ref block, label) => self.resolve_labeled_block(label, block.id, block),
+            ExprKind::Block(ref block, label) => self.with_enclosing_breakable(expr, |this| {
+                this.resolve_labeled_block(label, block.id, block);
+            }),
 
             // Equivalent to `visit::walk_expr` + passing some context to children.
             ExprKind::Field(ref subexpression, _) => {
@@ -2160,6 +2728,18 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
                     self.resolve_expr(argument, None);
                 }
             }
+            // `foo<A, B>(x)` can get parsed as `foo < A`, only the type on the right is of
+            // interest.
+            ExprKind::Binary(op, ref lhs, ref rhs) if op.node == BinOpKind::Lt => {
+                self.resolve_expr(lhs, None);
+                self.resolve_expr(rhs, Some(expr));
+            }
+            ExprKind::Assign(ref lhs, ref rhs, _) => {
+                let prev = replace(&mut self.diagnostic_metadata.in_assignment, Some(expr));
+                self.visit_expr(lhs);
+                self.diagnostic_metadata.in_assignment = prev;
+                self.visit_expr(rhs);
+            }
             ExprKind::Type(ref type_expr, _) => {
                 self.diagnostic_metadata.current_type_ascription.push(type_expr.span);
                 visit::walk_expr(self, expr);
@@ -2170,7 +2750,7 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
             // closure are detected as upvars rather than normal closure arg usages.
             ExprKind::Closure(_, Async::Yes { .. }, _, ref fn_decl, ref body, _span) => {
                 self.with_rib(ValueNS, NormalRibKind, |this| {
-                    this.with_label_rib(ClosureOrAsyncRibKind, |this| {
+                    this.with_closure_or_async_label_rib(expr, |this| {
                         // Resolve arguments:
                         this.resolve_params(&fn_decl.inputs);
                         // No need to resolve return type --
@@ -2188,7 +2768,10 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
                 });
             }
             ExprKind::Async(..) | ExprKind::Closure(..) => {
-                self.with_label_rib(ClosureOrAsyncRibKind, |this| visit::walk_expr(this, expr));
+                self.with_closure_or_async_label_rib(expr, |this| visit::walk_expr(this, expr));
+            }
+            ExprKind::Err => {
+                self.take_new_operator_error(expr.span);
             }
             _ => {
                 visit::walk_expr(self, expr);
@@ -2340,6 +2923,7 @@ impl<'a, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
 
 impl<'a> Resolver<'a> {
     pub(crate) fn late_resolve_crate(&mut self, krate: &Crate) {
+        self.resolve_local_impls();
         let mut late_resolution_visitor = LateResolutionVisitor::new(self);
         visit::walk_crate(&mut late_resolution_visitor, krate);
         for (id, span) in late_resolution_visitor.diagnostic_metadata.unused_labels.iter() {
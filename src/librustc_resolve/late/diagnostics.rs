@@ -1,38 +1,186 @@
-use crate::diagnostics::{ImportSuggestion, LabelSuggestion, TypoSuggestion};
+use crate::diagnostics::{is_typo_candidate, sort_typo_candidates};
+use crate::diagnostics::{ImportSuggestion, LabelSuggestion, TypoSuggestion, MAX_TYPO_CANDIDATES};
+use crate::heuristics::{self, AssocSuggestion, PathSnapshot};
 use crate::late::lifetimes::{ElisionFailureInfo, LifetimeContext};
-use crate::late::{LateResolutionVisitor, RibKind};
+use crate::late::{AliasPossibility, HasGenericParams, LateResolutionVisitor, RibKind};
 use crate::path_names_to_string;
-use crate::{CrateLint, Module, ModuleKind, ModuleOrUniformRoot};
-use crate::{PathResult, PathSource, Segment};
-
-use rustc_ast::ast::{self, Expr, ExprKind, Item, ItemKind, NodeId, Path, Ty, TyKind};
-use rustc_ast::util::lev_distance::find_best_match_for_name;
+use crate::imports::{ImportKind, NameResolution};
+use crate::{CrateLint, Module, ModuleKind, ModuleOrUniformRoot, NameBindingKind, ParentScope};
+use crate::{ImplMethod, NewOperatorError, PathResult, PathSource, ResolutionError, Segment};
+
+use rustc_ast::ast::{self, Expr, ExprKind, Item, ItemKind, NodeId, Path, PatKind, Stmt, StmtKind};
+use rustc_ast::ast::{AssocItem, AssocItemKind, Block, FieldPat, GenericParamKind};
+use rustc_ast::ast::{GenericBound, Local, Pat, TraitBoundModifier, Ty, TyKind, WherePredicate};
+use rustc_ast::ptr::P;
+use rustc_ast::util::lev_distance::{find_best_match_for_name, lev_distance};
 use rustc_data_structures::fx::FxHashSet;
-use rustc_errors::{pluralize, struct_span_err, Applicability, DiagnosticBuilder};
+use rustc_errors::{pluralize, struct_span_err, Applicability, DiagnosticBuilder, StashKey};
 use rustc_hir as hir;
 use rustc_hir::def::Namespace::{self, *};
-use rustc_hir::def::{self, CtorKind, DefKind};
+use rustc_hir::def::{self, CtorKind, CtorOf, DefKind, PartialRes};
 use rustc_hir::def_id::{DefId, CRATE_DEF_INDEX};
+use rustc_hir::intravisit::{NestedVisitorMap, Visitor};
 use rustc_hir::PrimTy;
+use rustc_middle::hir::map::Map;
+use rustc_middle::ty::DefIdTree;
 use rustc_session::config::nightly_options;
+use rustc_session::lint::{self, BuiltinLintDiagnostics};
 use rustc_span::hygiene::MacroKind;
-use rustc_span::symbol::{kw, sym, Ident};
-use rustc_span::Span;
+use rustc_span::edition::Edition;
+use rustc_span::symbol::{kw, sym, Ident, Symbol};
+use rustc_span::{BytePos, Span};
+use std::fmt;
+use std::iter;
+use std::mem::take;
+use std::ptr;
 
 use log::debug;
 
 type Res = def::Res<ast::NodeId>;
 
-/// A field or associated item from self type suggested in case of resolution failure.
-enum AssocSuggestion {
-    Field,
-    MethodWithSelf,
-    AssocItem,
+/// The number of items of a module used as a value that are suggested in its place.
+const MAX_SUGGESTED_MODULE_ITEMS: usize = 3;
+
+/// A construct introduced in a later edition that parses as an ordinary path when written in an
+/// earlier one, e.g. `async { .. }` in Rust 2015 is a struct expression of type `async`.
+struct GatedConstruct {
+    keyword: Symbol,
+    position: GatedPosition,
+    /// The first edition in which `keyword` introduces the construct.
+    edition: Edition,
+    /// The feature gate the construct needs on top of the edition, if it's still unstable.
+    feature: Option<Symbol>,
+    /// The message explaining which edition the construct needs.
+    slug: &'static str,
+}
+
+/// Where the unresolved `keyword` has to appear for it to be taken for a `GatedConstruct`.
+enum GatedPosition {
+    /// `keyword { .. }`, parsed as a struct expression.
+    Block,
+    /// `keyword |args| body`, parsed as a bitwise or.
+    Closure,
+    /// `keyword` anywhere in an expression.
+    Expr,
+    /// `keyword` as a type on its own.
+    Type,
+}
+
+static GATED_CONSTRUCTS: &[GatedConstruct] = &[
+    GatedConstruct {
+        keyword: kw::Async,
+        position: GatedPosition::Block,
+        edition: Edition::Edition2018,
+        feature: None,
+        slug: "async-block-2018",
+    },
+    GatedConstruct {
+        keyword: kw::Async,
+        position: GatedPosition::Closure,
+        edition: Edition::Edition2018,
+        feature: Some(sym::async_closure),
+        slug: "async-closure-2018",
+    },
+    GatedConstruct {
+        keyword: kw::Try,
+        position: GatedPosition::Block,
+        edition: Edition::Edition2018,
+        feature: Some(sym::try_blocks),
+        slug: "try-block-2018",
+    },
+    GatedConstruct {
+        keyword: kw::Await,
+        position: GatedPosition::Expr,
+        edition: Edition::Edition2018,
+        feature: None,
+        slug: "await-2018",
+    },
+    GatedConstruct {
+        keyword: kw::Dyn,
+        position: GatedPosition::Type,
+        edition: Edition::Edition2018,
+        feature: None,
+        slug: "dyn-2018",
+    },
+];
+
+/// Returns the construct that the unresolved `path` was most likely meant to be, if it's a
+/// keyword of a later edition in a position where that edition would have accepted it.
+fn gated_construct(
+    path: &[Segment],
+    source: PathSource<'_>,
+    span: Span,
+) -> Option<&'static GatedConstruct> {
+    let ident = match path {
+        [segment] => segment.ident,
+        _ => return None,
+    };
+    GATED_CONSTRUCTS.iter().find(|construct| {
+        ident.name == construct.keyword
+            && ident.span.edition() < construct.edition
+            && match (&construct.position, source) {
+                (GatedPosition::Block, PathSource::Struct)
+                | (GatedPosition::Expr, PathSource::Expr(_))
+                | (GatedPosition::Type, PathSource::Type) => true,
+                (GatedPosition::Closure, PathSource::Expr(Some(parent))) => match parent.kind {
+                    ExprKind::Binary(op, ref lhs, _) => {
+                        matches!(op.node, ast::BinOpKind::BitOr | ast::BinOpKind::Or)
+                            && lhs.span == span
+                    }
+                    _ => false,
+                },
+                _ => false,
+            }
+    })
 }
 
+/// Output functions and streams of other languages, and the macro Rust uses for them.
+static FOREIGN_OUTPUT_NAMES: &[(&str, &str)] = &[
+    ("printf", "print"),
+    ("puts", "println"),
+    ("putchar", "print"),
+    ("cout", "print"),
+    ("fprintf", "eprint"),
+    ("perror", "eprintln"),
+    ("cerr", "eprint"),
+];
+
+/// Items of the `alloc` crate that are usually reached through `std`, and their paths in `alloc`.
+static ALLOC_ITEMS: &[(&str, &str)] = &[
+    ("Arc", "sync::Arc"),
+    ("BTreeMap", "collections::BTreeMap"),
+    ("BTreeSet", "collections::BTreeSet"),
+    ("BinaryHeap", "collections::BinaryHeap"),
+    ("Box", "boxed::Box"),
+    ("Cow", "borrow::Cow"),
+    ("LinkedList", "collections::LinkedList"),
+    ("Rc", "rc::Rc"),
+    ("String", "string::String"),
+    ("ToOwned", "borrow::ToOwned"),
+    ("ToString", "string::ToString"),
+    ("Vec", "vec::Vec"),
+    ("VecDeque", "collections::VecDeque"),
+];
+
 crate enum MissingLifetimeSpot<'tcx> {
     Generics(&'tcx hir::Generics<'tcx>),
+    /// The generics of an impl, where a lifetime can only be introduced if the header of the impl
+    /// names it, here by naming its elided lifetime at `elided_span`, either a `&` or `'_`.
+    ImplHeader { generics: &'tcx hir::Generics<'tcx>, elided_span: Span },
     HigherRanked { span: Span, span_type: ForLifetimeSpanType },
+    /// An item that can't introduce lifetimes, in an impl that can't either.
+    Static,
+}
+
+impl<'tcx> MissingLifetimeSpot<'tcx> {
+    /// The generics that the missing lifetime would be introduced on.
+    fn generics(&self) -> Option<&'tcx hir::Generics<'tcx>> {
+        match *self {
+            MissingLifetimeSpot::Generics(generics)
+            | MissingLifetimeSpot::ImplHeader { generics, .. } => Some(generics),
+            MissingLifetimeSpot::HigherRanked { .. } | MissingLifetimeSpot::Static => None,
+        }
+    }
 }
 
 crate enum ForLifetimeSpanType {
@@ -97,6 +245,7 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
         source: PathSource<'_>,
         res: Option<Res>,
     ) -> (DiagnosticBuilder<'a>, Vec<ImportSuggestion>) {
+        let sess = self.r.session;
         let ident_span = path.last().map_or(span, |ident| ident.ident.span);
         let ns = source.namespace();
         let is_expected = &|res| source.is_expected(res);
@@ -106,10 +255,17 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
         let expected = source.descr_expected();
         let path_str = Segment::names_to_string(path);
         let item_str = path.last().unwrap().ident;
+        let gated_construct = gated_construct(path, source, span);
         let (base_msg, fallback_label, base_span, could_be_expr) = if let Some(res) = res {
             (
-                format!("expected {}, found {} `{}`", expected, res.descr(), path_str),
-                format!("not a {}", expected),
+                msg!(
+                    sess,
+                    "expected-found",
+                    expected = expected,
+                    found = res.descr(),
+                    path = path_str,
+                ),
+                msg!(sess, "not-a", expected = expected),
                 span,
                 match res {
                     Res::Def(DefKind::Fn, _) => {
@@ -134,9 +290,9 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
         } else {
             let item_span = path.last().unwrap().ident.span;
             let (mod_prefix, mod_str) = if path.len() == 1 {
-                (String::new(), "this scope".to_string())
+                (String::new(), msg!(sess, "this-scope"))
             } else if path.len() == 2 && path[0].ident.name == kw::PathRoot {
-                (String::new(), "the crate root".to_string())
+                (String::new(), msg!(sess, "the-crate-root"))
             } else {
                 let mod_path = &path[..path.len() - 1];
                 let mod_prefix =
@@ -148,11 +304,17 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
                 (mod_prefix, format!("`{}`", Segment::names_to_string(mod_path)))
             };
             (
-                format!("cannot find {} `{}` in {}{}", expected, item_str, mod_prefix, mod_str),
-                if path_str == "async" && expected.starts_with("struct") {
-                    "`async` blocks are only allowed in the 2018 edition".to_string()
-                } else {
-                    format!("not found in {}", mod_str)
+                msg!(
+                    sess,
+                    "cannot-find",
+                    expected = expected,
+                    name = item_str,
+                    prefix = mod_prefix,
+                    scope = mod_str,
+                ),
+                match gated_construct {
+                    Some(construct) => crate::messages::format_message(sess, construct.slug, &[]),
+                    None => msg!(sess, "not-found-in", scope = mod_str),
                 },
                 item_span,
                 false,
@@ -160,7 +322,30 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
         };
 
         let code = source.error_code(res.is_some());
-        let mut err = self.r.session.struct_span_err_with_code(base_span, &base_msg, code);
+        let teach_scopes = res.is_none()
+            && path.len() == 1
+            && (code == rustc_errors::error_code!(E0425)
+                || code == rustc_errors::error_code!(E0412))
+            && sess.teach(&code);
+        let mut err = sess.struct_span_err_with_code(base_span, &base_msg, code);
+        if teach_scopes {
+            let scopes = self.searched_scopes(ns);
+            err.note(&msg!(sess, "teach-scope-rules", name = item_str, scopes = scopes));
+        }
+
+        if let Some(construct) = gated_construct {
+            // Without a resolution the fallback label already says this.
+            if res.is_some() {
+                err.note(&crate::messages::format_message(sess, construct.slug, &[]));
+            }
+            if let Some(feature) = construct.feature {
+                if nightly_options::is_nightly_build()
+                    && !sess.features_untracked().enabled(feature)
+                {
+                    err.help(&msg!(sess, "enable-feature", feature = feature));
+                }
+            }
+        }
 
         // Emit help message for fake-self from other languages (e.g., `this` in Javascript).
         if ["this", "my"].contains(&&*item_str.as_str())
@@ -168,7 +353,7 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
         {
             err.span_suggestion_short(
                 span,
-                "you might have meant to use `self` here instead",
+                &msg!(sess, "fake-self"),
                 "self".to_string(),
                 Applicability::MaybeIncorrect,
             );
@@ -177,10 +362,7 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
         // Emit special messages for unresolved `Self` and `self`.
         if is_self_type(path, ns) {
             err.code(rustc_errors::error_code!(E0411));
-            err.span_label(
-                span,
-                "`Self` is only available in impls, traits, and type definitions".to_string(),
-            );
+            err.span_label(span, msg!(sess, "self-type-unavailable"));
             return (err, Vec::new());
         }
         if is_self_value(path, ns) {
@@ -188,27 +370,41 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
 
             err.code(rustc_errors::error_code!(E0424));
             err.span_label(span, match source {
-                PathSource::Pat => "`self` value is a keyword and may not be bound to variables or shadowed"
-                                   .to_string(),
-                _ => "`self` value is a keyword only available in methods with a `self` parameter"
-                     .to_string(),
+                PathSource::Pat => msg!(sess, "self-value-in-pattern"),
+                _ => msg!(sess, "self-value-unavailable"),
             });
             if let Some((fn_kind, span)) = &self.diagnostic_metadata.current_function {
                 // The current function has a `self' parameter, but we were unable to resolve
                 // a reference to `self`. This can only happen if the `self` identifier we
                 // are resolving came from a different hygiene context.
                 if fn_kind.decl().inputs.get(0).map(|p| p.is_self()).unwrap_or(false) {
-                    err.span_label(*span, "this function has a `self` parameter, but a macro invocation can only access identifiers it receives from parameters");
+                    err.span_label(*span, msg!(sess, "self-param-hygiene"));
                 } else {
-                    err.span_label(*span, "this function doesn't have a `self` parameter");
+                    err.span_label(*span, msg!(sess, "no-self-param"));
                 }
             }
             return (err, Vec::new());
         }
 
+        // A variant written in type position is most likely meant as its enum, which the path
+        // written before the variant names already.
+        let mut variant_enum_suggested = false;
+        if let (Some(Res::Def(DefKind::Variant, _)), PathSource::Type) = (res, source) {
+            err.note(&msg!(sess, "variants-are-not-types"));
+            if let Some(enum_path) = self.variant_enum_path(path, span) {
+                err.span_suggestion(
+                    span,
+                    &msg!(sess, "use-variant-enum"),
+                    enum_path,
+                    Applicability::MachineApplicable,
+                );
+                variant_enum_suggested = true;
+            }
+        }
+
         // Try to lookup name in more relaxed fashion for better error reporting.
         let ident = path.last().unwrap().ident;
-        let candidates = self
+        let mut candidates = self
             .r
             .lookup_import_candidates(ident, ns, &self.parent_scope, is_expected)
             .drain(..)
@@ -220,7 +416,10 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
             })
             .collect::<Vec<_>>();
         let crate_def_id = DefId::local(CRATE_DEF_INDEX);
-        if candidates.is_empty() && is_expected(Res::Def(DefKind::Enum, crate_def_id)) {
+        if candidates.is_empty()
+            && !variant_enum_suggested
+            && is_expected(Res::Def(DefKind::Enum, crate_def_id))
+        {
             let enum_candidates =
                 self.r.lookup_import_candidates(ident, ns, &self.parent_scope, is_enum_variant);
             let mut enum_candidates = enum_candidates
@@ -232,96 +431,258 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
             if !enum_candidates.is_empty() {
                 // Contextualize for E0412 "cannot find type", but don't belabor the point
                 // (that it's a variant) for E0573 "expected type, found variant".
-                let preamble = if res.is_none() {
-                    let others = match enum_candidates.len() {
-                        1 => String::new(),
-                        2 => " and 1 other".to_owned(),
-                        n => format!(" and {} others", n),
-                    };
-                    format!("there is an enum variant `{}`{}; ", enum_candidates[0].0, others)
-                } else {
-                    String::new()
+                let variant = &enum_candidates[0].0;
+                let msg = match (res, enum_candidates.len() - 1) {
+                    (Some(_), _) => msg!(sess, "use-variant-enum"),
+                    (None, 0) => msg!(sess, "variant-exists", variant = variant),
+                    (None, others) => msg!(
+                        sess,
+                        "variant-exists-among-others",
+                        variant = variant,
+                        others = others,
+                        s = pluralize!(others),
+                    ),
                 };
-                let msg = format!("{}try using the variant's enum", preamble);
 
-                err.span_suggestions(
-                    span,
-                    &msg,
-                    enum_candidates
-                        .into_iter()
-                        .map(|(_variant_path, enum_ty_path)| enum_ty_path)
-                        // Variants re-exported in prelude doesn't mean `prelude::v1` is the
-                        // type name!
-                        // FIXME: is there a more principled way to do this that
-                        // would work for other re-exports?
-                        .filter(|enum_ty_path| enum_ty_path != "std::prelude::v1")
-                        // Also write `Option` rather than `std::prelude::v1::Option`.
-                        .map(|enum_ty_path| {
-                            // FIXME #56861: DRY-er prelude filtering.
-                            enum_ty_path.trim_start_matches("std::prelude::v1::").to_owned()
-                        }),
-                    Applicability::MachineApplicable,
-                );
+                let enum_ty_paths = enum_candidates
+                    .into_iter()
+                    .map(|(_variant_path, enum_ty_path)| enum_ty_path)
+                    // Variants re-exported in prelude doesn't mean `prelude::v1` is the
+                    // type name!
+                    // FIXME: is there a more principled way to do this that
+                    // would work for other re-exports?
+                    .filter(|enum_ty_path| enum_ty_path != "std::prelude::v1")
+                    // Also write `Option` rather than `std::prelude::v1::Option`.
+                    .map(|enum_ty_path| {
+                        // FIXME #56861: DRY-er prelude filtering.
+                        enum_ty_path.trim_start_matches("std::prelude::v1::").to_owned()
+                    })
+                    .collect::<Vec<_>>();
+                // Candidate paths are written from the crate root, which may not be where
+                // the error is.
+                let all_resolve = enum_ty_paths
+                    .iter()
+                    .all(|ty_path| self.suggested_path_resolves(ty_path, span, is_expected));
+                let applicability = if all_resolve {
+                    Applicability::MachineApplicable
+                } else {
+                    Applicability::MaybeIncorrect
+                };
+                err.span_suggestions(span, &msg, enum_ty_paths.into_iter(), applicability);
+            }
+        }
+        // In the arms of a `match` on a value of a known enum, its own variants are what's meant.
+        let scrutinee_enum = match source {
+            PathSource::Pat | PathSource::TupleStruct(_) | PathSource::Struct => {
+                self.match_scrutinee_enum()
             }
+            _ => None,
+        };
+        if let Some((enum_module, _)) = scrutinee_enum {
+            let r = &*self.r;
+            let is_variant_of_enum = |candidate: &ImportSuggestion| {
+                candidate.did.and_then(|did| r.parent(did)) == enum_module.def_id()
+            };
+            if candidates.iter().any(is_variant_of_enum) {
+                candidates.retain(is_variant_of_enum);
+            }
+        }
+        // Imports only apply to the module they're written in, point at one in a nearby module.
+        if res.is_none() && path.len() == 1 {
+            self.note_import_in_nearby_module(&mut err, &mut candidates, ident, ns, is_expected);
+        }
+        if res.is_none() {
+            self.note_on_unresolved(&mut err, ident);
+        }
+        if path.len() == 1 {
+            self.note_disabled_prelude(&mut err, ident, ns, is_expected);
         }
+        // What was expected may be imported by a glob, shadowed by what was found instead.
+        if let (Some(res), [_]) = (res, path) {
+            if self.note_shadowed_glob(&mut err, &mut candidates, ident, ns, res, is_expected) {
+                err.span_label(base_span, fallback_label);
+                return (err, candidates);
+            }
+        }
+        // The item may be imported already, but under another name.
+        if res.is_none()
+            && path.len() == 1
+            && self.suggest_renamed_import(&mut err, &mut candidates, ident, ns, is_expected)
+        {
+            err.span_label(base_span, fallback_label);
+            return (err, candidates);
+        }
+        // The heuristics proposing fixes for the path don't exclude each other, the fixes they
+        // propose are collected and ranked before being offered as alternatives.
+        let lifetime_params = self.diagnostic_metadata.current_lifetime_params.clone();
+        let cx = PathSnapshot {
+            path,
+            source,
+            res,
+            span,
+            suggestion_span: self.r.suggestion_span(span),
+            lifetime_params: &lifetime_params,
+            self_value_available: self.self_value_is_available(path[0].ident.span, span),
+        };
+        let mut fixes = Vec::new();
         if path.len() == 1 && self.self_type_is_available(span) {
             if let Some(candidate) = self.lookup_assoc_candidate(ident, ns, is_expected) {
-                let self_is_available = self.self_value_is_available(path[0].ident.span, span);
-                match candidate {
-                    AssocSuggestion::Field => {
-                        if self_is_available {
-                            err.span_suggestion(
-                                span,
-                                "you might have meant to use the available field",
-                                format!("self.{}", path_str),
-                                Applicability::MachineApplicable,
-                            );
-                        } else {
-                            err.span_label(span, "a field by this name exists in `Self`");
-                        }
-                    }
-                    AssocSuggestion::MethodWithSelf if self_is_available => {
-                        err.span_suggestion(
-                            span,
-                            "try",
-                            format!("self.{}", path_str),
-                            Applicability::MachineApplicable,
-                        );
-                    }
-                    AssocSuggestion::MethodWithSelf | AssocSuggestion::AssocItem => {
-                        err.span_suggestion(
-                            span,
-                            "try",
-                            format!("Self::{}", path_str),
-                            Applicability::MachineApplicable,
-                        );
+                match heuristics::assoc_item_fix(&cx, candidate) {
+                    Some(fix) => fixes.push(fix),
+                    None => {
+                        err.span_label(span, msg!(sess, "field-exists-in-self"));
+                        return (err, candidates);
                     }
                 }
-                return (err, candidates);
-            }
-
-            // If the first argument in call is `self` suggest calling a method.
-            if let Some((call_span, args_span)) = self.call_has_self_arg(source) {
+            } else if let Some((_, call_span, args_span)) =
+                // If the first argument in call is `self` suggest calling a method.
+                self.call_first_arg(source).filter(|(arg, ..)| arg.name == kw::SelfLower)
+            {
                 let mut args_snippet = String::new();
                 if let Some(args_span) = args_span {
-                    if let Ok(snippet) = self.r.session.source_map().span_to_snippet(args_span) {
+                    if let Ok(snippet) = sess.source_map().span_to_snippet(args_span) {
                         args_snippet = snippet;
                     }
                 }
 
-                err.span_suggestion(
-                    call_span,
-                    &format!("try calling `{}` as a method", ident),
-                    format!("self.{}({})", path_str, args_snippet),
-                    Applicability::MachineApplicable,
-                );
+                fixes.push(heuristics::self_arg_call_fix(&cx, call_span, &args_snippet));
+            }
+        }
+
+        let sm = sess.source_map();
+        let snippet = |span| sm.span_to_snippet(span).ok();
+
+        // Point at a binding of this name that is only introduced after the use, or only in a
+        // block that ended before it. Moving a later binding is ranked along with the other fixes.
+        if res.is_none() && path.len() == 1 && matches!(source, PathSource::Expr(_)) {
+            if let Some((stmts, use_index, let_index, binding_span)) =
+                self.later_binding(ident, span)
+            {
+                err.span_label(binding_span, msg!(sess, "binding-declared-later"));
+                let fix = heuristics::later_binding_fix(&cx, stmts, use_index, let_index, snippet);
+                fixes.extend(fix);
+            } else if fixes.is_empty() && self.note_binding_in_inner_scope(&mut err, ident, span) {
+                err.span_label(base_span, fallback_label);
                 return (err, candidates);
             }
         }
 
-        // Try Levenshtein algorithm.
-        let typo_sugg = self.lookup_typo_candidate(path, ns, is_expected, span);
-        let levenshtein_worked = self.r.add_typo_suggestion(&mut err, typo_sugg, ident_span);
+        // A field of a binding whose type is written out, like the fields of `self` above.
+        if is_expected(Res::Local(ast::DUMMY_NODE_ID)) {
+            let typed_bindings = self.typed_bindings().filter_map(|(binding, did)| {
+                self.r.field_names.get(&did).map(|fields| (binding, &fields[..]))
+            });
+            fixes.extend(heuristics::binding_field_fix(&cx, typed_bindings));
+        }
+
+        // A method called like a free function on a binding, like the methods of `self` above.
+        if res.is_none() && path.len() == 1 {
+            if let Some((receiver, call_span, args_span)) = self.call_first_arg(source) {
+                if self.receiver_has_method(&mut candidates, ident, receiver) {
+                    let args = args_span.and_then(snippet).unwrap_or_default();
+                    let fix = heuristics::binding_method_call_fix(&cx, receiver, call_span, &args);
+                    fixes.push(fix);
+                }
+            }
+        }
+
+        // A label used without its tick, e.g. `break outer` for `'outer`.
+        let labels = (0..self.label_ribs.len())
+            .rev()
+            .take_while(|&i| self.is_label_valid_from_rib(i))
+            .flat_map(|i| self.label_ribs[i].bindings.keys().copied());
+        fixes.extend(heuristics::label_tick_fix(&cx, labels));
+
+        // Prefix `await` written in Rust 2015, where it isn't a keyword yet. Without an argument
+        // to rewrite, the postfix form is explained instead.
+        if res.is_none() && gated_construct.map_or(false, |c| c.keyword == kw::Await) {
+            match heuristics::postfix_await_fix(&cx, snippet) {
+                Some(fix) => fixes.push(fix),
+                None => {
+                    err.help(&msg!(sess, "postfix-await-help"));
+                }
+            }
+        }
+
+        // A bang macro called like a function, e.g. `my_macro(x)` for `my_macro!(x)`.
+        let is_bang_macro = || self.bang_macro_in_scope(path, span);
+        fixes.extend(heuristics::invoke_macro_fix(&cx, is_bang_macro));
+
+        // Generic arguments written without the turbofish, e.g. `foo<A, B>(x)`.
+        fixes.extend(heuristics::turbofish_fix(&cx));
+
+        // A variant used with the wrong pattern shape, e.g. `Some` or `None(..)`.
+        let def_span = res.and_then(|res| res.opt_def_id()).and_then(|did| self.r.opt_span(did));
+        fixes.extend(heuristics::variant_pat_shape_fix(&cx, def_span, snippet));
+
+        // A misspelled variant of the enum being matched on is likelier than any other typo.
+        if let Some((enum_module, ref enum_path)) = scrutinee_enum {
+            if res.is_none() && candidates.is_empty() {
+                let variants = self.variant_names(enum_module, ns, is_expected);
+                fixes.extend(heuristics::scrutinee_variant_fix(&cx, enum_path, &variants));
+            }
+        }
+
+        // A primitive type of another language, e.g. `int` or `size_t`.
+        let resolve = |name: &str| self.suggested_path_res(name, span);
+        fixes.extend(heuristics::foreign_type_fix(&cx, resolve));
+
+        // A lifetime written without its apostrophe, e.g. `Ref<a, T>` for `Ref<'a, T>`, names
+        // similar to it are unrelated.
+        let mut typo_fix = None;
+        if let Some(fix) = heuristics::lifetime_fix(&cx) {
+            fixes.push(fix);
+        } else {
+            // Try Levenshtein algorithm.
+            let typo_sugg = self.lookup_typo_candidate(path, ns, is_expected, span);
+            typo_fix = self.r.typo_fix(typo_sugg, ident_span);
+        }
+
+        // A path in a pattern that refers to nothing, e.g. `limits::Max` for `limits::MAX`, or to a
+        // local binding, e.g. `threshold(..)`.
+        let mut pattern_fix = None;
+        if matches!(source, PathSource::Pat | PathSource::TupleStruct(_))
+            && (res.is_none() && path.len() > 1 || matches!(res, Some(Res::Local(_))))
+        {
+            self.help_match_local_with_const(&mut err, ident, span);
+            let items = self.pattern_path_items(path, source, span);
+            pattern_fix = heuristics::closest_pattern_item_fix(&cx, items);
+        }
+
+        // Even the closest name is only a guess when another heuristic proposed a fix as well.
+        if let Some(mut fix) = typo_fix {
+            if !fixes.is_empty() || pattern_fix.is_some() {
+                fix.applicability = Applicability::MaybeIncorrect;
+            }
+            fixes.push(fix);
+        }
+        fixes.extend(pattern_fix);
+
+        let fixes = heuristics::rank_fixes(fixes);
+        let mut levenshtein_worked = !fixes.is_empty();
+        let keep_fallback_label = fixes.iter().any(|fix| fix.keeps_fallback_label);
+        for fix in fixes {
+            fix.add_to(&mut err, sess);
+        }
+        if keep_fallback_label {
+            err.span_label(base_span, fallback_label);
+            return (err, candidates);
+        }
+
+        // A type used as a trait, look for a similarly named trait that isn't in scope.
+        if !levenshtein_worked && candidates.is_empty() && res.is_some() {
+            if let (PathSource::Trait(_), [segment]) = (source, path) {
+                levenshtein_worked =
+                    self.suggest_similar_trait(&mut err, segment.ident, is_expected);
+            }
+        }
+
+        // An output function of another language, e.g. `printf` from C.
+        if !levenshtein_worked && res.is_none() && ns == ValueNS && candidates.is_empty() {
+            if let [segment] = path {
+                self.note_print_macro(&mut err, segment.ident);
+            }
+        }
 
         // Try context-dependent help if relaxed lookup didn't work.
         if let Some(res) = res {
@@ -337,6 +698,13 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
             }
         }
 
+        // A module used as a value, e.g. `mem(a, b)` for `mem::swap(a, b)`.
+        if let Some(Res::Def(DefKind::Mod, def_id)) = res {
+            if candidates.is_empty() && !ident.is_path_segment_keyword() {
+                self.suggest_module_items(&mut err, def_id, ident, span, &path_str, source);
+            }
+        }
+
         // Fallback label.
         if !levenshtein_worked {
             err.span_label(base_span, fallback_label);
@@ -345,197 +713,1082 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
                 Some((pat_sp, Some(ty_sp), None)) if ty_sp.contains(base_span) && could_be_expr => {
                     err.span_suggestion_short(
                         pat_sp.between(ty_sp),
-                        "use `=` if you meant to assign",
+                        &msg!(sess, "assign-instead"),
                         " = ".to_string(),
                         Applicability::MaybeIncorrect,
                     );
                 }
                 _ => {}
             }
+            if res.is_none() && path.len() == 1 && ns == ValueNS {
+                self.suggest_let_for_assignment(&mut err, span);
+            }
         }
         (err, candidates)
     }
 
-    /// Check if the source is call expression and the first argument is `self`. If true,
-    /// return the span of whole call and the span for all arguments expect the first one (`self`).
-    fn call_has_self_arg(&self, source: PathSource<'_>) -> Option<(Span, Option<Span>)> {
-        let mut has_self_arg = None;
-        if let PathSource::Expr(parent) = source {
-            match &parent?.kind {
-                ExprKind::Call(_, args) if !args.is_empty() => {
-                    let mut expr_kind = &args[0].kind;
-                    loop {
-                        match expr_kind {
-                            ExprKind::Path(_, arg_name) if arg_name.segments.len() == 1 => {
-                                if arg_name.segments[0].ident.name == kw::SelfLower {
-                                    let call_span = parent.unwrap().span;
-                                    let tail_args_span = if args.len() > 1 {
-                                        Some(Span::new(
-                                            args[1].span.lo(),
-                                            args.last().unwrap().span.hi(),
-                                            call_span.ctxt(),
-                                        ))
-                                    } else {
-                                        None
-                                    };
-                                    has_self_arg = Some((call_span, tail_args_span));
-                                }
-                                break;
-                            }
-                            ExprKind::AddrOf(_, _, expr) => expr_kind = &expr.kind,
-                            _ => break,
-                        }
-                    }
-                }
-                _ => (),
-            }
+    /// The enum written as the type of the local that the scrutinee of the `match` being resolved
+    /// refers to, e.g. `Shape` for `fn area(shape: &Shape) { match shape { .. } }`, along with the
+    /// path it was written with.
+    fn match_scrutinee_enum(&mut self) -> Option<(Module<'a>, String)> {
+        let mut scrutinee = self.diagnostic_metadata.current_match_scrutinee?;
+        while let ExprKind::AddrOf(_, _, ref inner)
+        | ExprKind::Unary(ast::UnOp::Deref, ref inner)
+        | ExprKind::Paren(ref inner) = scrutinee.kind
+        {
+            scrutinee = inner;
+        }
+        let binding_id = match self.r.partial_res_map.get(&scrutinee.id)?.base_res() {
+            Res::Local(binding_id) => binding_id,
+            _ => return None,
+        };
+        let is_binding =
+            &|pat: &Pat| matches!(pat.kind, PatKind::Ident(..)) && pat.id == binding_id;
+        let params = self.diagnostic_metadata.current_function.iter().flat_map(|(fn_kind, _)| {
+            let inputs = fn_kind.decl().inputs.iter();
+            inputs.filter(move |param| is_binding(&param.pat)).map(|param| &param.ty)
+        });
+        let locals = self.diagnostic_metadata.enclosing_blocks.iter().copied().flat_map(|block| {
+            block.stmts.iter().filter_map(move |stmt| match stmt.kind {
+                StmtKind::Local(ref local) if is_binding(&local.pat) => local.ty.as_ref(),
+                _ => None,
+            })
+        });
+        let mut ty = params.chain(locals).next()?;
+        while let TyKind::Rptr(_, ref mut_ty) = ty.kind {
+            ty = &mut_ty.ty;
+        }
+        let path = match ty.kind {
+            TyKind::Path(None, ref path) => path,
+            _ => return None,
         };
-        has_self_arg
+        let segments = Segment::from_path(path);
+        match self.resolve_path(&segments, Some(TypeNS), false, path.span, CrateLint::No) {
+            PathResult::Module(ModuleOrUniformRoot::Module(module))
+                if matches!(module.res(), Some(Res::Def(DefKind::Enum, _))) =>
+            {
+                Some((module, Segment::names_to_string(&segments)))
+            }
+            _ => None,
+        }
     }
 
-    fn followed_by_brace(&self, span: Span) -> (bool, Option<Span>) {
-        // HACK(estebank): find a better way to figure out that this was a
-        // parser issue where a struct literal is being used on an expression
-        // where a brace being opened means a block is being started. Look
-        // ahead for the next text to see if `span` is followed by a `{`.
-        let sm = self.r.session.source_map();
-        let mut sp = span;
-        loop {
-            sp = sm.next_point(sp);
-            match sm.span_to_snippet(sp) {
-                Ok(ref snippet) => {
-                    if snippet.chars().any(|c| !c.is_whitespace()) {
+    /// Lists the scopes that a single-segment path in `ns` is looked up in from here, from the
+    /// innermost one outwards.
+    fn searched_scopes(&self, ns: Namespace) -> String {
+        let sess = self.r.session;
+        let mut scopes = Vec::new();
+        let mut module = None;
+        for rib in self.ribs[ns].iter().rev() {
+            match rib.kind {
+                RibKind::ModuleRibKind(rib_module) => {
+                    scopes.push(self.r.module_descr(rib_module));
+                    // Items of the enclosing module are visible through blocks.
+                    if let ModuleKind::Def(..) = rib_module.kind {
+                        module = Some(rib_module);
                         break;
                     }
                 }
-                _ => break,
+                RibKind::MacroDefinition(_) => {}
+                ref kind => scopes.push(kind.descr(sess)),
             }
         }
-        let followed_by_brace = match sm.span_to_snippet(sp) {
-            Ok(ref snippet) if snippet == "{" => true,
-            _ => false,
-        };
-        // In case this could be a struct literal that needs to be surrounded
-        // by parentheses, find the appropriate span.
-        let mut i = 0;
-        let mut closing_brace = None;
-        loop {
-            sp = sm.next_point(sp);
-            match sm.span_to_snippet(sp) {
-                Ok(ref snippet) => {
-                    if snippet == "}" {
-                        closing_brace = Some(span.to(sp));
-                        break;
-                    }
-                }
-                _ => break,
-            }
-            i += 1;
-            // The bigger the span, the more likely we're incorrect --
-            // bound it to 100 chars long.
-            if i > 100 {
-                break;
+        scopes.dedup();
+        if ns == TypeNS {
+            scopes.push(msg!(sess, "scope-extern-prelude"));
+        }
+        if let Some(prelude) = self.r.prelude {
+            if !module.map_or(false, |module| module.no_implicit_prelude) {
+                scopes.push(self.r.module_descr(prelude));
             }
         }
-        (followed_by_brace, closing_brace)
+        match scopes.split_last() {
+            Some((last, [])) => last.clone(),
+            Some((last, rest)) => msg!(sess, "list-and", rest = rest.join(", "), last = last),
+            None => String::new(),
+        }
     }
 
-    /// Provides context-dependent help for errors reported by the `smart_resolve_path_fragment`
-    /// function.
-    /// Returns `true` if able to provide context-dependent help.
-    fn smart_resolve_context_dependent_help(
+    /// The names of the variants of the enum at `enum_module` in `ns` that are expected.
+    fn variant_names(
         &mut self,
-        err: &mut DiagnosticBuilder<'a>,
-        span: Span,
-        source: PathSource<'_>,
-        res: Res,
-        path_str: &str,
-        fallback_label: &str,
-    ) -> bool {
-        let ns = source.namespace();
-        let is_expected = &|res| source.is_expected(res);
+        enum_module: Module<'a>,
+        ns: Namespace,
+        is_expected: &impl Fn(Res) -> bool,
+    ) -> Vec<Symbol> {
+        let mut names = Vec::new();
+        enum_module.for_each_child(self.r, |_, name, child_ns, binding| {
+            if child_ns == ns && is_expected(binding.res()) {
+                names.push(name.name);
+            }
+        });
+        names
+    }
 
-        let path_sep = |err: &mut DiagnosticBuilder<'_>, expr: &Expr| match expr.kind {
-            ExprKind::Field(_, ident) => {
-                err.span_suggestion(
-                    expr.span,
-                    "use the path separator to refer to an item",
-                    format!("{}::{}", path_str, ident),
-                    Applicability::MaybeIncorrect,
-                );
-                true
+    /// Points at the local binding named like `ident`, the last segment of a pattern path, which
+    /// can't be matched against while a `const` could be.
+    fn help_match_local_with_const(
+        &mut self,
+        err: &mut DiagnosticBuilder<'_>,
+        ident: Ident,
+        span: Span,
+    ) {
+        let sess = self.r.session;
+        let binding = self.resolve_ident_in_lexical_scope(ident, ValueNS, None, span);
+        if let Some(Res::Local(binding_id)) = binding.map(|binding| binding.res()) {
+            let local = self.ribs[ValueNS].iter().rev().find_map(|rib| {
+                rib.bindings.iter().find(|(_, res)| **res == Res::Local(binding_id))
+            });
+            if let Some((binding, _)) = local {
+                err.span_help(binding.span, &msg!(sess, "match-local-with-const", name = ident));
             }
-            ExprKind::MethodCall(ref segment, ..) => {
-                let span = expr.span.with_hi(segment.ident.span.hi());
-                err.span_suggestion(
-                    span,
-                    "use the path separator to refer to an item",
-                    format!("{}::{}", path_str, segment.ident),
-                    Applicability::MaybeIncorrect,
-                );
-                true
+        }
+    }
+
+    /// The accessible items of the kind a pattern expects in the module that a path with several
+    /// segments was looked up in, with what they resolve to.
+    fn pattern_path_items(
+        &mut self,
+        path: &[Segment],
+        source: PathSource<'_>,
+        span: Span,
+    ) -> Vec<(Symbol, Res)> {
+        let mut items = Vec::new();
+        let mod_path = &path[..path.len() - 1];
+        if mod_path.is_empty() {
+            return items;
+        }
+        let module = match self.resolve_path(mod_path, Some(TypeNS), false, span, CrateLint::No) {
+            PathResult::Module(ModuleOrUniformRoot::Module(module)) => module,
+            _ => return items,
+        };
+        let parent_module = self.parent_scope.module;
+        module.for_each_child(self.r, |r, name, ns, binding| {
+            if ns == ValueNS
+                && source.is_expected(binding.res())
+                && r.is_accessible_from(binding.vis, parent_module)
+            {
+                items.push((name.name, binding.res()));
             }
-            _ => false,
+        });
+        items
+    }
+
+    /// The source of the path to the enum of the variant that `path` resolved to, e.g. `Enum` for
+    /// `Enum::Variant`. Single-segment paths don't name the enum.
+    fn variant_enum_path(&self, path: &[Segment], span: Span) -> Option<String> {
+        let variant_span = path.last()?.ident.span;
+        if path.len() < 2 || !span.contains(variant_span) {
+            return None;
+        }
+        let prefix = self.r.session.source_map().span_to_snippet(span.until(variant_span)).ok()?;
+        let prefix = prefix.trim_end();
+        if !prefix.ends_with("::") {
+            return None;
+        }
+        let enum_path = prefix[..prefix.len() - 2].trim_end();
+        if enum_path.is_empty() { None } else { Some(enum_path.to_string()) }
+    }
+
+    /// Points to the printing macros when `ident` names a well-known output function or stream
+    /// of C or C++.
+    fn note_print_macro(&self, err: &mut DiagnosticBuilder<'_>, ident: Ident) {
+        let name = ident.as_str();
+        let mac = match FOREIGN_OUTPUT_NAMES.iter().find(|(foreign, _)| *foreign == &*name) {
+            Some((_, mac)) => mac,
+            None => return,
+        };
+        let sess = self.r.session;
+        let stream = if mac.starts_with('e') {
+            msg!(sess, "standard-error")
+        } else {
+            msg!(sess, "standard-output")
         };
+        err.note(&msg!(sess, "use-print-macro", name = ident, mac = mac, stream = stream));
+    }
 
-        let mut bad_struct_syntax_suggestion = |def_id: DefId| {
-            let (followed_by_brace, closing_brace) = self.followed_by_brace(span);
-            let mut suggested = false;
-            match source {
-                PathSource::Expr(Some(parent)) => {
-                    suggested = path_sep(err, &parent);
-                }
-                PathSource::Expr(None) if followed_by_brace => {
-                    if let Some(sp) = closing_brace {
-                        err.multipart_suggestion(
-                            "surround the struct literal with parentheses",
-                            vec![
-                                (sp.shrink_to_lo(), "(".to_string()),
-                                (sp.shrink_to_hi(), ")".to_string()),
-                            ],
-                            Applicability::MaybeIncorrect,
-                        );
-                    } else {
-                        err.span_label(
-                            span, // Note the parentheses surrounding the suggestion below
-                            format!(
-                                "you might want to surround a struct literal with parentheses: \
-                                 `({} {{ /* fields */ }})`?",
-                                path_str
-                            ),
-                        );
-                    }
-                    suggested = true;
-                }
-                _ => {}
+    /// Removes the constants of the modules named after numeric types, e.g. `std::i32::MAX`, from
+    /// `candidates`, and returns the paths of the associated constants of those types they are
+    /// the old spelling of, e.g. `i32::MAX`.
+    crate fn take_primitive_assoc_consts(
+        &self,
+        candidates: &mut Vec<ImportSuggestion>,
+    ) -> Vec<String> {
+        let primitive_types = &self.r.primitive_type_table.primitive_types;
+        let cstore = self.r.cstore();
+        let is_const = |did: Option<DefId>| match did {
+            Some(did) if !did.is_local() => matches!(
+                Res::Def(cstore.def_kind_untracked(did), did),
+                Res::Def(DefKind::AssocConst | DefKind::Const, _)
+            ),
+            _ => false,
+        };
+        let mut assoc_consts = Vec::new();
+        candidates.retain(|candidate| match &candidate.path.segments[..] {
+            [krate, ty, name]
+                if is_const(candidate.did)
+                    && (krate.ident.name == sym::std || krate.ident.name == sym::core)
+                    && matches!(
+                        primitive_types.get(&ty.ident.name),
+                        Some(PrimTy::Int(_) | PrimTy::Uint(_) | PrimTy::Float(_))
+                    ) =>
+            {
+                assoc_consts.push(format!("{}::{}", ty.ident, name.ident));
+                false
             }
-            if !suggested {
-                if let Some(span) = self.r.opt_span(def_id) {
-                    err.span_label(span, &format!("`{}` defined here", path_str));
-                }
-                err.span_label(span, format!("did you mean `{} {{ /* fields */ }}`?", path_str));
+            _ => true,
+        });
+        assoc_consts.sort();
+        assoc_consts.dedup();
+        assoc_consts
+    }
+
+    /// The path in the `alloc` crate of the item named `ident`, if it is one that `std` re-exports
+    /// and this is a `no_std` crate which doesn't link `alloc`.
+    crate fn unlinked_alloc_item(&self, ident: Ident, ns: Namespace) -> Option<String> {
+        let extern_prelude = &self.r.extern_prelude;
+        if ns != TypeNS
+            || !extern_prelude.contains_key(&Ident::with_dummy_span(sym::core))
+            || extern_prelude.contains_key(&Ident::with_dummy_span(sym::std))
+            || extern_prelude.contains_key(&Ident::with_dummy_span(sym::alloc))
+        {
+            return None;
+        }
+        let name = ident.as_str();
+        ALLOC_ITEMS
+            .iter()
+            .find(|(item, _)| *item == &*name)
+            .map(|(_, path)| format!("alloc::{}", path))
+    }
+
+    /// Adds the note of the closest `#[rustc_on_unresolved]` attribute on the current module or
+    /// one of its ancestors, with `{name}` replaced by the name that failed to resolve.
+    fn note_on_unresolved(&self, err: &mut DiagnosticBuilder<'_>, ident: Ident) {
+        let mut module = Some(self.parent_scope.module);
+        while let Some(current) = module {
+            if let Some(note) = current.on_unresolved {
+                err.note(&crate::messages::fill_placeholders(&note.as_str(), &[("name", &ident)]));
+                return;
             }
-        };
+            module = current.parent;
+        }
+    }
 
-        match (res, source) {
+    /// Explains that `ident` would have come from the prelude if the current module wasn't under
+    /// `#[no_implicit_prelude]`, and how to import the prelude explicitly.
+    fn note_disabled_prelude(
+        &mut self,
+        err: &mut DiagnosticBuilder<'_>,
+        ident: Ident,
+        ns: Namespace,
+        is_expected: &impl Fn(Res) -> bool,
+    ) {
+        let module = self.r.get_module(self.parent_scope.module.normal_ancestor_id);
+        let prelude = match self.r.prelude {
+            Some(prelude) if module.no_implicit_prelude => prelude,
+            _ => return,
+        };
+        match self.r.resolve_ident_in_module(
+            ModuleOrUniformRoot::Module(prelude),
+            ident,
+            ns,
+            &self.parent_scope,
+            false,
+            ident.span,
+        ) {
+            Ok(binding) if is_expected(binding.res()) => {}
+            _ => return,
+        }
+
+        // The prelude is in another crate, whose root module is named after it.
+        let mut names = Vec::new();
+        let mut current = Some(prelude);
+        while let Some(module) = current {
+            if let ModuleKind::Def(.., name) = module.kind {
+                names.push(name.to_string());
+            }
+            current = module.parent;
+        }
+        names.reverse();
+
+        let sess = self.r.session;
+        err.note(&msg!(sess, "prelude-disabled", name = ident));
+        err.help(&msg!(sess, "import-prelude", prelude = names.join("::")));
+    }
+
+    /// Points at a single import of `ident` in the parent module or in a sibling module of the
+    /// current one. An import in the parent module can be repeated here through `super`, a private
+    /// import in a sibling module can be made visible to the rest of the crate.
+    fn note_import_in_nearby_module(
+        &mut self,
+        err: &mut DiagnosticBuilder<'_>,
+        candidates: &mut Vec<ImportSuggestion>,
+        ident: Ident,
+        ns: Namespace,
+        is_expected: &impl Fn(Res) -> bool,
+    ) {
+        let module = self.r.get_module(self.parent_scope.module.normal_ancestor_id);
+        let parent = match module.parent {
+            Some(parent) => self.r.get_module(parent.normal_ancestor_id),
+            None => return,
+        };
+        // Look at the parent module first, then at the siblings in declaration order.
+        let mut nearby = vec![(parent, None)];
+        parent.for_each_child(self.r, |_, name, child_ns, binding| match binding.module() {
+            Some(sibling)
+                if child_ns == TypeNS
+                    && !binding.is_import()
+                    && sibling.is_normal()
+                    && !ptr::eq(sibling, module) =>
+            {
+                nearby.push((sibling, Some(name)))
+            }
+            _ => {}
+        });
+
+        for (nearby_module, sibling_name) in nearby {
+            let key = self.r.new_key(ident, ns);
+            let binding = match self.r.resolutions(nearby_module).borrow().get(&key) {
+                Some(resolution) => resolution.borrow().binding,
+                None => continue,
+            };
+            let (binding, import, imported) = match binding {
+                Some(binding) => match binding.kind {
+                    NameBindingKind::Import { binding: imported, import, .. }
+                        if matches!(import.kind, ImportKind::Single { .. }) =>
+                    {
+                        (binding, import, imported)
+                    }
+                    _ => continue,
+                },
+                None => continue,
+            };
+            if !is_expected(binding.res()) || import.use_span.from_expansion() {
+                continue;
+            }
+
+            let sess = self.r.session;
+            match sibling_name {
+                None => {
+                    err.span_note(import.span, &msg!(sess, "imported-in-parent", name = ident));
+                    // Offer importing it through the parent module, unless it can already be
+                    // imported from where it is defined.
+                    let did = binding.res().opt_def_id();
+                    candidates.retain(|candidate| candidate.did != did || candidate.accessible);
+                    if candidates.iter().all(|candidate| candidate.did != did) {
+                        let segments = vec![
+                            ast::PathSegment::from_ident(Ident::with_dummy_span(kw::Super)),
+                            ast::PathSegment::from_ident(Ident::with_dummy_span(ident.name)),
+                        ];
+                        candidates.push(ImportSuggestion {
+                            did,
+                            descr: binding.res().descr(),
+                            path: Path { span: binding.span, segments },
+                            accessible: true,
+                            unstable: None,
+                        });
+                    }
+                }
+                Some(sibling_name) => {
+                    if self.r.is_accessible_from(binding.vis, module) {
+                        continue;
+                    }
+                    err.span_note(
+                        import.span,
+                        &msg!(sess, "imported-privately", name = ident, module = sibling_name),
+                    );
+                    // The import can only be made more visible than the imported item allows.
+                    if self.r.is_accessible_from(imported.vis, self.r.graph_root) {
+                        err.span_suggestion_verbose(
+                            import.use_span.shrink_to_lo(),
+                            &msg!(sess, "reexport-to-crate", module = sibling_name),
+                            "pub(crate) ".to_string(),
+                            Applicability::MaybeIncorrect,
+                        );
+                    }
+                }
+            }
+            return;
+        }
+    }
+
+    /// Points at both the explicit import or item that `ident` resolved to, and the glob import
+    /// it shadows, when the glob would have provided what was expected. Returns `true` if such a
+    /// glob import was found.
+    fn note_shadowed_glob(
+        &mut self,
+        err: &mut DiagnosticBuilder<'_>,
+        candidates: &mut Vec<ImportSuggestion>,
+        ident: Ident,
+        ns: Namespace,
+        res: Res,
+        is_expected: &impl Fn(Res) -> bool,
+    ) -> bool {
+        // Globs only apply to the module they're written in, and to the blocks nested in it.
+        let key = self.r.new_key(ident, ns);
+        let mut shadowing = None;
+        let mut module = Some(self.parent_scope.module);
+        while let Some(m) = module {
+            if let Some(resolution) = self.r.resolutions(m).borrow().get(&key) {
+                let NameResolution { binding, shadowed_glob, .. } = *resolution.borrow();
+                if let (Some(binding), Some(glob)) = (binding, shadowed_glob) {
+                    if binding.res() == res {
+                        shadowing = Some((binding, glob));
+                    }
+                }
+                break;
+            }
+            if m.is_normal() {
+                break;
+            }
+            module = m.parent;
+        }
+        let (binding, glob) = match shadowing {
+            Some((binding, glob)) if is_expected(glob.res()) => (binding, glob),
+            _ => return false,
+        };
+        let import = match glob.kind {
+            NameBindingKind::Import { import, .. } if !import.use_span.from_expansion() => import,
+            _ => return false,
+        };
+
+        let sess = self.r.session;
+        let descr = binding.res().descr();
+        let shadowing_msg = if binding.is_import() {
+            msg!(sess, "shadowing-import", name = ident, descr = descr)
+        } else {
+            msg!(sess, "shadowing-item", name = ident, descr = descr)
+        };
+        err.span_note(binding.span, &shadowing_msg);
+        let glob_descr = glob.res().descr();
+        err.span_note(glob.span, &msg!(sess, "shadowed-glob", name = ident, descr = glob_descr));
+
+        // The glob's path is written relative to the module of the `use`, which is where `ident`
+        // is used as well.
+        let mut path = Segment::names_to_string(&import.module_path);
+        if import.module_path.first().map_or(false, |seg| seg.ident.name == kw::PathRoot) {
+            path.insert_str(0, "::");
+        }
+        err.span_suggestion_verbose(
+            ident.span,
+            &msg!(sess, "use-shadowed-glob-item", descr = glob_descr),
+            format!("{}::{}", path, ident),
+            Applicability::MaybeIncorrect,
+        );
+        // The item is in scope already, only its name is taken.
+        let did = glob.res().opt_def_id();
+        candidates.retain(|candidate| candidate.did != did);
+        true
+    }
+
+    /// Points at a `use path::ident as alias;` in scope when `ident` is used under its original
+    /// name. Returns `true` if such an import was found.
+    fn suggest_renamed_import(
+        &mut self,
+        err: &mut DiagnosticBuilder<'_>,
+        candidates: &mut Vec<ImportSuggestion>,
+        ident: Ident,
+        ns: Namespace,
+        is_expected: &impl Fn(Res) -> bool,
+    ) -> bool {
+        // Imports apply to the module they're written in, and to the blocks nested in it.
+        let mut renamed = None;
+        let mut module = Some(self.parent_scope.module);
+        while let Some(m) = module {
+            m.for_each_child(self.r, |_, alias, child_ns, binding| {
+                if let NameBindingKind::Import { import, .. } = binding.kind {
+                    if let ImportKind::Single { source, target, type_ns_only, .. } = import.kind {
+                        if renamed.is_none()
+                            && child_ns == ns
+                            && source.name == ident.name
+                            && alias.name != ident.name
+                            && alias.name != kw::Underscore
+                            && is_expected(binding.res())
+                            && !import.use_span.from_expansion()
+                        {
+                            renamed = Some((binding, import, source, target, type_ns_only));
+                        }
+                    }
+                }
+            });
+            if renamed.is_some() || m.is_normal() {
+                break;
+            }
+            module = m.parent;
+        }
+        let (binding, import, source, target, type_ns_only) = match renamed {
+            Some(renamed) => renamed,
+            None => return false,
+        };
+
+        let sess = self.r.session;
+        err.span_note(import.span, &msg!(sess, "renamed-import", name = ident, alias = target));
+        err.span_suggestion_verbose(
+            ident.span,
+            &msg!(sess, "use-import-alias"),
+            target.to_string(),
+            Applicability::MachineApplicable,
+        );
+        // `use path::{self as alias}` has nothing to remove between `source` and `alias`.
+        if !type_ns_only {
+            err.span_suggestion_verbose(
+                source.span.shrink_to_hi().to(target.span),
+                &msg!(sess, "remove-import-rename"),
+                String::new(),
+                Applicability::MaybeIncorrect,
+            );
+        }
+        // The item is already imported, so don't offer importing it again.
+        let did = binding.res().opt_def_id();
+        candidates.retain(|candidate| candidate.did != did);
+        true
+    }
+
+    /// Suggests a similarly named trait that isn't in scope, e.g. `graphics::Render` when the
+    /// struct `Renderer` is used as a trait. Returns `true` if a suggestion was made.
+    fn suggest_similar_trait(
+        &mut self,
+        err: &mut DiagnosticBuilder<'_>,
+        ident: Ident,
+        filter_fn: &impl Fn(Res) -> bool,
+    ) -> bool {
+        let lookup_str = ident.as_str();
+        let suggestion = self
+            .r
+            .lookup_similar_import_candidates(ident, TypeNS, &self.parent_scope, filter_fn)
+            .into_iter()
+            .filter(|suggestion| suggestion.accessible)
+            .map(|suggestion| {
+                let name = suggestion.path.segments.last().unwrap().ident.as_str();
+                let dist = lev_distance(&lookup_str, &name);
+                let path = path_names_to_string(&suggestion.path);
+                (dist, suggestion.path.segments.len(), path, suggestion.descr)
+            })
+            .min();
+        if let Some((_, _, path, descr)) = suggestion {
+            err.span_suggestion(
+                ident.span,
+                &msg!(self.r.session, "similar-name-exists", descr = descr),
+                path,
+                Applicability::MaybeIncorrect,
+            );
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Suggests introducing a new binding when the unresolved name is the whole left-hand side of
+    /// an assignment, as in `x = 42;` where `let x = 42;` was most likely intended.
+    fn suggest_let_for_assignment(&self, err: &mut DiagnosticBuilder<'_>, span: Span) {
+        if let Some(Expr { kind: ExprKind::Assign(lhs, ..), span: assign_span, .. }) =
+            self.diagnostic_metadata.in_assignment
+        {
+            if let ExprKind::Path(None, _) = lhs.kind {
+                if lhs.span == span && !assign_span.from_expansion() {
+                    err.span_suggestion_verbose(
+                        span.shrink_to_lo(),
+                        &msg!(self.r.session, "introduce-binding"),
+                        "let ".to_string(),
+                        Applicability::MaybeIncorrect,
+                    );
+                }
+            }
+        }
+    }
+
+    /// The `let` statement declaring `ident` after the statement using it in one of the enclosing
+    /// blocks, as the statements of that block, the indices of the use and of the declaration in
+    /// them and the span of the binding.
+    fn later_binding(
+        &self,
+        ident: Ident,
+        span: Span,
+    ) -> Option<(&'ast [Stmt], usize, usize, Span)> {
+        for &block in self.diagnostic_metadata.enclosing_blocks.iter().rev() {
+            // Statements produced by a macro are looked at through its invocation.
+            let use_index =
+                block.stmts.iter().position(|stmt| stmt.span.source_callsite().contains(span));
+            let use_index = match use_index {
+                Some(use_index) => use_index,
+                None => continue,
+            };
+            for (let_index, let_stmt) in block.stmts.iter().enumerate().skip(use_index + 1) {
+                let local = match let_stmt.kind {
+                    StmtKind::Local(ref local) => local,
+                    _ => continue,
+                };
+                let mut binding_span = None;
+                local.pat.walk(&mut |pat| {
+                    match pat.kind {
+                        PatKind::Ident(_, binding, _) if binding == ident => {
+                            binding_span = Some(binding.span);
+                        }
+                        _ => {}
+                    }
+                    binding_span.is_none()
+                });
+                if let Some(binding_span) = binding_span {
+                    return Some((&block.stmts[..], use_index, let_index, binding_span));
+                }
+            }
+        }
+        None
+    }
+
+    /// The bindings in scope that are written to have the type of an ADT, or a reference to one,
+    /// with the `DefId` of that ADT, outermost first.
+    fn typed_bindings(&self) -> impl Iterator<Item = (Ident, DefId)> + '_ {
+        let params = self.diagnostic_metadata.current_function.iter().flat_map(|(fn_kind, _)| {
+            fn_kind.decl().inputs.iter().map(|param| (&param.pat, &param.ty))
+        });
+        let locals = self.diagnostic_metadata.enclosing_blocks.iter().copied().flat_map(|block| {
+            block.stmts.iter().filter_map(|stmt| match stmt.kind {
+                StmtKind::Local(ref local) => local.ty.as_ref().map(|ty| (&local.pat, ty)),
+                _ => None,
+            })
+        });
+        params.chain(locals).filter_map(move |(pat, ty)| {
+            let binding = match pat.kind {
+                PatKind::Ident(_, binding, None) => binding,
+                _ => return None,
+            };
+            // The binding has to be the one that its name refers to here.
+            let res = Some(&Res::Local(pat.id));
+            if !self.ribs[ValueNS].iter().any(|rib| rib.bindings.get(&binding) == res) {
+                return None;
+            }
+            let mut ty = ty;
+            while let TyKind::Rptr(_, ref mut_ty) = ty.kind {
+                ty = &mut_ty.ty;
+            }
+            let partial_res = self.r.partial_res_map.get(&ty.id)?;
+            match partial_res.base_res() {
+                Res::Def(DefKind::Struct | DefKind::Union | DefKind::Enum, did)
+                    if partial_res.unresolved_segments() == 0 =>
+                {
+                    Some((binding, did))
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Whether the type that `receiver` is written to have has a method named `ident` in a local
+    /// `impl`, or in an inherent `impl` of another crate, inherent ones first. If the method is
+    /// from a trait that isn't in scope, the imports of that trait are added to `candidates`.
+    fn receiver_has_method(
+        &mut self,
+        candidates: &mut Vec<ImportSuggestion>,
+        ident: Ident,
+        receiver: Ident,
+    ) -> bool {
+        let did = match self.typed_bindings().filter(|&(binding, _)| binding == receiver).last() {
+            Some((_, did)) => did,
+            None => return false,
+        };
+        let mut methods: Vec<_> = self
+            .r
+            .impl_methods
+            .get(&ident.name)
+            .into_iter()
+            .flatten()
+            .filter(|method| method.self_ty == did)
+            .copied()
+            .collect();
+        // The inherent methods of a type of another crate are only known from its metadata.
+        let sess = self.r.session;
+        if !did.is_local() && self.r.cstore().has_inherent_method_untracked(did, ident.name, sess) {
+            methods.push(ImplMethod { self_ty: did, of_trait: None });
+        }
+        methods.sort_by_key(|method| method.of_trait.is_some());
+        let of_trait = match methods.first() {
+            Some(method) => method.of_trait,
+            None => return false,
+        };
+
+        if let Some(trait_did) = of_trait {
+            let traits = self.get_traits_containing_item(ident, ValueNS);
+            if !traits.iter().any(|candidate| candidate.def_id == trait_did) {
+                let trait_name = self.r.get_module(trait_did).kind.name().unwrap();
+                candidates.extend(self.r.lookup_import_candidates(
+                    Ident::with_dummy_span(trait_name),
+                    TypeNS,
+                    &self.parent_scope,
+                    |res| res == Res::Def(DefKind::Trait, trait_did),
+                ));
+            }
+        }
+        true
+    }
+
+    /// Points at a `let` statement declaring `ident` in a block that ended before the statement
+    /// using it, as in `if c { let x = 1; } x`, and at where that block ends.
+    fn note_binding_in_inner_scope(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        ident: Ident,
+        span: Span,
+    ) -> bool {
+        use rustc_ast::visit::{self, Visitor};
+
+        /// Finds the last `let` statement binding `ident` that is nested in a block, along with
+        /// the innermost block it's in.
+        struct InnerBlockBinding<'ast> {
+            ident: Ident,
+            blocks: Vec<&'ast Block>,
+            found: Option<(Span, Span)>,
+        }
+
+        impl<'ast> Visitor<'ast> for InnerBlockBinding<'ast> {
+            // Bindings of nested items are unrelated.
+            fn visit_item(&mut self, _: &'ast Item) {}
+
+            fn visit_block(&mut self, block: &'ast Block) {
+                self.blocks.push(block);
+                visit::walk_block(self, block);
+                self.blocks.pop();
+            }
+
+            fn visit_local(&mut self, local: &'ast Local) {
+                if let Some(&block) = self.blocks.last() {
+                    let ident = self.ident;
+                    let mut binding_span = None;
+                    local.pat.walk(&mut |pat| {
+                        match pat.kind {
+                            PatKind::Ident(_, binding, _) if binding == ident => {
+                                binding_span = Some(binding.span);
+                            }
+                            _ => {}
+                        }
+                        binding_span.is_none()
+                    });
+                    if let Some(binding_span) = binding_span {
+                        self.found = Some((binding_span, block.span));
+                    }
+                }
+                visit::walk_local(self, local);
+            }
+        }
+
+        for block in self.diagnostic_metadata.enclosing_blocks.iter().rev() {
+            let use_index =
+                block.stmts.iter().position(|stmt| stmt.span.source_callsite().contains(span));
+            let use_index = match use_index {
+                Some(use_index) => use_index,
+                None => continue,
+            };
+            for stmt in block.stmts[..use_index].iter().rev() {
+                let mut finder = InnerBlockBinding { ident, blocks: Vec::new(), found: None };
+                finder.visit_stmt(stmt);
+                let (binding_span, block_span) = match finder.found {
+                    Some(found) => found,
+                    None => continue,
+                };
+                if binding_span.from_expansion() || block_span.from_expansion() {
+                    return false;
+                }
+                let sess = self.r.session;
+                let block_end = block_span.with_lo(block_span.hi() - BytePos(1));
+                err.span_label(binding_span, msg!(sess, "binding-in-inner-block"));
+                err.span_label(block_end, msg!(sess, "inner-block-ends", name = ident));
+                err.note(&msg!(sess, "binding-out-of-scope", name = ident));
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Suggests the public functions of a module called like a function, or its constants and
+    /// statics when the module is used as a value otherwise, the few most similar to the written
+    /// name first.
+    fn suggest_module_items(
+        &mut self,
+        err: &mut DiagnosticBuilder<'_>,
+        def_id: DefId,
+        ident: Ident,
+        span: Span,
+        path_str: &str,
+        source: PathSource<'_>,
+    ) {
+        let is_call = match source {
+            PathSource::Expr(Some(parent)) => match parent.kind {
+                ExprKind::Call(ref callee, _) if callee.span == span => true,
+                // These have more specific suggestions.
+                ExprKind::Field(..) | ExprKind::MethodCall(..) | ExprKind::Type(..) => return,
+                _ => false,
+            },
+            PathSource::Expr(None) => false,
+            _ => return,
+        };
+        let module = self.r.get_module(def_id);
+        let mut names: Vec<Symbol> = self
+            .r
+            .resolutions(module)
+            .borrow()
+            .iter()
+            .filter_map(|(key, resolution)| {
+                let binding = resolution.borrow().binding?;
+                let is_fn = match binding.res() {
+                    Res::Def(DefKind::Fn, _) => true,
+                    Res::Def(DefKind::Const | DefKind::Static, _) => false,
+                    _ => return None,
+                };
+                if key.ns == ValueNS
+                    && is_fn == is_call
+                    && self.r.is_accessible_from(binding.vis, self.parent_scope.module)
+                {
+                    Some(key.ident.name)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        if names.is_empty() {
+            return;
+        }
+        let written = ident.as_str();
+        names.sort_by_cached_key(|name| {
+            let name = name.as_str();
+            (lev_distance(&written, &name), name.to_string())
+        });
+        names.truncate(MAX_SUGGESTED_MODULE_ITEMS);
+
+        let sess = self.r.session;
+        err.span_suggestions(
+            span,
+            &if is_call {
+                msg!(sess, "call-module-fn", module = path_str)
+            } else {
+                msg!(sess, "use-module-value", module = path_str)
+            },
+            names.iter().map(|name| format!("{}::{}", path_str, name)),
+            Applicability::MaybeIncorrect,
+        );
+    }
+
+    /// Whether `path` names a bang macro defined with `macro_rules!` before the enclosing item.
+    fn bang_macro_in_scope(&mut self, path: &[Segment], span: Span) -> bool {
+        let ast_path = Path {
+            span,
+            segments: path.iter().map(|seg| ast::PathSegment::from_ident(seg.ident)).collect(),
+        };
+        // Late resolution doesn't track the `macro_rules` scope, so take the one the enclosing
+        // item was defined in.
+        let macro_rules = match self.diagnostic_metadata.current_item {
+            Some(item) => match self.r.item_macro_rules_scopes.get(&self.r.local_def_id(item.id)) {
+                Some(&macro_rules) => macro_rules,
+                None => return false,
+            },
+            None => return false,
+        };
+        let parent_scope = ParentScope { macro_rules, ..self.parent_scope };
+        let res = self.r.resolve_macro_path(
+            &ast_path,
+            Some(MacroKind::Bang),
+            &parent_scope,
+            false,
+            false,
+        );
+        matches!(res, Ok((_, res)) if res.macro_kind() == Some(MacroKind::Bang))
+    }
+
+    /// Reports a struct pattern naming fields of a tuple variant, e.g. `Some { x }`, and
+    /// suggests the equivalent tuple variant pattern.
+    crate fn check_tuple_variant_struct_pat(
+        &mut self,
+        pat: &Pat,
+        path: &Path,
+        fields: &[FieldPat],
+        has_rest: bool,
+    ) {
+        // Tuple variants can be matched with struct syntax using their numeric field names.
+        if fields.iter().all(|field| field.ident.name.as_str().parse::<usize>().is_ok()) {
+            return;
+        }
+        let full_res =
+            |res: PartialRes| Some(res.base_res()).filter(|_| res.unresolved_segments() == 0);
+        match self.r.partial_res_map.get(&pat.id).copied().and_then(full_res) {
+            Some(Res::Def(DefKind::Variant, _)) => {}
+            _ => return,
+        }
+        let segments = Segment::from_path(path);
+        let ctor_res =
+            match self.resolve_path(&segments, Some(ValueNS), false, path.span, CrateLint::No) {
+                PathResult::NonModule(res) => full_res(res),
+                _ => None,
+            };
+        match ctor_res {
+            Some(Res::Def(DefKind::Ctor(CtorOf::Variant, CtorKind::Fn), _)) => {}
+            _ => return,
+        }
+
+        let sess = self.r.session;
+        let mut err = struct_span_err!(
+            sess,
+            pat.span,
+            E0769,
+            "{}",
+            msg!(sess, "tuple-variant-as-struct", path = path_names_to_string(path))
+        );
+        let sm = sess.source_map();
+        let subpats = fields
+            .iter()
+            .map(|field| sm.span_to_snippet(field.pat.span).ok())
+            .collect::<Option<Vec<_>>>();
+        if let (Ok(path), Some(mut subpats)) = (sm.span_to_snippet(path.span), subpats) {
+            if has_rest {
+                subpats.push("..".to_string());
+            }
+            err.span_suggestion_verbose(
+                pat.span,
+                &msg!(sess, "use-tuple-variant-pat-syntax"),
+                format!("{}({})", path, subpats.join(", ")),
+                Applicability::MaybeIncorrect,
+            );
+        }
+        err.emit();
+
+        // Avoid follow-up errors about the named fields during type checking.
+        self.r.partial_res_map.insert(pat.id, PartialRes::new(Res::Err));
+    }
+
+    /// Check if the source is call expression and the first argument is a single identifier, maybe
+    /// borrowed. If true, return that identifier, the span of whole call and the span for all
+    /// arguments expect the first one.
+    fn call_first_arg(&self, source: PathSource<'_>) -> Option<(Ident, Span, Option<Span>)> {
+        let mut first_arg = None;
+        if let PathSource::Expr(parent) = source {
+            match &parent?.kind {
+                ExprKind::Call(_, args) if !args.is_empty() => {
+                    let mut expr_kind = &args[0].kind;
+                    loop {
+                        match expr_kind {
+                            ExprKind::Path(None, arg_name) if arg_name.segments.len() == 1 => {
+                                let call_span = parent.unwrap().span;
+                                let tail_args_span = if args.len() > 1 {
+                                    Some(Span::new(
+                                        args[1].span.lo(),
+                                        args.last().unwrap().span.hi(),
+                                        call_span.ctxt(),
+                                    ))
+                                } else {
+                                    None
+                                };
+                                let arg = arg_name.segments[0].ident;
+                                first_arg = Some((arg, call_span, tail_args_span));
+                                break;
+                            }
+                            ExprKind::AddrOf(_, _, expr) => expr_kind = &expr.kind,
+                            _ => break,
+                        }
+                    }
+                }
+                _ => (),
+            }
+        };
+        first_arg
+    }
+
+    /// Provides context-dependent help for errors reported by the `smart_resolve_path_fragment`
+    /// function.
+    /// Returns `true` if able to provide context-dependent help.
+    fn smart_resolve_context_dependent_help(
+        &mut self,
+        err: &mut DiagnosticBuilder<'a>,
+        span: Span,
+        source: PathSource<'_>,
+        res: Res,
+        path_str: &str,
+        fallback_label: &str,
+    ) -> bool {
+        let sess = self.r.session;
+        let ns = source.namespace();
+        let is_expected = &|res| source.is_expected(res);
+
+        // The span of `path.item` or `path.item(..)` up to `item`, when `expr` is either of them.
+        let dot_item = |expr: &Expr| match expr.kind {
+            ExprKind::Field(_, ident) => Some((expr.span, ident)),
+            // Only when the path is the receiver, not one of the arguments.
+            ExprKind::MethodCall(ref segment, ref args, _) if args[0].span == span => {
+                Some((expr.span.with_hi(segment.ident.span.hi()), segment.ident))
+            }
+            _ => None,
+        };
+        let path_sep = |err: &mut DiagnosticBuilder<'_>, expr: &Expr| match dot_item(expr) {
+            Some((span, item)) => {
+                err.span_suggestion(
+                    span,
+                    &msg!(sess, "use-path-separator"),
+                    format!("{}::{}", path_str, item),
+                    Applicability::MaybeIncorrect,
+                );
+                true
+            }
+            None => false,
+        };
+
+        let mut bad_struct_syntax_suggestion = |def_id: DefId| {
+            // Where struct literals aren't allowed, the parser took the fields for a block.
+            let banned_struct_literals = sess.parse_sess.banned_struct_literals.borrow();
+            let struct_literal = banned_struct_literals.get(&span).copied();
+            let mut suggested = false;
+            match (source, struct_literal) {
+                (PathSource::Expr(Some(parent)), _) if path_sep(err, &parent) => {
+                    suggested = true;
+                }
+                (PathSource::Expr(_), Some(sp)) => {
+                    err.multipart_suggestion(
+                        &msg!(sess, "parenthesize-struct-literal"),
+                        vec![
+                            (sp.shrink_to_lo(), "(".to_string()),
+                            (sp.shrink_to_hi(), ")".to_string()),
+                        ],
+                        Applicability::MaybeIncorrect,
+                    );
+                    suggested = true;
+                }
+                _ => {}
+            }
+            if !suggested {
+                if let Some(span) = self.r.opt_span(def_id) {
+                    err.span_label(span, msg!(sess, "defined-here", path = path_str));
+                }
+                err.span_label(span, msg!(sess, "struct-literal-syntax", path = path_str));
+            }
+        };
+
+        match (res, source) {
             (Res::Def(DefKind::Macro(MacroKind::Bang), _), _) => {
                 err.span_suggestion_verbose(
                     span.shrink_to_hi(),
-                    "use `!` to invoke the macro",
+                    &msg!(sess, "invoke-macro"),
                     "!".to_string(),
                     Applicability::MaybeIncorrect,
                 );
-                if path_str == "try" && span.rust_2015() {
-                    err.note("if you want the `try` keyword, you need to be in the 2018 edition");
+                // `try { .. }` already got a note about `try` blocks.
+                let is_try_block = matches!(source, PathSource::Struct);
+                if path_str == "try" && span.rust_2015() && !is_try_block {
+                    err.note(&msg!(sess, "try-keyword-2018"));
                 }
             }
             (Res::Def(DefKind::TyAlias, def_id), PathSource::Trait(_)) => {
-                err.span_label(span, "type aliases cannot be used as traits");
+                err.span_label(span, msg!(sess, "alias-as-trait"));
                 if nightly_options::is_nightly_build() {
-                    let msg = "you might have meant to use `#![feature(trait_alias)]` instead of a \
-                               `type` alias";
+                    let msg = &msg!(sess, "use-trait-alias");
                     if let Some(span) = self.r.opt_span(def_id) {
-                        err.span_help(span, msg);
+                        // Trait aliases can't be implemented, only offer the rewrite in bounds.
+                        let sugg = match source {
+                            PathSource::Trait(AliasPossibility::Maybe) => {
+                                self.trait_alias_suggestion(def_id, span)
+                            }
+                            _ => None,
+                        };
+                        if let Some(sugg) = sugg {
+                            err.multipart_suggestion(msg, sugg, Applicability::MaybeIncorrect);
+                        } else {
+                            err.span_help(span, msg);
+                        }
                     } else {
                         err.help(msg);
                     }
@@ -546,24 +1799,45 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
                     return false;
                 }
             }
-            (Res::Def(DefKind::Enum, def_id), PathSource::TupleStruct | PathSource::Expr(..)) => {
+            // `Type.item` or `Type.method()` meant as `Type::item` or `Type::method()`.
+            (
+                Res::Def(
+                    DefKind::Enum
+                    | DefKind::Trait
+                    | DefKind::TraitAlias
+                    | DefKind::TyAlias
+                    | DefKind::TyParam
+                    | DefKind::AssocTy
+                    | DefKind::ForeignTy,
+                    _,
+                )
+                | Res::PrimTy(..)
+                | Res::SelfTy(..),
+                PathSource::Expr(Some(parent)),
+            ) if dot_item(parent).is_some() => {
+                path_sep(err, &parent);
+            }
+            (
+                Res::Def(DefKind::Enum, def_id),
+                PathSource::TupleStruct(_) | PathSource::Expr(..),
+            ) => {
                 if let Some(variants) = self.collect_enum_variants(def_id) {
                     if !variants.is_empty() {
                         let msg = if variants.len() == 1 {
-                            "try using the enum's variant"
+                            msg!(sess, "use-enum-variant")
                         } else {
-                            "try using one of the enum's variants"
+                            msg!(sess, "use-one-of-enum-variants")
                         };
 
                         err.span_suggestions(
                             span,
-                            msg,
+                            &msg,
                             variants.iter().map(path_names_to_string),
                             Applicability::MaybeIncorrect,
                         );
                     }
                 } else {
-                    err.note("did you mean to use one of the enum's variants?");
+                    err.note(&msg!(sess, "enum-variants-note"));
                 }
             }
             (Res::Def(DefKind::Struct, def_id), _) if ns == ValueNS => {
@@ -572,10 +1846,7 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
                     let accessible_ctor =
                         self.r.is_accessible_from(ctor_vis, self.parent_scope.module);
                     if is_expected(ctor_def) && !accessible_ctor {
-                        err.span_label(
-                            span,
-                            "constructor is not visible here due to private fields".to_string(),
-                        );
+                        err.span_label(span, msg!(sess, "private-ctor"));
                     }
                 } else {
                     bad_struct_syntax_suggestion(def_id);
@@ -592,22 +1863,42 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
             }
             (Res::Def(DefKind::Ctor(_, CtorKind::Fn), def_id), _) if ns == ValueNS => {
                 if let Some(span) = self.r.opt_span(def_id) {
-                    err.span_label(span, &format!("`{}` defined here", path_str));
+                    err.span_label(span, msg!(sess, "defined-here", path = path_str));
                 }
-                err.span_label(span, format!("did you mean `{}( /* fields */ )`?", path_str));
+                err.span_label(span, msg!(sess, "tuple-struct-syntax", path = path_str));
             }
             (Res::SelfTy(..), _) if ns == ValueNS => {
                 err.span_label(span, fallback_label);
-                err.note("can't use `Self` as a constructor, you must use the implemented struct");
+                err.note(&msg!(sess, "self-as-ctor"));
             }
             (Res::Def(DefKind::TyAlias | DefKind::AssocTy, _), _) if ns == ValueNS => {
-                err.note("can't use a type alias as a constructor");
+                err.note(&msg!(sess, "alias-as-ctor"));
             }
             _ => return false,
         }
         true
     }
 
+    /// Rewrites `type Alias = dyn Trait;` into `trait Alias = Trait;`, enabling the `trait_alias`
+    /// feature at the crate root if needed.
+    fn trait_alias_suggestion(
+        &self,
+        alias_def_id: DefId,
+        alias_span: Span,
+    ) -> Option<Vec<(Span, String)>> {
+        let sm = self.r.session.source_map();
+        let mut sugg = self.r.trait_object_aliases.get(&alias_def_id)?.clone();
+        if !self.r.session.features_untracked().trait_alias {
+            // The attribute can only be added if the alias is in the crate root's file.
+            let crate_span = self.r.graph_root.span;
+            if sm.span_to_filename(crate_span) != sm.span_to_filename(alias_span) {
+                return None;
+            }
+            sugg.push((crate_span.shrink_to_lo(), "#![feature(trait_alias)]\n".to_string()));
+        }
+        Some(sugg)
+    }
+
     fn lookup_assoc_candidate<FilterFn>(
         &mut self,
         ident: Ident,
@@ -654,9 +1945,13 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
             }
         }
 
-        for assoc_type_ident in &self.diagnostic_metadata.current_trait_assoc_types {
-            if *assoc_type_ident == ident {
-                return Some(AssocSuggestion::AssocItem);
+        // Associated types are only worth suggesting where a type is expected, and never as a
+        // fix for a pattern or an expression.
+        if filter_fn(Res::Def(DefKind::AssocTy, DefId::local(CRATE_DEF_INDEX))) {
+            for assoc_type_ident in &self.diagnostic_metadata.current_assoc_types {
+                if *assoc_type_ident == ident {
+                    return Some(AssocSuggestion::AssocItem);
+                }
             }
         }
 
@@ -691,21 +1986,32 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
         filter_fn: &impl Fn(Res) -> bool,
         span: Span,
     ) -> Option<TypoSuggestion> {
-        let mut names = Vec::new();
+        let _prof_timer = self.r.session.prof.generic_activity("resolve_lookup_typo_candidate");
+        let name = path[path.len() - 1].ident.name;
+        let lookup = name.as_str();
+        // Reuse the buffer of earlier lookups, it is handed back once a candidate is picked.
+        let mut names = take(&mut self.typo_candidates);
+        names.clear();
+        let mut truncated = false;
         if path.len() == 1 {
             // Search in lexical scope.
             // Walk backwards up the ribs in scope and collect candidates.
             for rib in self.ribs[ns].iter().rev() {
+                if names.len() >= MAX_TYPO_CANDIDATES {
+                    // The closest scopes already provide plenty of candidates.
+                    truncated = true;
+                    break;
+                }
                 // Locals and type parameters
                 for (ident, &res) in &rib.bindings {
-                    if filter_fn(res) {
+                    if filter_fn(res) && is_typo_candidate(&lookup, ident.name) {
                         names.push(TypoSuggestion::from_res(ident.name, res));
                     }
                 }
                 // Items in scope
                 if let RibKind::ModuleRibKind(module) = rib.kind {
                     // Items from this module
-                    self.r.add_module_candidates(module, &mut names, &filter_fn);
+                    truncated |= self.r.add_module_candidates(module, name, &mut names, &filter_fn);
 
                     if let ModuleKind::Block(..) = module.kind {
                         // We can see through blocks
@@ -713,26 +2019,40 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
                         // Items from the prelude
                         if !module.no_implicit_prelude {
                             let extern_prelude = self.r.extern_prelude.clone();
-                            names.extend(extern_prelude.iter().flat_map(|(ident, _)| {
-                                self.r
-                                    .crate_loader
-                                    .maybe_process_path_extern(ident.name, ident.span)
-                                    .and_then(|crate_id| {
-                                        let crate_mod = Res::Def(
-                                            DefKind::Mod,
-                                            DefId { krate: crate_id, index: CRATE_DEF_INDEX },
-                                        );
-
-                                        if filter_fn(crate_mod) {
-                                            Some(TypoSuggestion::from_res(ident.name, crate_mod))
-                                        } else {
-                                            None
-                                        }
-                                    })
+                            let extern_prelude = extern_prelude
+                                .iter()
+                                .filter(|(ident, _)| is_typo_candidate(&lookup, ident.name));
+                            // Crates aren't loaded only to be suggested, unless asked to.
+                            let load_crates =
+                                self.r.session.opts.debugging_opts.suggestions_load_crates;
+                            names.extend(extern_prelude.flat_map(|(ident, _)| {
+                                let crate_loader = &mut self.r.crate_loader;
+                                let crate_id = if load_crates {
+                                    crate_loader.maybe_process_path_extern(ident.name, ident.span)
+                                } else {
+                                    crate_loader.loaded_path_extern(ident.name)
+                                };
+                                crate_id.and_then(|crate_id| {
+                                    let crate_mod = Res::Def(
+                                        DefKind::Mod,
+                                        DefId { krate: crate_id, index: CRATE_DEF_INDEX },
+                                    );
+
+                                    if filter_fn(crate_mod) {
+                                        Some(TypoSuggestion::from_res(ident.name, crate_mod))
+                                    } else {
+                                        None
+                                    }
+                                })
                             }));
 
                             if let Some(prelude) = self.r.prelude {
-                                self.r.add_module_candidates(prelude, &mut names, &filter_fn);
+                                truncated |= self.r.add_module_candidates(
+                                    prelude,
+                                    name,
+                                    &mut names,
+                                    &filter_fn,
+                                );
                             }
                         }
                         break;
@@ -741,10 +2061,14 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
             }
             // Add primitive types to the mix
             if filter_fn(Res::PrimTy(PrimTy::Bool)) {
+                let primitive_types = &self.r.primitive_type_table.primitive_types;
                 names.extend(
-                    self.r.primitive_type_table.primitive_types.iter().map(|(name, prim_ty)| {
-                        TypoSuggestion::from_res(*name, Res::PrimTy(*prim_ty))
-                    }),
+                    primitive_types
+                        .iter()
+                        .filter(|(name, _)| is_typo_candidate(&lookup, **name))
+                        .map(|(name, prim_ty)| {
+                            TypoSuggestion::from_res(*name, Res::PrimTy(*prim_ty))
+                        }),
                 )
             }
         } else {
@@ -754,113 +2078,94 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
                 self.resolve_path(mod_path, Some(TypeNS), false, span, CrateLint::No)
             {
                 if let ModuleOrUniformRoot::Module(module) = module {
-                    self.r.add_module_candidates(module, &mut names, &filter_fn);
+                    truncated = self.r.add_module_candidates(module, name, &mut names, &filter_fn);
                 }
             }
         }
 
-        let name = path[path.len() - 1].ident.name;
-        // Make sure error reporting is deterministic.
-        names.sort_by_cached_key(|suggestion| suggestion.candidate.as_str());
+        sort_typo_candidates(&mut names);
 
-        match find_best_match_for_name(
-            names.iter().map(|suggestion| &suggestion.candidate),
-            &name.as_str(),
-            None,
-        ) {
+        let candidates = || names.iter().map(|suggestion| &suggestion.candidate);
+        let suggestion = match heuristics::find_best_typo(candidates(), &lookup) {
             Some(found) if found != name => {
-                names.into_iter().find(|suggestion| suggestion.candidate == found)
+                let applicability =
+                    heuristics::typo_applicability(candidates(), &lookup, found, truncated);
+                let index = names.iter().position(|suggestion| suggestion.candidate == found);
+                index.map(|index| (index, applicability))
             }
             _ => None,
+        };
+        let mut suggestion = suggestion.map(|(index, applicability)| TypoSuggestion {
+            applicability,
+            ..names.swap_remove(index)
+        });
+        self.typo_candidates = names;
+        // Candidates are collected ignoring shadowing and privacy, the rename is only certain if
+        // it resolves where the error is.
+        if let Some(suggestion) = &mut suggestion {
+            if suggestion.applicability == Applicability::MachineApplicable {
+                let mut renamed = path.to_vec();
+                let last = renamed.last_mut().unwrap();
+                last.ident = Ident::new(suggestion.candidate, last.ident.span);
+                if !self.suggested_segments_res(&renamed, ns, span).map_or(false, filter_fn) {
+                    suggestion.applicability = Applicability::MaybeIncorrect;
+                }
+            }
         }
+        suggestion
     }
 
-    /// Only used in a specific case of type ascription suggestions
-    fn get_colon_suggestion_span(&self, start: Span) -> Span {
-        let sm = self.r.session.source_map();
-        start.to(sm.next_point(start))
-    }
-
+    /// Suggests what might have been meant by the `:` of the innermost type ascription being
+    /// resolved, going by the tokens the parser saw around it.
     fn type_ascription_suggestion(&self, err: &mut DiagnosticBuilder<'_>, base_span: Span) {
-        let sm = self.r.session.source_map();
-        let base_snippet = sm.span_to_snippet(base_span);
-        if let Some(sp) = self.diagnostic_metadata.current_type_ascription.last() {
-            let mut sp = *sp;
-            loop {
-                // Try to find the `:`; bail on first non-':' / non-whitespace.
-                sp = sm.next_point(sp);
-                if let Ok(snippet) = sm.span_to_snippet(sp.to(sm.next_point(sp))) {
-                    let line_sp = sm.lookup_char_pos(sp.hi()).line;
-                    let line_base_sp = sm.lookup_char_pos(base_span.lo()).line;
-                    if snippet == ":" {
-                        let mut show_label = true;
-                        if line_sp != line_base_sp {
-                            err.span_suggestion_short(
-                                sp,
-                                "did you mean to use `;` here instead?",
-                                ";".to_string(),
-                                Applicability::MaybeIncorrect,
-                            );
-                        } else {
-                            let colon_sp = self.get_colon_suggestion_span(sp);
-                            let after_colon_sp =
-                                self.get_colon_suggestion_span(colon_sp.shrink_to_hi());
-                            if !sm
-                                .span_to_snippet(after_colon_sp)
-                                .map(|s| s == " ")
-                                .unwrap_or(false)
-                            {
-                                err.span_suggestion(
-                                    colon_sp,
-                                    "maybe you meant to write a path separator here",
-                                    "::".to_string(),
-                                    Applicability::MaybeIncorrect,
-                                );
-                                show_label = false;
-                            }
-                            if let Ok(base_snippet) = base_snippet {
-                                let mut sp = after_colon_sp;
-                                for _ in 0..100 {
-                                    // Try to find an assignment
-                                    sp = sm.next_point(sp);
-                                    let snippet = sm.span_to_snippet(sp.to(sm.next_point(sp)));
-                                    match snippet {
-                                        Ok(ref x) if x.as_str() == "=" => {
-                                            err.span_suggestion(
-                                                base_span,
-                                                "maybe you meant to write an assignment here",
-                                                format!("let {}", base_snippet),
-                                                Applicability::MaybeIncorrect,
-                                            );
-                                            show_label = false;
-                                            break;
-                                        }
-                                        Ok(ref x) if x.as_str() == "\n" => break,
-                                        Err(_) => break,
-                                        Ok(_) => {}
-                                    }
-                                }
-                            }
-                        }
-                        if show_label {
-                            err.span_label(
-                                base_span,
-                                "expecting a type here because of type ascription",
-                            );
-                        }
-                        break;
-                    } else if !snippet.trim().is_empty() {
-                        debug!("tried to find type ascription `:` token, couldn't find it");
-                        break;
-                    }
-                } else {
-                    break;
-                }
+        let sess = self.r.session;
+        let ascription = match self.diagnostic_metadata.current_type_ascription.last() {
+            Some(expr_span) => sess.parse_sess.type_ascriptions.borrow().get(expr_span).copied(),
+            None => return,
+        };
+        let ascription = match ascription {
+            Some(ascription) => ascription,
+            None => {
+                debug!("couldn't find the type ascription `:` token");
+                return;
             }
+        };
+        let colon_span = ascription.colon_span;
+        let mut show_label = true;
+        if ascription.followed_by_eq {
+            if let Ok(base_snippet) = sess.source_map().span_to_snippet(base_span) {
+                err.span_suggestion(
+                    base_span,
+                    &msg!(sess, "assignment-instead"),
+                    format!("let {}", base_snippet),
+                    Applicability::MaybeIncorrect,
+                );
+                show_label = false;
+            }
+        } else if ascription.type_on_next_line {
+            err.span_suggestion_short(
+                colon_span,
+                &msg!(sess, "semicolon-instead"),
+                ";".to_string(),
+                Applicability::MaybeIncorrect,
+            );
+        }
+        if ascription.type_adjacent {
+            err.span_suggestion(
+                colon_span,
+                &msg!(sess, "path-separator-instead"),
+                "::".to_string(),
+                Applicability::MaybeIncorrect,
+            );
+            show_label = false;
+        }
+        if show_label {
+            err.span_label(base_span, msg!(sess, "type-ascription-label"));
         }
     }
 
     fn find_module(&mut self, def_id: DefId) -> Option<(Module<'a>, ImportSuggestion)> {
+        let _prof_timer = self.r.session.prof.generic_activity("resolve_find_module");
         let mut result = None;
         let mut seen_modules = FxHashSet::default();
         let mut worklist = vec![(self.r.graph_root, Vec::new())];
@@ -890,6 +2195,7 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
                                 descr: "module",
                                 path,
                                 accessible: true,
+                                unstable: None,
                             },
                         ));
                     } else {
@@ -906,6 +2212,7 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
     }
 
     fn collect_enum_variants(&mut self, def_id: DefId) -> Option<Vec<Path>> {
+        let _prof_timer = self.r.session.prof.generic_activity("resolve_collect_enum_variants");
         self.find_module(def_id).map(|(enum_module, enum_import_suggestion)| {
             let mut variants = Vec::new();
             enum_module.for_each_child(self.r, |_, ident, _, name_binding| {
@@ -922,7 +2229,8 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
     crate fn report_missing_type_error(
         &self,
         path: &[Segment],
-    ) -> Option<(Span, &'static str, String, Applicability)> {
+        source: PathSource<'_>,
+    ) -> Option<(Span, String, String, Applicability)> {
         let (ident, span) = match path {
             [segment] if !segment.has_generic_args => {
                 (segment.ident.to_string(), segment.ident.span)
@@ -935,6 +2243,15 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
         if !self.diagnostic_metadata.currently_processing_generics && !single_uppercase_char {
             return None;
         }
+        // A value in an array length or const argument can only come from a const parameter,
+        // suggesting a type parameter there would produce invalid code.
+        let in_const_position =
+            matches!(source, PathSource::Expr(_)) && self.is_in_anon_const();
+        if in_const_position
+            && !(single_uppercase_char && self.r.session.features_untracked().const_generics)
+        {
+            return None;
+        }
         match (self.diagnostic_metadata.current_item, single_uppercase_char) {
             (Some(Item { kind: ItemKind::Fn(..), ident, .. }), _) if ident.name == sym::main => {
                 // Ignore `fn main()` as we don't want to suggest `fn main<T>()`
@@ -945,12 +2262,30 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
                         kind @ ItemKind::Fn(..)
                         | kind @ ItemKind::Enum(..)
                         | kind @ ItemKind::Struct(..)
-                        | kind @ ItemKind::Union(..),
+                        | kind @ ItemKind::Union(..)
+                        | kind @ ItemKind::Impl { .. }
+                        | kind @ ItemKind::Trait(..),
                     ..
                 }),
                 true,
             )
             | (Some(Item { kind, .. }), false) => {
+                match kind {
+                    // `impl A {}` and `impl A for B {}` are missing an item, not a parameter.
+                    ItemKind::Impl { self_ty, of_trait, .. }
+                        if self_ty.span == span
+                            || of_trait.as_ref().map_or(false, |t| t.path.span == span) =>
+                    {
+                        return None;
+                    }
+                    // A type parameter can't be used as a supertrait.
+                    ItemKind::Trait(_, _, _, bounds, _)
+                        if bounds.iter().any(|bound| bound.span().contains(span)) =>
+                    {
+                        return None;
+                    }
+                    _ => {}
+                }
                 // Likely missing type parameter.
                 if let Some(generics) = kind.generics() {
                     if span.overlaps(generics.span) {
@@ -964,21 +2299,46 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
                         //   |           not found in this scope
                         return None;
                     }
-                    let msg = "you might be missing a type parameter";
-                    let (span, sugg) = if let [.., param] = &generics.params[..] {
-                        let span = if let [.., bound] = &param.bounds[..] {
-                            bound.span()
-                        } else {
-                            param.ident.span
-                        };
-                        (span, format!(", {}", ident))
+                    let (msg, new_param) = if in_const_position {
+                        let new_param = format!("const {}: usize", ident);
+                        (msg!(self.r.session, "missing-const-param"), new_param)
                     } else {
-                        (generics.span, format!("<{}>", ident))
+                        (msg!(self.r.session, "missing-type-param"), ident)
+                    };
+                    // Parameters are ordered lifetimes, then types, then consts, and type
+                    // parameters with a default must come after those without one. A new type
+                    // parameter thus goes before the first const or defaulted type parameter,
+                    // anything else after the last parameter.
+                    let first_after_new_type = generics.params.iter().find(|param| {
+                        matches!(
+                            param.kind,
+                            GenericParamKind::Const { .. }
+                                | GenericParamKind::Type { default: Some(_) }
+                        )
+                    });
+                    let (span, sugg) = match (first_after_new_type, &generics.params[..]) {
+                        (Some(param), _) if !in_const_position => {
+                            let span = match param.kind {
+                                GenericParamKind::Const { kw_span, .. } => kw_span,
+                                _ => param.ident.span,
+                            };
+                            (span.shrink_to_lo(), format!("{}, ", new_param))
+                        }
+                        (_, [.., param]) => {
+                            let span = match (&param.kind, &param.bounds[..]) {
+                                (GenericParamKind::Const { ty, .. }, _) => ty.span,
+                                (GenericParamKind::Type { default: Some(ty) }, _) => ty.span,
+                                (_, [.., bound]) => bound.span(),
+                                _ => param.ident.span,
+                            };
+                            (span.shrink_to_hi(), format!(", {}", new_param))
+                        }
+                        (_, []) => (generics.span.shrink_to_hi(), format!("<{}>", new_param)),
                     };
                     // Do not suggest if this is coming from macro expansion.
                     if !span.from_expansion() {
                         return Some((
-                            span.shrink_to_hi(),
+                            span,
                             msg,
                             sugg,
                             Applicability::MaybeIncorrect,
@@ -986,71 +2346,583 @@ impl<'a> LateResolutionVisitor<'a, '_, '_> {
                     }
                 }
             }
-            _ => {}
-        }
-        None
+            _ => {}
+        }
+        None
+    }
+
+    /// When all the import `candidates` for an unresolved bare name are variants of a single enum
+    /// in scope, suggests qualifying the name with that enum. Returns the enum's path, to
+    /// alternatively suggest importing all of its variants.
+    crate fn suggest_qualified_variant(
+        &mut self,
+        err: &mut DiagnosticBuilder<'_>,
+        path: &[Segment],
+        span: Span,
+        source: PathSource<'_>,
+        candidates: &[ImportSuggestion],
+    ) -> Option<String> {
+        let ident = match path {
+            [segment] if !segment.has_generic_args => segment.ident,
+            _ => return None,
+        };
+        let first = candidates.first()?;
+        let span = self.r.suggestion_span(span).filter(|span| !span.from_expansion())?;
+        if candidates.iter().any(|candidate| !candidate.accessible) {
+            return None;
+        }
+        let is_variant = |res| {
+            let is_variant =
+                matches!(res, Res::Def(DefKind::Variant | DefKind::Ctor(CtorOf::Variant, _), _));
+            is_variant && source.is_expected(res)
+        };
+        let ns = source.namespace();
+        let variants = self.r.lookup_import_candidates(ident, ns, &self.parent_scope, is_variant);
+        if variants.len() != candidates.len() {
+            return None;
+        }
+        let enum_did = self.r.parent(first.did?)?;
+        let same_enum = |candidate: &ImportSuggestion| {
+            candidate.did.and_then(|did| self.r.parent(did)) == Some(enum_did)
+        };
+        if !candidates.iter().all(same_enum) {
+            return None;
+        }
+        // The enum has to be nameable from here under the name of its definition.
+        let (_, enum_path) = import_candidate_to_enum_paths(first);
+        let enum_name = first.path.segments[first.path.segments.len() - 2].ident.name;
+        let enum_ident = Ident::new(enum_name, span);
+        let binding = self.resolve_ident_in_lexical_scope(enum_ident, TypeNS, None, span);
+        if binding.map(|binding| binding.res()) != Some(Res::Def(DefKind::Enum, enum_did)) {
+            return None;
+        }
+        err.span_suggestion_verbose(
+            span,
+            &msg!(self.r.session, "use-variant-path"),
+            format!("{}::{}", enum_ident, ident),
+            Applicability::MachineApplicable,
+        );
+        Some(enum_path)
+    }
+
+    /// Whether `path_str`, suggested in place of the path at `span`, resolves there to something
+    /// that `is_expected`. Suggestions which don't can't be applied without a second look.
+    fn suggested_path_resolves(
+        &mut self,
+        path_str: &str,
+        span: Span,
+        is_expected: &impl Fn(Res) -> bool,
+    ) -> bool {
+        self.suggested_path_res(path_str, span).map_or(false, is_expected)
+    }
+
+    /// What `path_str`, suggested in place of the path at `span`, resolves to there.
+    fn suggested_path_res(&mut self, path_str: &str, span: Span) -> Option<Res> {
+        let path = path_str
+            .split("::")
+            .map(|name| Segment::from_ident(Ident::new(Symbol::intern(name), span)))
+            .collect::<Vec<_>>();
+        self.suggested_segments_res(&path, TypeNS, span)
+    }
+
+    /// What the suggested `path` resolves to in `ns` in place of the path at `span`.
+    fn suggested_segments_res(
+        &mut self,
+        path: &[Segment],
+        ns: Namespace,
+        span: Span,
+    ) -> Option<Res> {
+        let parent_scope = &self.parent_scope;
+        let ribs = Some(&self.ribs);
+        self.r.speculative_resolve_path(path, ns, parent_scope, span, ribs, false)
+    }
+
+    /// Whether the path being resolved is inside an anonymous constant, such as an array length
+    /// or a braced const argument.
+    fn is_in_anon_const(&self) -> bool {
+        for rib in self.ribs[ValueNS].iter().rev() {
+            match rib.kind {
+                RibKind::ConstantItemRibKind(None) => return true,
+                RibKind::ConstantItemRibKind(Some(_))
+                | RibKind::ItemRibKind(_)
+                | RibKind::FnItemRibKind(_)
+                | RibKind::ModuleRibKind(_) => return false,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// Given the target `label`, search the `rib_index`th label rib for similarly named labels,
+    /// optionally returning the closest match and whether it is reachable.
+    crate fn suggestion_for_label_in_rib(
+        &self,
+        rib_index: usize,
+        label: Ident,
+    ) -> Option<LabelSuggestion> {
+        // Are ribs from this `rib_index` within scope?
+        let within_scope = self.is_label_valid_from_rib(rib_index);
+
+        let rib = &self.label_ribs[rib_index];
+        let names = rib
+            .bindings
+            .iter()
+            .filter(|(id, _)| id.span.ctxt() == label.span.ctxt())
+            .map(|(id, _)| &id.name);
+
+        find_best_match_for_name(names, &label.as_str(), None).map(|symbol| {
+            // Upon finding a similar name, get the ident that it was from - the span
+            // contained within helps make a useful diagnostic. In addition, determine
+            // whether this candidate is within scope.
+            let (ident, _) = rib.bindings.iter().find(|(ident, _)| ident.name == symbol).unwrap();
+            (*ident, within_scope)
+        })
+    }
+
+    /// A lowercase binding used as a whole match arm pattern, e.g. `none => ...`, is easily
+    /// mistaken for a unit variant. Lint if its name is close to a unit variant in scope. The
+    /// type of the scrutinee isn't known yet, so the lint is allow-by-default.
+    crate fn lint_binding_similar_to_variant(&mut self, ident: Ident, pat_id: NodeId) {
+        let name = ident.as_str();
+        if ident.span.from_expansion() || !name.starts_with(|c: char| c.is_lowercase()) {
+            return;
+        }
+
+        // Collect the modules whose items are nameable from here, as in `lookup_typo_candidate`.
+        let mut modules = Vec::new();
+        for rib in self.ribs[ValueNS].iter().rev() {
+            if let RibKind::ModuleRibKind(module) = rib.kind {
+                modules.push(module);
+                if let ModuleKind::Block(..) = module.kind {
+                    continue;
+                }
+                if !module.no_implicit_prelude {
+                    modules.extend(self.r.prelude);
+                }
+                break;
+            }
+        }
+
+        // Unit variants imported directly (e.g. `None`), and enums whose variants can be named
+        // through the enum (e.g. `Color::Red`).
+        let mut candidates = Vec::new();
+        let mut enums = Vec::new();
+        for module in modules {
+            module.for_each_child(self.r, |_, ident, ns, binding| match (ns, binding.res()) {
+                (ValueNS, Res::Def(DefKind::Ctor(CtorOf::Variant, CtorKind::Const), _)) => {
+                    candidates.push((ident.name, ident.to_string()))
+                }
+                (TypeNS, Res::Def(DefKind::Enum, def_id)) => enums.push((ident, def_id)),
+                _ => {}
+            });
+        }
+        for (enum_ident, def_id) in enums {
+            let enum_module = self.r.get_module(def_id);
+            enum_module.for_each_child(self.r, |_, ident, ns, binding| {
+                if let (ValueNS, Res::Def(DefKind::Ctor(_, CtorKind::Const), _)) =
+                    (ns, binding.res())
+                {
+                    candidates.push((ident.name, format!("{}::{}", enum_ident, ident)));
+                }
+            });
+        }
+        // Make sure the lint is deterministic.
+        candidates.sort_by(|a, b| a.1.cmp(&b.1));
+
+        // Bindings with the exact name of a variant are linted during type checking, where
+        // the matched type is known. Other than case differences, only consider typos that
+        // keep the first letter, short names are too easily within edit distance otherwise.
+        let lookup = name.to_uppercase();
+        let max_dist = lookup.len() / 3;
+        let best = candidates
+            .into_iter()
+            .filter(|(candidate, _)| *candidate != ident.name)
+            .filter_map(|(candidate, path)| {
+                let candidate = candidate.as_str().to_uppercase();
+                let dist = lev_distance(&lookup, &candidate);
+                let same_initial = candidate.chars().next() == lookup.chars().next();
+                let similar = dist == 0 || (dist <= max_dist && same_initial);
+                if similar { Some((dist, path)) } else { None }
+            })
+            .min_by_key(|&(dist, _)| dist);
+
+        if let Some((_, path)) = best {
+            self.r.lint_buffer.buffer_lint_with_diagnostic(
+                lint::builtin::BINDINGS_SIMILAR_TO_VARIANT_NAME,
+                pat_id,
+                ident.span,
+                &msg!(self.r.session, "binding-similar-to-variant", name = name, variant = path),
+                BuiltinLintDiagnostics::BindingSimilarToVariant(ident.span, path),
+            );
+        }
+    }
+
+    /// Within an impl, a path starting with the self type written out in full, generic arguments
+    /// included, could start with `Self` instead. Lint it, as long as `Self` is usable there.
+    crate fn lint_spelled_out_self_type(
+        &mut self,
+        id: NodeId,
+        path: &Path,
+        partial_res: PartialRes,
+    ) {
+        let self_ty = match &self.diagnostic_metadata.current_self_type {
+            Some(ty) => ty,
+            None => return,
+        };
+        let self_segment = match &self_ty.kind {
+            TyKind::Path(None, self_path) => self_path.segments.last().unwrap(),
+            _ => return,
+        };
+        let self_res = match self.r.partial_res_map.get(&self_ty.id) {
+            Some(res) if res.unresolved_segments() == 0 => res.base_res(),
+            _ => return,
+        };
+        if !matches!(self_res, Res::Def(DefKind::Struct | DefKind::Union | DefKind::Enum, _))
+            || partial_res.base_res() != self_res
+            || path.span.from_expansion()
+        {
+            return;
+        }
+        // Trailing segments are associated items, left for type checking to resolve.
+        let segment = &path.segments[path.segments.len() - partial_res.unresolved_segments() - 1];
+        // `Self` isn't available in items nested in the impl's items.
+        let self_ident = Ident::new(kw::SelfUpper, path.span);
+        let binding = self.resolve_ident_in_lexical_scope(self_ident, TypeNS, None, path.span);
+        if !matches!(binding.map(|binding| binding.res()), Some(Res::SelfTy(_, Some(_))))
+            || !self.generic_args_match_self_type(segment, self_segment)
+        {
+            return;
+        }
+        let hi = segment.args.as_ref().map_or(segment.ident.span, |args| args.span()).hi();
+        let span = path.span.with_hi(hi);
+        self.r.lint_buffer.buffer_lint_with_diagnostic(
+            lint::builtin::SPELLED_OUT_SELF_TYPES,
+            id,
+            span,
+            &msg!(self.r.session, "spelled-out-self-type"),
+            BuiltinLintDiagnostics::SpelledOutSelfType(span),
+        );
+    }
+
+    /// Whether the generic args of `segment` are the impl's own parameters, in the order the self
+    /// type `self_segment` passes them.
+    fn generic_args_match_self_type(
+        &mut self,
+        segment: &ast::PathSegment,
+        self_segment: &ast::PathSegment,
+    ) -> bool {
+        use ast::GenericArgs::AngleBracketed;
+        let (args, self_args) = match (&segment.args, &self_segment.args) {
+            (None, None) => return true,
+            (Some(args), Some(self_args)) => match (&**args, &**self_args) {
+                (AngleBracketed(args), AngleBracketed(self_args))
+                    if args.args.len() == self_args.args.len() =>
+                {
+                    (&args.args, &self_args.args)
+                }
+                _ => return false,
+            },
+            _ => return false,
+        };
+        args.iter().zip(self_args).all(|(arg, self_arg)| match (arg, self_arg) {
+            (
+                ast::AngleBracketedArg::Arg(ast::GenericArg::Lifetime(lifetime)),
+                ast::AngleBracketedArg::Arg(ast::GenericArg::Lifetime(self_lifetime)),
+            ) => lifetime.ident.name == self_lifetime.ident.name,
+            (
+                ast::AngleBracketedArg::Arg(ast::GenericArg::Type(ty)),
+                ast::AngleBracketedArg::Arg(ast::GenericArg::Type(self_ty)),
+            ) => {
+                // The self type has been resolved already, but the args of `segment` are only
+                // resolved once the path itself is.
+                let self_res = match self.r.partial_res_map.get(&self_ty.id) {
+                    Some(res) if res.unresolved_segments() == 0 => res.base_res(),
+                    _ => return false,
+                };
+                let ident = match &ty.kind {
+                    TyKind::Path(None, path)
+                        if path.segments.len() == 1 && path.segments[0].args.is_none() =>
+                    {
+                        path.segments[0].ident
+                    }
+                    _ => return false,
+                };
+                let res = self
+                    .resolve_ident_in_lexical_scope(ident, TypeNS, None, ty.span)
+                    .map(|binding| binding.res());
+                matches!(self_res, Res::Def(DefKind::TyParam, _)) && res == Some(self_res)
+            }
+            _ => false,
+        })
     }
 
-    /// Given the target `label`, search the `rib_index`th label rib for similarly named labels,
-    /// optionally returning the closest match and whether it is reachable.
-    crate fn suggestion_for_label_in_rib(
-        &self,
-        rib_index: usize,
-        label: Ident,
-    ) -> Option<LabelSuggestion> {
-        // Are ribs from this `rib_index` within scope?
-        let within_scope = self.is_label_valid_from_rib(rib_index);
+    /// Reports the uses of generic parameters of outer items found by the last resolution, along
+    /// with the bounds to declare them with on the nested item.
+    crate fn report_outer_generic_param_uses(&mut self) {
+        for (span, res, has_generic_params) in take(&mut self.r.outer_generic_param_uses) {
+            let has_generic_params = match has_generic_params {
+                // Only suggest declaring each parameter once on a nested item.
+                HasGenericParams::Yes(generics_span)
+                    if !generics_span.is_dummy()
+                        && !self
+                            .diagnostic_metadata
+                            .suggested_outer_generic_params
+                            .insert((generics_span, res)) =>
+                {
+                    HasGenericParams::No
+                }
+                has_generic_params => has_generic_params,
+            };
+            let bounds = self.outer_generic_param_bounds(res);
+            self.r.report_error(
+                span,
+                ResolutionError::GenericParamsFromOuterFunction(res, has_generic_params, bounds),
+            );
+        }
+    }
 
-        let rib = &self.label_ribs[rib_index];
-        let names = rib
-            .bindings
+    /// The trait bounds of the outer type parameter `res`, from its declaration and where clause,
+    /// or the type of the outer const parameter `res`.
+    fn outer_generic_param_bounds(&self, res: Res) -> Vec<Span> {
+        let def_id = match res {
+            Res::Def(DefKind::TyParam | DefKind::ConstParam, def_id) => def_id,
+            _ => return Vec::new(),
+        };
+        let (generics, param) = match self
+            .diagnostic_metadata
+            .current_generics
             .iter()
-            .filter(|(id, _)| id.span.ctxt() == label.span.ctxt())
-            .map(|(id, _)| &id.name);
+            .flat_map(|generics| generics.params.iter().map(move |param| (generics, param)))
+            .find(|(_, param)| self.r.local_def_id(param.id).to_def_id() == def_id)
+        {
+            Some(found) => found,
+            None => return Vec::new(),
+        };
+        let bounds = match param.kind {
+            GenericParamKind::Type { .. } => &param.bounds,
+            GenericParamKind::Const { ref ty, .. } => return vec![ty.span],
+            GenericParamKind::Lifetime => return Vec::new(),
+        };
+        let is_param =
+            |ty: &Ty| matches!(&ty.kind, TyKind::Path(None, path) if *path == param.ident.name);
+        let where_bounds = generics
+            .where_clause
+            .predicates
+            .iter()
+            .filter_map(|predicate| match predicate {
+                WherePredicate::BoundPredicate(predicate) if is_param(&predicate.bounded_ty) => {
+                    Some(&predicate.bounds)
+                }
+                _ => None,
+            })
+            .flatten();
+        bounds
+            .iter()
+            .chain(where_bounds)
+            .filter(|bound| matches!(bound, GenericBound::Trait(_, TraitBoundModifier::None)))
+            .map(|bound| bound.span())
+            .collect()
+    }
 
-        find_best_match_for_name(names, &label.as_str(), None).map(|symbol| {
-            // Upon finding a similar name, get the ident that it was from - the span
-            // contained within helps make a useful diagnostic. In addition, determine
-            // whether this candidate is within scope.
-            let (ident, _) = rib.bindings.iter().find(|(ident, _)| ident.name == symbol).unwrap();
-            (*ident, within_scope)
-        })
+    /// Records the number of parameters of the associated `new` functions of an inherent impl,
+    /// to suggest them for `new Type(<args>)`.
+    crate fn record_new_fns(&mut self, self_ty: &Ty, impl_items: &[P<AssocItem>]) {
+        let def_id = match self.r.partial_res_map.get(&self_ty.id) {
+            Some(res) if res.unresolved_segments() == 0 => match res.base_res() {
+                Res::Def(_, def_id) => def_id,
+                _ => return,
+            },
+            _ => return,
+        };
+        for item in impl_items {
+            match item.kind {
+                AssocItemKind::Fn(_, ref sig, ..)
+                    if item.ident.name == sym::new && !sig.decl.has_self() =>
+                {
+                    let counts = self.r.new_fn_param_counts.entry(def_id).or_default();
+                    counts.push(sig.decl.inputs.len());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Takes the error that the parser stashed for the erroneous expression at `span`, if it was
+    /// recovered from `new Type(<args>)`, to report it once all the local impls have been seen.
+    crate fn take_new_operator_error(&mut self, span: Span) {
+        let new_expr = match self.r.session.parse_sess.new_exprs.borrow_mut().remove(&span) {
+            Some(new_expr) => new_expr,
+            None => return,
+        };
+        let diagnostic = self.r.session.diagnostic();
+        let err = match diagnostic.steal_diagnostic(new_expr.new_span, StashKey::NewOperator) {
+            Some(err) => err,
+            None => return,
+        };
+        let segments: Vec<_> =
+            new_expr.ty_path.iter().map(|&ident| Segment::from_ident(ident)).collect();
+        let ty_span = new_expr.ty_span;
+        let result = self.resolve_path(&segments, Some(TypeNS), false, ty_span, CrateLint::No);
+        let ty_res = match result {
+            PathResult::NonModule(res) if res.unresolved_segments() == 0 => Some(res.base_res()),
+            PathResult::Module(ModuleOrUniformRoot::Module(module)) => module.res(),
+            _ => None,
+        };
+        self.r.new_operator_errors.push(NewOperatorError {
+            err,
+            new_span: new_expr.new_span,
+            ty_span: new_expr.ty_span,
+            call_span: span,
+            ty_res,
+            arg_count: new_expr.arg_count,
+        });
     }
 }
 
 impl<'tcx> LifetimeContext<'_, 'tcx> {
     crate fn report_missing_lifetime_specifiers(
         &self,
-        span: Span,
+        spans: Vec<Span>,
         count: usize,
     ) -> DiagnosticBuilder<'tcx> {
+        let sess = self.tcx.sess;
         struct_span_err!(
-            self.tcx.sess,
-            span,
+            sess,
+            spans,
             E0106,
-            "missing lifetime specifier{}",
-            pluralize!(count)
+            "{}",
+            msg!(sess, "missing-lifetime-specifier", s = pluralize!(count))
         )
     }
 
     crate fn emit_undeclared_lifetime_error(&self, lifetime_ref: &hir::Lifetime) {
+        let sess = self.tcx.sess;
         let mut err = struct_span_err!(
-            self.tcx.sess,
+            sess,
             lifetime_ref.span,
             E0261,
-            "use of undeclared lifetime name `{}`",
-            lifetime_ref
+            "{}",
+            msg!(sess, "undeclared-lifetime", lifetime = lifetime_ref)
         );
-        err.span_label(lifetime_ref.span, "undeclared lifetime");
+        err.span_label(lifetime_ref.span, msg!(sess, "undeclared-lifetime-label"));
+        if let Some(param_span) = self.type_param_named_like(lifetime_ref) {
+            // `'T` for the type parameter `T`, introducing a lifetime isn't what's wanted.
+            let name = lifetime_ref.name.ident().as_str().trim_start_matches('\'').to_string();
+            err.span_label(param_span, msg!(sess, "type-param-declared-here", name = name));
+            err.span_suggestion(
+                lifetime_ref.span,
+                &msg!(sess, "remove-lifetime-apostrophe"),
+                name,
+                Applicability::MaybeIncorrect,
+            );
+            err.emit();
+            return;
+        }
+        if self.is_returned_trait_object_bound(lifetime_ref) {
+            // Introducing a new lifetime is rarely what's wanted for the bound of a returned trait
+            // object: it either borrows from the arguments or owns all of its data.
+            err.span_suggestion(
+                lifetime_ref.span,
+                &msg!(sess, "trait-object-borrows"),
+                "'_".to_string(),
+                Applicability::MaybeIncorrect,
+            );
+            err.span_suggestion(
+                lifetime_ref.span,
+                &msg!(sess, "trait-object-owns"),
+                "'static".to_string(),
+                Applicability::MaybeIncorrect,
+            );
+            err.emit();
+            return;
+        }
         let mut suggests_in_band = false;
-        for missing in &self.missing_named_lifetime_spots {
+        // A lifetime in an impl header can only be introduced in the impl's own generics, no
+        // matter which other generics happen to be on the stack.
+        let hir = self.tcx.hir();
+        let parent = hir.find(hir.get_parent_item(lifetime_ref.hir_id));
+        let impl_spot = match parent {
+            Some(hir::Node::Item(hir::Item { kind: hir::ItemKind::Impl { generics, .. }, .. })) => {
+                Some(MissingLifetimeSpot::Generics(generics))
+            }
+            _ => None,
+        };
+        // `const` and `static` items can't have generics of their own, so the lifetime can't be
+        // introduced on them.
+        let (const_item, in_assoc_const) = match parent {
+            Some(hir::Node::Item(item)) => match item.kind {
+                hir::ItemKind::Const(..) => (Some("const"), false),
+                hir::ItemKind::Static(..) => (Some("static"), false),
+                _ => (None, false),
+            },
+            Some(hir::Node::ForeignItem(hir::ForeignItem {
+                kind: hir::ForeignItemKind::Static(..),
+                ..
+            })) => (Some("static"), false),
+            Some(hir::Node::TraitItem(hir::TraitItem {
+                kind: hir::TraitItemKind::Const(..),
+                ..
+            }))
+            | Some(hir::Node::ImplItem(hir::ImplItem {
+                kind: hir::ImplItemKind::Const(..),
+                ..
+            })) => (None, true),
+            _ => (None, false),
+        };
+        // The generics of an impl can't introduce a lifetime for its associated items unless its
+        // header names it, which is only suggested through the spots the items themselves pushed.
+        let spots = &self.missing_named_lifetime_spots;
+        let first_usable_spot = spots
+            .iter()
+            .rposition(|missing| match missing {
+                MissingLifetimeSpot::ImplHeader { .. } | MissingLifetimeSpot::Static => true,
+                _ => false,
+            })
+            .map_or(0, |i| match spots[i] {
+                MissingLifetimeSpot::Static => i + 1,
+                _ => i,
+            });
+        let in_static_spot = first_usable_spot > 0 && first_usable_spot == spots.len();
+        if const_item.is_some() || in_assoc_const || in_static_spot {
+            err.span_suggestion(
+                lifetime_ref.span,
+                &msg!(sess, "use-static-lifetime"),
+                "'static".to_string(),
+                Applicability::MaybeIncorrect,
+            );
+        }
+        if let Some(kind) = const_item {
+            err.note(&msg!(sess, "static-only-in-item", kind = kind));
+        }
+        if self.is_inferable_in_body(lifetime_ref) {
+            err.span_suggestion(
+                lifetime_ref.span,
+                &msg!(sess, "use-anonymous-lifetime"),
+                "'_".to_string(),
+                Applicability::MachineApplicable,
+            );
+        }
+        let missing_spots = impl_spot.iter().chain(
+            spots[first_usable_spot..].iter().filter(|missing| match missing {
+                MissingLifetimeSpot::Generics(_) | MissingLifetimeSpot::ImplHeader { .. } => {
+                    impl_spot.is_none()
+                }
+                MissingLifetimeSpot::HigherRanked { .. } => true,
+                MissingLifetimeSpot::Static => false,
+            }),
+        );
+        for missing in missing_spots {
             match missing {
-                MissingLifetimeSpot::Generics(generics) => {
+                MissingLifetimeSpot::Generics(generics)
+                | MissingLifetimeSpot::ImplHeader { generics, .. } => {
                     let (span, sugg) = if let Some(param) =
                         generics.params.iter().find(|p| match p.kind {
                             hir::GenericParamKind::Type {
                                 synthetic: Some(hir::SyntheticTyParamKind::ImplTrait),
                                 ..
+                            }
+                            | hir::GenericParamKind::Lifetime {
+                                kind: hir::LifetimeParamKind::Elided,
                             } => false,
                             _ => true,
                         }) {
@@ -1059,43 +2931,227 @@ impl<'tcx> LifetimeContext<'_, 'tcx> {
                         suggests_in_band = true;
                         (generics.span, format!("<{}>", lifetime_ref))
                     };
-                    err.span_suggestion(
-                        span,
-                        &format!("consider introducing lifetime `{}` here", lifetime_ref),
-                        sugg,
-                        Applicability::MaybeIncorrect,
-                    );
+                    let msg = msg!(sess, "introduce-lifetime", lifetime = lifetime_ref);
+                    if let MissingLifetimeSpot::ImplHeader { elided_span, .. } = missing {
+                        let rename = self.elided_lifetime_renamed(*elided_span, lifetime_ref);
+                        err.multipart_suggestion(
+                            &msg,
+                            vec![(span, sugg), (*elided_span, rename)],
+                            Applicability::MaybeIncorrect,
+                        );
+                    } else {
+                        err.span_suggestion(span, &msg, sugg, Applicability::MaybeIncorrect);
+                    }
                 }
                 MissingLifetimeSpot::HigherRanked { span, span_type } => {
                     err.span_suggestion(
                         *span,
-                        &format!(
-                            "consider making the {} lifetime-generic with a new `{}` lifetime",
-                            span_type.descr(),
-                            lifetime_ref
+                        &msg!(
+                            sess,
+                            "make-lifetime-generic",
+                            kind = span_type.descr(),
+                            lifetime = lifetime_ref,
                         ),
                         span_type.suggestion(&lifetime_ref.to_string()),
                         Applicability::MaybeIncorrect,
                     );
-                    err.note(
-                        "for more information on higher-ranked polymorphism, visit \
-                            https://doc.rust-lang.org/nomicon/hrtb.html",
-                    );
+                    err.note(&msg!(sess, "higher-ranked-note"));
                 }
+                MissingLifetimeSpot::Static => {}
             }
         }
+        if self.is_single_use_in_elidable_position(lifetime_ref) {
+            err.span_suggestion(
+                lifetime_ref.span,
+                &msg!(sess, "use-single-use-anonymous-lifetime", lifetime = lifetime_ref),
+                "'_".to_string(),
+                Applicability::MaybeIncorrect,
+            );
+        }
         if nightly_options::is_nightly_build()
             && !self.tcx.features().in_band_lifetimes
             && suggests_in_band
+            && self.in_band_lifetimes_would_apply(lifetime_ref)
         {
-            err.help(
-                "if you want to experiment with in-band lifetime bindings, \
-                    add `#![feature(in_band_lifetimes)]` to the crate attributes",
-            );
+            err.help(&msg!(sess, "in-band-lifetimes-help"));
         }
         err.emit();
     }
 
+    /// The span of the type parameter in scope named like `lifetime_ref` without its apostrophe.
+    fn type_param_named_like(&self, lifetime_ref: &hir::Lifetime) -> Option<Span> {
+        let lifetime = lifetime_ref.name.ident();
+        let name = lifetime.as_str();
+        let name = name.trim_start_matches('\'');
+        self.missing_named_lifetime_spots.iter().rev().find_map(|missing| {
+            missing.generics()?.params.iter().find_map(|param| match param.kind {
+                hir::GenericParamKind::Type { synthetic: None, .. }
+                    if &*param.name.ident().as_str() == name =>
+                {
+                    Some(param.span)
+                }
+                _ => None,
+            })
+        })
+    }
+
+    /// The spot introducing a lifetime on the impl of `impl_item` along with naming an elided
+    /// lifetime of its header, if it has one that can be named.
+    crate fn impl_header_lifetime_spot(
+        &self,
+        impl_item: &hir::ImplItem<'_>,
+    ) -> Option<MissingLifetimeSpot<'tcx>> {
+        let hir = self.tcx.hir();
+        let generics = match hir.find(hir.get_parent_item(impl_item.hir_id)) {
+            Some(hir::Node::Item(hir::Item { kind: hir::ItemKind::Impl { generics, .. }, .. })) => {
+                generics
+            }
+            _ => return None,
+        };
+        let source_map = self.tcx.sess.source_map();
+        generics.params.iter().find_map(|param| match param.kind {
+            hir::GenericParamKind::Lifetime { kind: hir::LifetimeParamKind::Elided } => {
+                match source_map.span_to_snippet(param.span).as_deref() {
+                    Ok("&") | Ok("'_") => {
+                        Some(MissingLifetimeSpot::ImplHeader { generics, elided_span: param.span })
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+    }
+
+    /// The replacement of the elided lifetime at `elided_span`, a `&` or `'_`, naming it `name`.
+    fn elided_lifetime_renamed(&self, elided_span: Span, name: impl fmt::Display) -> String {
+        match self.tcx.sess.source_map().span_to_snippet(elided_span).as_deref() {
+            Ok("&") => format!("&{} ", name),
+            _ => name.to_string(),
+        }
+    }
+
+    /// Whether enabling `in_band_lifetimes` would define `lifetime_ref`. In-band lifetimes are only
+    /// collected from function signatures and impl headers, and can't be mixed with explicitly
+    /// declared lifetimes.
+    fn in_band_lifetimes_would_apply(&self, lifetime_ref: &hir::Lifetime) -> bool {
+        for (_, node) in self.tcx.hir().parent_iter(lifetime_ref.hir_id) {
+            let generics = match node {
+                hir::Node::Item(hir::Item { kind: hir::ItemKind::Fn(_, generics, _), .. })
+                | hir::Node::Item(hir::Item { kind: hir::ItemKind::Impl { generics, .. }, .. })
+                | hir::Node::TraitItem(hir::TraitItem {
+                    kind: hir::TraitItemKind::Fn(..),
+                    generics,
+                    ..
+                })
+                | hir::Node::ImplItem(hir::ImplItem {
+                    kind: hir::ImplItemKind::Fn(..),
+                    generics,
+                    ..
+                })
+                | hir::Node::ForeignItem(hir::ForeignItem {
+                    kind: hir::ForeignItemKind::Fn(_, _, generics),
+                    ..
+                }) => generics,
+                hir::Node::Item(_)
+                | hir::Node::TraitItem(_)
+                | hir::Node::ImplItem(_)
+                | hir::Node::ForeignItem(_)
+                | hir::Node::Expr(_)
+                | hir::Node::Local(_)
+                | hir::Node::Block(_)
+                | hir::Node::AnonConst(_) => return false,
+                _ => continue,
+            };
+            return !generics.params.iter().any(|param| {
+                matches!(
+                    param.kind,
+                    hir::GenericParamKind::Lifetime { kind: hir::LifetimeParamKind::Explicit }
+                )
+            });
+        }
+        false
+    }
+
+    /// Whether `lifetime_ref` is the lifetime bound of a trait object in a function's return type,
+    /// like the `'a` in `fn f(&self) -> Box<dyn Trait + 'a>`.
+    fn is_returned_trait_object_bound(&self, lifetime_ref: &hir::Lifetime) -> bool {
+        let hir = self.tcx.hir();
+        match hir.find(hir.get_parent_node(lifetime_ref.hir_id)) {
+            Some(hir::Node::Ty(hir::Ty { kind: hir::TyKind::TraitObject(_, lifetime), .. }))
+                if lifetime.hir_id == lifetime_ref.hir_id => {}
+            _ => return false,
+        }
+        let parent = hir.find(hir.get_parent_item(lifetime_ref.hir_id));
+        match parent.as_ref().and_then(|node| node.fn_decl()) {
+            Some(decl) => decl.output.span().contains(lifetime_ref.span),
+            None => false,
+        }
+    }
+
+    /// Whether `lifetime_ref` is an argument of a path to a type written in a body, like the `'a`
+    /// in `let x: Ref<'a, u8>`, where the lifetime can be left to inference instead.
+    fn is_inferable_in_body(&self, lifetime_ref: &hir::Lifetime) -> bool {
+        if !self.is_in_body() {
+            return false;
+        }
+        let mut parents = self.tcx.hir().parent_iter(lifetime_ref.hir_id);
+        let parent = parents.find(|(_, node)| !matches!(node, hir::Node::PathSegment(_)));
+        matches!(parent, Some((_, hir::Node::Ty(hir::Ty { kind: hir::TyKind::Path(_), .. }))))
+    }
+
+    /// Whether `lifetime_ref` is the only use of its name in the enclosing item and appears where
+    /// `'_` is also accepted, namely in the parameters of a function or in an impl header.
+    fn is_single_use_in_elidable_position(&self, lifetime_ref: &hir::Lifetime) -> bool {
+        struct LifetimeUseCounter<'tcx> {
+            map: Map<'tcx>,
+            name: Symbol,
+            uses: usize,
+        }
+
+        impl<'tcx> Visitor<'tcx> for LifetimeUseCounter<'tcx> {
+            type Map = Map<'tcx>;
+
+            fn nested_visit_map(&mut self) -> NestedVisitorMap<Self::Map> {
+                NestedVisitorMap::All(self.map)
+            }
+
+            fn visit_lifetime(&mut self, lifetime: &'tcx hir::Lifetime) {
+                if lifetime.name.ident().name == self.name {
+                    self.uses += 1;
+                }
+            }
+        }
+
+        let hir = self.tcx.hir();
+        let span = lifetime_ref.span;
+        let parent = hir.get(hir.get_parent_item(lifetime_ref.hir_id));
+        let elidable = match parent {
+            hir::Node::Item(hir::Item {
+                kind: hir::ItemKind::Impl { of_trait, self_ty, .. }, ..
+            }) => {
+                self_ty.span.contains(span)
+                    || of_trait.as_ref().map_or(false, |t| t.path.span.contains(span))
+            }
+            _ => parent
+                .fn_decl()
+                .map_or(false, |decl| decl.inputs.iter().any(|input| input.span.contains(span))),
+        };
+        if !elidable {
+            return false;
+        }
+
+        let name = lifetime_ref.name.ident().name;
+        let mut counter = LifetimeUseCounter { map: hir, name, uses: 0 };
+        match parent {
+            hir::Node::Item(item) => counter.visit_item(item),
+            hir::Node::TraitItem(item) => counter.visit_trait_item(item),
+            hir::Node::ImplItem(item) => counter.visit_impl_item(item),
+            hir::Node::ForeignItem(item) => counter.visit_foreign_item(item),
+            _ => return false,
+        }
+        counter.uses == 1
+    }
+
     crate fn is_trait_ref_fn_scope(&mut self, trait_ref: &'tcx hir::PolyTraitRef<'tcx>) -> bool {
         if let def::Res::Def(_, did) = trait_ref.trait_ref.path.res {
             if [
@@ -1120,43 +3176,108 @@ impl<'tcx> LifetimeContext<'_, 'tcx> {
     crate fn add_missing_lifetime_specifiers_label(
         &self,
         err: &mut DiagnosticBuilder<'_>,
-        span: Span,
-        count: usize,
+        spans_with_counts: Vec<(Span, usize)>,
         lifetime_names: &FxHashSet<Ident>,
+        ty_params: &FxHashSet<Ident>,
         params: &[ElisionFailureInfo],
     ) {
-        let snippet = self.tcx.sess.source_map().span_to_snippet(span).ok();
-
-        err.span_label(
-            span,
-            &format!(
-                "expected {} lifetime parameter{}",
-                if count == 1 { "named".to_string() } else { count.to_string() },
-                pluralize!(count)
-            ),
-        );
+        let sess = self.tcx.sess;
+        let snippets: Vec<Option<String>> = spans_with_counts
+            .iter()
+            .map(|(span, _)| sess.source_map().span_to_snippet(*span).ok())
+            .collect();
+        let is_async = self.is_async_fn_elision(params);
 
-        let suggest_existing = |err: &mut DiagnosticBuilder<'_>, sugg| {
-            err.span_suggestion_verbose(
-                span,
-                &format!("consider using the `{}` lifetime", lifetime_names.iter().next().unwrap()),
-                sugg,
-                Applicability::MaybeIncorrect,
+        for (span, count) in &spans_with_counts {
+            err.span_label(
+                *span,
+                if *count == 1 {
+                    msg!(sess, "expected-named-lifetime")
+                } else {
+                    msg!(sess, "expected-lifetimes", count = count)
+                },
             );
+        }
+
+        let suggest_existing =
+            |err: &mut DiagnosticBuilder<'_>, name: &Ident, suggs: Vec<(Span, String)>| {
+                let msg = msg!(sess, "use-existing-lifetime", lifetime = name);
+                if let [(span, sugg)] = &suggs[..] {
+                    err.span_suggestion_verbose(
+                        *span,
+                        &msg,
+                        sugg.clone(),
+                        Applicability::MaybeIncorrect,
+                    );
+                } else {
+                    err.multipart_suggestion(&msg, suggs, Applicability::MaybeIncorrect);
+                }
+            };
+        // The type parameters in the types borrowing for `lifetime` have to outlive it.
+        let outlives_bounds = |generics: &hir::Generics<'_>, lifetime: &str| {
+            let bounds: Vec<_> = generics
+                .params
+                .iter()
+                .filter(|param| match param.kind {
+                    hir::GenericParamKind::Type { synthetic: None, .. } => {
+                        ty_params.contains(&param.name.ident())
+                    }
+                    _ => false,
+                })
+                .map(|param| format!("{}: {}", param.name.ident(), lifetime))
+                .collect();
+            if bounds.is_empty() {
+                return None;
+            }
+            let where_clause = &generics.where_clause;
+            Some((
+                where_clause.tail_span_for_suggestion(),
+                format!(
+                    "{} {}",
+                    if where_clause.predicates.is_empty() { " where" } else { "," },
+                    bounds.join(", "),
+                ),
+            ))
         };
-        let suggest_new = |err: &mut DiagnosticBuilder<'_>, sugg: &str| {
+        // `suggs` name the new lifetime `'a`, and `static_suggs` use `'static` instead.
+        let suggest_new = |err: &mut DiagnosticBuilder<'_>,
+                           suggs: &[(Span, String)],
+                           static_suggs: &[(Span, String)]| {
             for missing in self.missing_named_lifetime_spots.iter().rev() {
+                if let MissingLifetimeSpot::Static = missing {
+                    let mut suggs = static_suggs.to_vec();
+                    let generics =
+                        self.missing_named_lifetime_spots.iter().rev().find_map(|m| m.generics());
+                    suggs.extend(generics.and_then(|g| outlives_bounds(g, "'static")));
+                    err.note(&msg!(sess, "impl-lifetime-unnamed-note"));
+                    let msg = msg!(sess, "use-static-lifetime");
+                    if let [(span, sugg)] = &suggs[..] {
+                        err.span_suggestion_verbose(
+                            *span,
+                            &msg,
+                            sugg.clone(),
+                            Applicability::MaybeIncorrect,
+                        );
+                    } else {
+                        err.multipart_suggestion(&msg, suggs, Applicability::MaybeIncorrect);
+                    }
+                    break;
+                }
                 let mut introduce_suggestion = vec![];
                 let msg;
                 let should_break;
                 introduce_suggestion.push(match missing {
-                    MissingLifetimeSpot::Generics(generics) => {
-                        msg = "consider introducing a named lifetime parameter".to_string();
+                    MissingLifetimeSpot::Generics(generics)
+                    | MissingLifetimeSpot::ImplHeader { generics, .. } => {
+                        msg = msg!(sess, "introduce-named-lifetime");
                         should_break = true;
                         if let Some(param) = generics.params.iter().find(|p| match p.kind {
                             hir::GenericParamKind::Type {
                                 synthetic: Some(hir::SyntheticTyParamKind::ImplTrait),
                                 ..
+                            }
+                            | hir::GenericParamKind::Lifetime {
+                                kind: hir::LifetimeParamKind::Elided,
                             } => false,
                             _ => true,
                         }) {
@@ -1166,84 +3287,231 @@ impl<'tcx> LifetimeContext<'_, 'tcx> {
                         }
                     }
                     MissingLifetimeSpot::HigherRanked { span, span_type } => {
-                        msg = format!(
-                            "consider making the {} lifetime-generic with a new `'a` lifetime",
-                            span_type.descr(),
+                        msg = msg!(
+                            sess,
+                            "make-lifetime-generic",
+                            kind = span_type.descr(),
+                            lifetime = "'a",
                         );
                         should_break = false;
-                        err.note(
-                            "for more information on higher-ranked polymorphism, visit \
-                            https://doc.rust-lang.org/nomicon/hrtb.html",
-                        );
+                        err.note(&msg!(sess, "higher-ranked-note"));
                         (*span, span_type.suggestion("'a"))
                     }
+                    MissingLifetimeSpot::Static => unreachable!(),
                 });
-                for param in params {
-                    if let Ok(snippet) = self.tcx.sess.source_map().span_to_snippet(param.span) {
+                if let MissingLifetimeSpot::ImplHeader { elided_span, .. } = missing {
+                    introduce_suggestion
+                        .push((*elided_span, self.elided_lifetime_renamed(*elided_span, "'a")));
+                }
+                if let Some(generics) = missing.generics() {
+                    introduce_suggestion.extend(outlives_bounds(generics, "'a"));
+                }
+                let param_suggs: Vec<_> = params
+                    .iter()
+                    .filter_map(|param| {
+                        let snippet = sess.source_map().span_to_snippet(param.span).ok()?;
                         if snippet.starts_with('&') && !snippet.starts_with("&'") {
-                            introduce_suggestion
-                                .push((param.span, format!("&'a {}", &snippet[1..])));
+                            Some((param.span, format!("&'a {}", &snippet[1..])))
                         } else if snippet.starts_with("&'_ ") {
-                            introduce_suggestion
-                                .push((param.span, format!("&'a {}", &snippet[4..])));
+                            Some((param.span, format!("&'a {}", &snippet[4..])))
+                        } else {
+                            None
                         }
-                    }
+                    })
+                    .collect();
+                if is_async && should_break && param_suggs.len() > 1 {
+                    // The future returned by an `async fn` captures all of its input lifetimes
+                    // regardless, so only the input that the output borrows from needs a name.
+                    let substitutions = param_suggs
+                        .into_iter()
+                        .map(|param_sugg| {
+                            introduce_suggestion
+                                .iter()
+                                .cloned()
+                                .chain(iter::once(param_sugg))
+                                .chain(suggs.iter().cloned())
+                                .collect()
+                        })
+                        .collect();
+                    err.multipart_suggestions(
+                        &msg!(sess, "name-borrowed-input-lifetime"),
+                        substitutions,
+                        Applicability::MaybeIncorrect,
+                    );
+                } else {
+                    introduce_suggestion.extend(param_suggs);
+                    introduce_suggestion.extend(suggs.iter().cloned());
+                    err.multipart_suggestion(
+                        &msg,
+                        introduce_suggestion,
+                        Applicability::MaybeIncorrect,
+                    );
                 }
-                introduce_suggestion.push((span, sugg.to_string()));
-                err.multipart_suggestion(&msg, introduce_suggestion, Applicability::MaybeIncorrect);
                 if should_break {
                     break;
                 }
             }
         };
 
-        match (lifetime_names.len(), lifetime_names.iter().next(), snippet.as_deref()) {
-            (1, Some(name), Some("&")) => {
-                suggest_existing(err, format!("&{} ", name));
-            }
-            (1, Some(name), Some("'_")) => {
-                suggest_existing(err, name.to_string());
-            }
-            (1, Some(name), Some("")) => {
-                suggest_existing(err, format!("{}, ", name).repeat(count));
-            }
-            (1, Some(name), Some(snippet)) if !snippet.ends_with('>') => {
-                suggest_existing(
-                    err,
-                    format!(
-                        "{}<{}>",
-                        snippet,
-                        std::iter::repeat(name.to_string())
-                            .take(count)
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    ),
-                );
-            }
-            (0, _, Some("&")) if count == 1 => {
-                suggest_new(err, "&'a ");
-            }
-            (0, _, Some("'_")) if count == 1 => {
-                suggest_new(err, "'a");
+        // All of the missing lifetimes are fixed by a single suggestion, so only suggest anything
+        // if every one of them can be fixed.
+        let spans_with_snippets = spans_with_counts.iter().zip(snippets.iter());
+        let existing_suggs = |name: &Ident| -> Option<Vec<(Span, String)>> {
+            spans_with_snippets
+                .clone()
+                .map(|(&(span, count), snippet)| {
+                    let sugg = match snippet.as_deref() {
+                        Some("&") => format!("&{} ", name),
+                        Some("'_") => name.to_string(),
+                        Some("") => format!("{}, ", name).repeat(count),
+                        Some(snippet) if !snippet.ends_with('>') => format!(
+                            "{}<{}>",
+                            snippet,
+                            std::iter::repeat(name.to_string())
+                                .take(count)
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ),
+                        _ => return None,
+                    };
+                    Some((span, sugg))
+                })
+                .collect()
+        };
+        match (lifetime_names.len(), lifetime_names.iter().next()) {
+            (1, Some(name)) => {
+                if let Some(suggs) = existing_suggs(name) {
+                    suggest_existing(err, name, suggs);
+                }
             }
-            (0, _, Some(snippet)) if !snippet.ends_with('>') && count == 1 => {
-                suggest_new(err, &format!("{}<'a>", snippet));
+            (0, _) => {
+                let new_suggs = |lifetime: &str| -> Option<Vec<(Span, String)>> {
+                    spans_with_snippets
+                        .clone()
+                        .map(|(&(span, count), snippet)| {
+                            let sugg = match snippet.as_deref() {
+                                Some("&") if count == 1 => format!("&{} ", lifetime),
+                                Some("'_") if count == 1 => lifetime.to_string(),
+                                // This happens when we have `Foo<T>` where we point at the space
+                                // before `T`, so the new lifetime has to be the first argument.
+                                Some("") => format!("{}, ", lifetime).repeat(count),
+                                Some(snippet) if !snippet.ends_with('>') && count == 1 => {
+                                    format!("{}<{}>", snippet, lifetime)
+                                }
+                                _ => return None,
+                            };
+                            Some((span, sugg))
+                        })
+                        .collect()
+                };
+                if let (Some(suggs), Some(static_suggs)) = (new_suggs("'a"), new_suggs("'static")) {
+                    suggest_new(err, &suggs, &static_suggs);
+                }
             }
-            (n, ..) if n > 1 => {
-                let spans: Vec<Span> = lifetime_names.iter().map(|lt| lt.span).collect();
-                err.span_note(spans, "these named lifetimes are available to use");
-                if Some("") == snippet.as_deref() {
-                    // This happens when we have `Foo<T>` where we point at the space before `T`,
-                    // but this can be confusing so we give a suggestion with placeholders.
-                    err.span_suggestion_verbose(
-                        span,
-                        "consider using one of the available lifetimes here",
-                        "'lifetime, ".repeat(count),
-                        Applicability::HasPlaceholders,
+            (n, _) if n > 1 => {
+                let best = self.lifetime_borrowed_from(lifetime_names, params);
+                if let Some((name, suggs)) =
+                    best.and_then(|name| existing_suggs(name).map(|suggs| (name, suggs)))
+                {
+                    suggest_existing(err, name, suggs);
+                    let spans: Vec<Span> =
+                        lifetime_names.iter().filter(|lt| lt != &name).map(|lt| lt.span).collect();
+                    err.span_note(
+                        spans,
+                        &if n == 2 {
+                            msg!(sess, "other-lifetime-available")
+                        } else {
+                            msg!(sess, "other-lifetimes-available")
+                        },
                     );
+                } else {
+                    let spans: Vec<Span> = lifetime_names.iter().map(|lt| lt.span).collect();
+                    err.span_note(spans, &msg!(sess, "lifetimes-available"));
+                    for (&(span, count), snippet) in spans_with_snippets {
+                        if Some("") == snippet.as_deref() {
+                            // This happens when we have `Foo<T>` where we point at the space
+                            // before `T`, but this can be confusing so we give a suggestion with
+                            // placeholders.
+                            err.span_suggestion_verbose(
+                                span,
+                                &msg!(sess, "use-available-lifetime"),
+                                "'lifetime, ".repeat(count),
+                                Applicability::HasPlaceholders,
+                            );
+                        }
+                    }
                 }
             }
             _ => {}
         }
     }
+
+    /// Whether the failed elision is in the return type of an `async fn`, going by the function
+    /// that the arguments belong to.
+    crate fn is_async_fn_elision(&self, params: &[ElisionFailureInfo]) -> bool {
+        let hir = self.tcx.hir();
+        params.iter().find_map(|param| param.parent).map_or(false, |body| {
+            hir.fn_sig_by_hir_id(hir.body_owner(body))
+                .map_or(false, |sig| sig.header.asyncness == hir::IsAsync::Async)
+        })
+    }
+
+    /// Picks the lifetime of the argument that the function body evaluates to a borrow of, as
+    /// `text` in `&text[..n]` or `text.trim()`. Returns `None` if there's no such argument, or if
+    /// its type doesn't mention exactly one of `lifetime_names`.
+    fn lifetime_borrowed_from<'n>(
+        &self,
+        lifetime_names: &'n FxHashSet<Ident>,
+        params: &[ElisionFailureInfo],
+    ) -> Option<&'n Ident> {
+        struct LifetimeCollector {
+            names: Vec<Symbol>,
+        }
+
+        impl<'tcx> Visitor<'tcx> for LifetimeCollector {
+            type Map = Map<'tcx>;
+
+            fn nested_visit_map(&mut self) -> NestedVisitorMap<Self::Map> {
+                NestedVisitorMap::None
+            }
+
+            fn visit_lifetime(&mut self, lifetime: &'tcx hir::Lifetime) {
+                self.names.push(lifetime.name.ident().name);
+            }
+        }
+
+        let hir = self.tcx.hir();
+        let body_id = params.iter().find_map(|param| param.parent)?;
+        let body = hir.body(body_id);
+        let mut expr = &body.value;
+        loop {
+            expr = match expr.kind {
+                hir::ExprKind::Block(block, _) => block.expr?,
+                hir::ExprKind::AddrOf(_, _, inner)
+                | hir::ExprKind::Index(inner, _)
+                | hir::ExprKind::Field(inner, _)
+                | hir::ExprKind::MethodCall(_, _, [inner, ..], _) => inner,
+                _ => break,
+            };
+        }
+        let local = match expr.kind {
+            hir::ExprKind::Path(hir::QPath::Resolved(None, path)) => match path.res {
+                def::Res::Local(local) => local,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let index = body.params.iter().position(|param| param.pat.hir_id == local)?;
+        params.iter().find(|param| param.index == index && param.lifetime_count > 0)?;
+        let decl = hir.fn_decl_by_hir_id(hir.body_owner(body_id))?;
+        let mut collector = LifetimeCollector { names: Vec::new() };
+        collector.visit_ty(&decl.inputs[index]);
+
+        let mut borrowed =
+            lifetime_names.iter().filter(|name| collector.names.contains(&name.name));
+        match (borrowed.next(), borrowed.next()) {
+            (Some(name), None) => Some(name),
+            _ => None,
+        }
+    }
 }
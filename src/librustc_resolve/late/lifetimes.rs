@@ -9,7 +9,7 @@ use crate::late::diagnostics::{ForLifetimeSpanType, MissingLifetimeSpot};
 use rustc_ast::attr;
 use rustc_ast::walk_list;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
-use rustc_errors::{struct_span_err, Applicability, DiagnosticBuilder};
+use rustc_errors::{error_code, struct_span_err, Applicability, DiagnosticBuilder};
 use rustc_hir as hir;
 use rustc_hir::def::{DefKind, Res};
 use rustc_hir::def_id::{CrateNum, DefId, DefIdMap, LOCAL_CRATE};
@@ -25,7 +25,7 @@ use rustc_span::symbol::{kw, sym, Ident, Symbol};
 use rustc_span::Span;
 use std::borrow::Cow;
 use std::cell::Cell;
-use std::mem::take;
+use std::mem::{replace, take};
 
 use log::debug;
 
@@ -184,6 +184,10 @@ crate struct LifetimeContext<'a, 'tcx> {
     /// When encountering an undefined named lifetime, we will suggest introducing it in these
     /// places.
     crate missing_named_lifetime_spots: Vec<MissingLifetimeSpot<'tcx>>,
+
+    /// Elided lifetimes in the return type being visited that failed to resolve, along with the
+    /// named lifetimes available to each of them and the type parameters their types mention.
+    missing_output_lifetimes: Vec<(Span, usize, FxHashSet<Ident>, FxHashSet<Ident>)>,
 }
 
 #[derive(Debug)]
@@ -266,10 +270,10 @@ enum Elide {
 #[derive(Clone, Debug)]
 crate struct ElisionFailureInfo {
     /// Where we can find the argument pattern.
-    parent: Option<hir::BodyId>,
+    crate parent: Option<hir::BodyId>,
     /// The index of the argument in the original definition.
-    index: usize,
-    lifetime_count: usize,
+    crate index: usize,
+    crate lifetime_count: usize,
     have_bound_regions: bool,
     crate span: Span,
 }
@@ -337,6 +341,7 @@ fn krate(tcx: TyCtxt<'_>) -> NamedRegionMap {
             xcrate_object_lifetime_defaults: Default::default(),
             lifetime_uses: &mut Default::default(),
             missing_named_lifetime_spots: vec![],
+            missing_output_lifetimes: vec![],
         };
         for item in krate.items.values() {
             visitor.visit_item(item);
@@ -620,8 +625,8 @@ impl<'a, 'tcx> Visitor<'tcx> for LifetimeContext<'a, 'tcx> {
                                         self.tcx.sess,
                                         lifetime.span,
                                         E0657,
-                                        "`impl Trait` can only capture lifetimes \
-                                         bound at the fn or impl level"
+                                        "{}",
+                                        msg!(self.tcx.sess, "impl-trait-captures-nested")
                                     )
                                     .emit();
                                     self.uninsert_lifetime_on_error(lifetime, def.unwrap());
@@ -709,7 +714,17 @@ impl<'a, 'tcx> Visitor<'tcx> for LifetimeContext<'a, 'tcx> {
 
     fn visit_trait_item(&mut self, trait_item: &'tcx hir::TraitItem<'tcx>) {
         use self::hir::TraitItemKind::*;
-        self.missing_named_lifetime_spots.push((&trait_item.generics).into());
+        // Associated constants can't have generics and associated types can only have lifetime
+        // parameters with `generic_associated_types`, so a missing lifetime in them has to be
+        // introduced on the trait instead.
+        let introduces_lifetimes = match trait_item.kind {
+            Fn(..) => true,
+            Type(..) => self.tcx.features().generic_associated_types,
+            Const(..) => false,
+        };
+        if introduces_lifetimes {
+            self.missing_named_lifetime_spots.push((&trait_item.generics).into());
+        }
         match trait_item.kind {
             Fn(ref sig, _) => {
                 let tcx = self.tcx;
@@ -762,12 +777,35 @@ impl<'a, 'tcx> Visitor<'tcx> for LifetimeContext<'a, 'tcx> {
                 intravisit::walk_trait_item(self, trait_item);
             }
         }
-        self.missing_named_lifetime_spots.pop();
+        if introduces_lifetimes {
+            self.missing_named_lifetime_spots.pop();
+        }
     }
 
     fn visit_impl_item(&mut self, impl_item: &'tcx hir::ImplItem<'tcx>) {
         use self::hir::ImplItemKind::*;
-        self.missing_named_lifetime_spots.push((&impl_item.generics).into());
+        // As in traits, missing lifetimes in associated constants and types can't be introduced
+        // on the item itself. An associated type can only use a lifetime of the impl that its
+        // header names, so the lifetime is only suggested along with naming an elided lifetime
+        // of the header, and `'static` otherwise. Associated constants are treated the same, as
+        // what they borrow is most likely `'static`. Methods taking the self type by value most
+        // likely return what it borrows, so they get the elided lifetime of the header if there
+        // is one, and introduce a lifetime of their own otherwise.
+        let spot = match impl_item.kind {
+            Fn(ref sig, _) => match sig.decl.implicit_self {
+                hir::ImplicitSelfKind::Imm | hir::ImplicitSelfKind::Mut => self
+                    .impl_header_lifetime_spot(impl_item)
+                    .unwrap_or_else(|| (&impl_item.generics).into()),
+                _ => (&impl_item.generics).into(),
+            },
+            TyAlias(..) if self.tcx.features().generic_associated_types => {
+                (&impl_item.generics).into()
+            }
+            TyAlias(..) | Const(..) => {
+                self.impl_header_lifetime_spot(impl_item).unwrap_or(MissingLifetimeSpot::Static)
+            }
+        };
+        self.missing_named_lifetime_spots.push(spot);
         match impl_item.kind {
             Fn(ref sig, _) => {
                 let tcx = self.tcx;
@@ -871,8 +909,22 @@ impl<'a, 'tcx> Visitor<'tcx> for LifetimeContext<'a, 'tcx> {
                     ref bounded_ty,
                     bounds,
                     ref bound_generic_params,
-                    ..
+                    span,
                 }) => {
+                    // An undeclared lifetime could also be introduced by making the predicate
+                    // higher-ranked: `where for<'a> T: Trait<'a>`.
+                    let lifetime_span: Option<Span> =
+                        bound_generic_params.iter().rev().find_map(|param| match param.kind {
+                            GenericParamKind::Lifetime { .. } => Some(param.span),
+                            _ => None,
+                        });
+                    let (missing_span, span_type) = if let Some(span) = lifetime_span {
+                        (span.shrink_to_hi(), ForLifetimeSpanType::BoundTail)
+                    } else {
+                        (span.shrink_to_lo(), ForLifetimeSpanType::BoundEmpty)
+                    };
+                    self.missing_named_lifetime_spots
+                        .push(MissingLifetimeSpot::HigherRanked { span: missing_span, span_type });
                     let lifetimes: FxHashMap<_, _> = bound_generic_params
                         .iter()
                         .filter_map(|param| match param.kind {
@@ -903,6 +955,7 @@ impl<'a, 'tcx> Visitor<'tcx> for LifetimeContext<'a, 'tcx> {
                         self.visit_ty(&bounded_ty);
                         walk_list!(self, visit_param_bound, bounds);
                     }
+                    self.missing_named_lifetime_spots.pop();
                 }
                 &hir::WherePredicate::RegionPredicate(hir::WhereRegionPredicate {
                     ref lifetime,
@@ -945,7 +998,8 @@ impl<'a, 'tcx> Visitor<'tcx> for LifetimeContext<'a, 'tcx> {
                     self.tcx.sess,
                     trait_ref.span,
                     E0316,
-                    "nested quantification of lifetimes"
+                    "{}",
+                    msg!(self.tcx.sess, "nested-quantification")
                 )
                 .emit();
             }
@@ -1033,43 +1087,33 @@ fn check_mixed_explicit_and_in_band_defs(tcx: TyCtxt<'_>, params: &[hir::Generic
             tcx.sess,
             *in_band_span,
             E0688,
-            "cannot mix in-band and explicit lifetime definitions"
+            "{}",
+            msg!(tcx.sess, "mixed-in-band-and-explicit")
         )
-        .span_label(*in_band_span, "in-band lifetime definition here")
-        .span_label(*explicit_span, "explicit lifetime definition here")
+        .span_label(*in_band_span, msg!(tcx.sess, "in-band-definition-here"))
+        .span_label(*explicit_span, msg!(tcx.sess, "explicit-definition-here"))
         .emit();
     }
 }
 
 fn signal_shadowing_problem(tcx: TyCtxt<'_>, name: Symbol, orig: Original, shadower: Shadower) {
+    let msg = msg!(
+        tcx.sess,
+        "shadowed-name",
+        kind = shadower.kind.desc(),
+        name = name,
+        orig_kind = orig.kind.desc(),
+    );
     let mut err = if let (ShadowKind::Lifetime, ShadowKind::Lifetime) = (orig.kind, shadower.kind) {
         // lifetime/lifetime shadowing is an error
-        struct_span_err!(
-            tcx.sess,
-            shadower.span,
-            E0496,
-            "{} name `{}` shadows a \
-             {} name that is already in scope",
-            shadower.kind.desc(),
-            name,
-            orig.kind.desc()
-        )
+        struct_span_err!(tcx.sess, shadower.span, E0496, "{}", msg)
     } else {
         // shadowing involving a label is only a warning, due to issues with
         // labels and lifetimes not being macro-hygienic.
-        tcx.sess.struct_span_warn(
-            shadower.span,
-            &format!(
-                "{} name `{}` shadows a \
-                 {} name that is already in scope",
-                shadower.kind.desc(),
-                name,
-                orig.kind.desc()
-            ),
-        )
+        tcx.sess.struct_span_warn_with_code(shadower.span, &msg, error_code!(E0770))
     };
-    err.span_label(orig.span, "first declared here");
-    err.span_label(shadower.span, format!("lifetime {} already in scope", name));
+    err.span_label(orig.span, msg!(tcx.sess, "first-declared-here"));
+    err.span_label(shadower.span, msg!(tcx.sess, "lifetime-already-in-scope", name = name));
     err.emit();
 }
 
@@ -1311,6 +1355,7 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
         let labels_in_fn = take(&mut self.labels_in_fn);
         let xcrate_object_lifetime_defaults = take(&mut self.xcrate_object_lifetime_defaults);
         let missing_named_lifetime_spots = take(&mut self.missing_named_lifetime_spots);
+        let missing_output_lifetimes = take(&mut self.missing_output_lifetimes);
         let mut this = LifetimeContext {
             tcx: *tcx,
             map,
@@ -1321,6 +1366,7 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
             xcrate_object_lifetime_defaults,
             lifetime_uses,
             missing_named_lifetime_spots,
+            missing_output_lifetimes,
         };
         debug!("entering scope {:?}", this.scope);
         f(self.scope, &mut this);
@@ -1329,6 +1375,7 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
         self.labels_in_fn = this.labels_in_fn;
         self.xcrate_object_lifetime_defaults = this.xcrate_object_lifetime_defaults;
         self.missing_named_lifetime_spots = this.missing_named_lifetime_spots;
+        self.missing_output_lifetimes = this.missing_output_lifetimes;
     }
 
     /// helper method to determine the span to remove when suggesting the
@@ -1437,7 +1484,7 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
             }
         }
 
-        let msg = "elide the single-use lifetime";
+        let msg = &msg!(self.tcx.sess, "elide-single-use-lifetime");
         match (remove_decl, remove_use, elide_use) {
             (Some(decl_span), Some(use_span), None) => {
                 // if both declaration and use deletion spans start at the same
@@ -1543,16 +1590,15 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
                             id,
                             span,
                             |lint| {
-                                let mut err = lint.build(&format!(
-                                    "lifetime parameter `{}` only used once",
-                                    name
-                                ));
+                                let sess = self.tcx.sess;
+                                let mut err =
+                                    lint.build(&msg!(sess, "single-use-lifetime", name = name));
                                 if span == lifetime.span {
                                     // spans are the same for in-band lifetime declarations
-                                    err.span_label(span, "this lifetime is only used here");
+                                    err.span_label(span, msg!(sess, "lifetime-only-used-here"));
                                 } else {
-                                    err.span_label(span, "this lifetime...");
-                                    err.span_label(lifetime.span, "...is used only here");
+                                    err.span_label(span, msg!(sess, "this-lifetime"));
+                                    err.span_label(lifetime.span, msg!(sess, "is-used-only-here"));
                                 }
                                 self.suggest_eliding_single_use_lifetime(
                                     &mut err, def_id, lifetime,
@@ -1584,8 +1630,9 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
                             id,
                             span,
                             |lint| {
-                                let mut err = lint
-                                    .build(&format!("lifetime parameter `{}` never used", name));
+                                let sess = self.tcx.sess;
+                                let mut err =
+                                    lint.build(&msg!(sess, "unused-lifetime", name = name));
                                 if let Some(parent_def_id) = self.tcx.parent(def_id) {
                                     if let Some(generics) =
                                         self.tcx.hir().get_generics(parent_def_id)
@@ -1595,7 +1642,7 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
                                         if let Some(span) = unused_lt_span {
                                             err.span_suggestion(
                                                 span,
-                                                "elide the unused lifetime",
+                                                &msg!(sess, "elide-unused-lifetime"),
                                                 String::new(),
                                                 Applicability::MachineApplicable,
                                             );
@@ -1797,10 +1844,10 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
                             self.tcx.sess,
                             lifetime_ref.span,
                             E0687,
-                            "lifetimes used in `fn` or `Fn` syntax must be \
-                             explicitly declared using `<...>` binders"
+                            "{}",
+                            msg!(self.tcx.sess, "in-band-in-fn-syntax")
                         )
-                        .span_label(lifetime_ref.span, "in-band lifetime definition")
+                        .span_label(lifetime_ref.span, msg!(self.tcx.sess, "in-band-definition"))
                         .emit();
                     }
 
@@ -2175,7 +2222,7 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
         // have that lifetime.
         let mut possible_implied_output_region = None;
         let mut lifetime_count = 0;
-        let arg_lifetimes = inputs
+        let arg_lifetimes: Vec<_> = inputs
             .iter()
             .enumerate()
             .skip(has_self as usize)
@@ -2210,13 +2257,36 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
         let elide = if lifetime_count == 1 {
             Elide::Exact(possible_implied_output_region.unwrap())
         } else {
-            Elide::Error(arg_lifetimes)
+            Elide::Error(arg_lifetimes.clone())
         };
 
         debug!("visit_fn_like_elision: elide={:?}", elide);
 
         let scope = Scope::Elision { elide, s: self.scope };
+        let outer_missing_lifetimes = take(&mut self.missing_output_lifetimes);
         self.with(scope, |_, this| this.visit_ty(output));
+        let missing_lifetimes =
+            replace(&mut self.missing_output_lifetimes, outer_missing_lifetimes);
+        if !missing_lifetimes.is_empty() {
+            // Report all of the lifetimes missing from the return type at once, so that the
+            // suggestions introduce a single new lifetime for all of them.
+            let mut lifetime_names = FxHashSet::default();
+            let mut ty_params = FxHashSet::default();
+            let spans_with_counts = missing_lifetimes
+                .into_iter()
+                .map(|(span, count, names, params)| {
+                    lifetime_names.extend(names);
+                    ty_params.extend(params);
+                    (span, count)
+                })
+                .collect();
+            self.report_missing_lifetimes(
+                spans_with_counts,
+                lifetime_names,
+                ty_params,
+                Some(&arg_lifetimes[..]),
+            );
+        }
         debug!("visit_fn_like_elision: exit");
 
         struct GatherLifetimes<'a> {
@@ -2359,9 +2429,73 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
             }
         };
 
-        let mut err = self.report_missing_lifetime_specifiers(span, lifetime_refs.len());
+        let ty_params = self.type_params_in_type_of(lifetime_refs[0]);
+        if error.is_some() {
+            // Elision failures in a return type are reported together once the whole type has
+            // been visited, see `visit_fn_like_elision`.
+            self.missing_output_lifetimes.push((
+                span,
+                lifetime_refs.len(),
+                lifetime_names,
+                ty_params,
+            ));
+        } else {
+            self.report_missing_lifetimes(
+                vec![(span, lifetime_refs.len())],
+                lifetime_names,
+                ty_params,
+                None,
+            );
+        }
+    }
+
+    /// Collects the type parameters mentioned by the type that `lifetime_ref` is elided in, as
+    /// they would have to outlive a lifetime introduced for it.
+    fn type_params_in_type_of(&self, lifetime_ref: &hir::Lifetime) -> FxHashSet<Ident> {
+        #[derive(Default)]
+        struct TypeParamCollector {
+            params: FxHashSet<Ident>,
+        }
+
+        impl<'v> Visitor<'v> for TypeParamCollector {
+            type Map = intravisit::ErasedMap<'v>;
+
+            fn nested_visit_map(&mut self) -> NestedVisitorMap<Self::Map> {
+                NestedVisitorMap::None
+            }
+
+            fn visit_ty(&mut self, ty: &'v hir::Ty<'v>) {
+                if let hir::TyKind::Path(QPath::Resolved(None, path)) = ty.kind {
+                    if let (Res::Def(DefKind::TyParam, _), [segment]) = (path.res, path.segments) {
+                        self.params.insert(segment.ident);
+                    }
+                }
+                intravisit::walk_ty(self, ty);
+            }
+        }
+
+        let mut collector = TypeParamCollector::default();
+        let mut parents = self.tcx.hir().parent_iter(lifetime_ref.hir_id);
+        if let Some((_, Node::Ty(ty))) =
+            parents.find(|(_, node)| !matches!(node, Node::PathSegment(_)))
+        {
+            collector.visit_ty(ty);
+        }
+        collector.params
+    }
+
+    fn report_missing_lifetimes(
+        &mut self,
+        spans_with_counts: Vec<(Span, usize)>,
+        mut lifetime_names: FxHashSet<Ident>,
+        ty_params: FxHashSet<Ident>,
+        params: Option<&[ElisionFailureInfo]>,
+    ) {
+        let count = spans_with_counts.iter().map(|(_, count)| count).sum();
+        let spans = spans_with_counts.iter().map(|(span, _)| *span).collect();
+        let mut err = self.report_missing_lifetime_specifiers(spans, count);
 
-        if let Some(params) = error {
+        if let Some(params) = params {
             // If there's no lifetime available, suggest `'static`.
             if self.report_elision_failure(&mut err, params) && lifetime_names.is_empty() {
                 lifetime_names.insert(Ident::from_str("'static"));
@@ -2369,10 +2503,10 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
         }
         self.add_missing_lifetime_specifiers_label(
             &mut err,
-            span,
-            lifetime_refs.len(),
+            spans_with_counts,
             &lifetime_names,
-            error.map(|p| &p[..]).unwrap_or(&[]),
+            &ty_params,
+            params.unwrap_or(&[]),
         );
         err.emit();
     }
@@ -2382,6 +2516,7 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
         db: &mut DiagnosticBuilder<'_>,
         params: &[ElisionFailureInfo],
     ) -> bool /* add `'static` lifetime to lifetime list */ {
+        let sess = self.tcx.sess;
         let mut m = String::new();
         let len = params.len();
 
@@ -2394,24 +2529,32 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
             let ElisionFailureInfo { parent, index, lifetime_count: n, have_bound_regions, span } =
                 info;
 
-            db.span_label(span, "");
+            db.span_label(
+                span,
+                if n == 1 {
+                    msg!(sess, "param-introduces-lifetime")
+                } else {
+                    msg!(sess, "param-introduces-lifetimes", count = n)
+                },
+            );
             let help_name = if let Some(ident) =
                 parent.and_then(|body| self.tcx.hir().body(body).params[index].pat.simple_ident())
             {
                 format!("`{}`", ident)
             } else {
-                format!("argument {}", index + 1)
+                msg!(sess, "argument-n", index = index + 1)
             };
 
             m.push_str(
                 &(if n == 1 {
                     help_name
                 } else {
-                    format!(
-                        "one of {}'s {} {}lifetimes",
-                        help_name,
-                        n,
-                        if have_bound_regions { "free " } else { "" }
+                    msg!(
+                        sess,
+                        "one-of-lifetimes",
+                        name = help_name,
+                        count = n,
+                        free = if have_bound_regions { "free " } else { "" },
                     )
                 })[..],
             );
@@ -2426,31 +2569,20 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
         }
 
         if len == 0 {
-            db.help(
-                "this function's return type contains a borrowed value, \
-                 but there is no value for it to be borrowed from",
-            );
+            db.help(&msg!(sess, "no-value-to-borrow-from"));
             true
         } else if elided_len == 0 {
-            db.help(
-                "this function's return type contains a borrowed value with \
-                 an elided lifetime, but the lifetime cannot be derived from \
-                 the arguments",
-            );
+            db.help(&msg!(sess, "lifetime-not-derivable"));
             true
-        } else if elided_len == 1 {
-            db.help(&format!(
-                "this function's return type contains a borrowed value, \
-                 but the signature does not say which {} it is borrowed from",
-                m
-            ));
-            false
         } else {
-            db.help(&format!(
-                "this function's return type contains a borrowed value, \
-                 but the signature does not say whether it is borrowed from {}",
-                m
-            ));
+            if elided_len == 1 {
+                db.help(&msg!(sess, "borrowed-from-which", candidates = m));
+            } else {
+                db.help(&msg!(sess, "borrowed-from-whether", candidates = m));
+            }
+            if self.is_async_fn_elision(params) {
+                db.note(&msg!(sess, "async-fn-captures-lifetimes"));
+            }
             false
         }
     }
@@ -2498,12 +2630,16 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
                         self.tcx.sess,
                         lifetime_i.span,
                         E0262,
-                        "invalid lifetime parameter name: `{}`",
-                        lifetime_i.name.ident(),
+                        "{}",
+                        msg!(
+                            self.tcx.sess,
+                            "invalid-lifetime-name",
+                            name = lifetime_i.name.ident(),
+                        ),
                     );
                     err.span_label(
                         lifetime_i.span,
-                        format!("{} is a reserved lifetime name", name),
+                        msg!(self.tcx.sess, "reserved-lifetime-name", name = name),
                     );
                     err.emit();
                 }
@@ -2516,11 +2652,15 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
                         self.tcx.sess,
                         lifetime_j.span,
                         E0263,
-                        "lifetime name `{}` declared twice in the same scope",
-                        lifetime_j.name.ident()
+                        "{}",
+                        msg!(
+                            self.tcx.sess,
+                            "lifetime-declared-twice",
+                            name = lifetime_j.name.ident(),
+                        )
                     )
-                    .span_label(lifetime_j.span, "declared twice")
-                    .span_label(lifetime_i.span, "previous declaration here")
+                    .span_label(lifetime_j.span, msg!(self.tcx.sess, "declared-twice"))
+                    .span_label(lifetime_i.span, msg!(self.tcx.sess, "previous-declaration"))
                     .emit();
                 }
             }
@@ -2539,16 +2679,19 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
                             self.insert_lifetime(lt, Region::Static);
                             self.tcx
                                 .sess
-                                .struct_span_warn(
+                                .struct_span_warn_with_code(
                                     lifetime_i.span.to(lt.span),
-                                    &format!(
-                                        "unnecessary lifetime parameter `{}`",
-                                        lifetime_i.name.ident(),
+                                    &msg!(
+                                        self.tcx.sess,
+                                        "unnecessary-lifetime",
+                                        name = lifetime_i.name.ident(),
                                     ),
+                                    error_code!(E0771),
                                 )
-                                .help(&format!(
-                                    "you can use the `'static` lifetime directly, in place of `{}`",
-                                    lifetime_i.name.ident(),
+                                .help(&msg!(
+                                    self.tcx.sess,
+                                    "use-static-directly",
+                                    name = lifetime_i.name.ident(),
                                 ))
                                 .emit();
                         }
@@ -2622,6 +2765,21 @@ impl<'a, 'tcx> LifetimeContext<'a, 'tcx> {
         }
     }
 
+    /// Returns `true` if the current scope is directly inside of a body,
+    /// where elided lifetimes are left to inference.
+    crate fn is_in_body(&self) -> bool {
+        let mut scope = self.scope;
+        loop {
+            match *scope {
+                Scope::Body { .. } => break true,
+
+                Scope::ObjectLifetimeDefault { s, .. } => scope = s,
+
+                Scope::Root | Scope::Binder { .. } | Scope::Elision { .. } => break false,
+            }
+        }
+    }
+
     /// Returns `true` if, in the current scope, replacing `'_` would be
     /// equivalent to a single-use lifetime.
     fn track_lifetime_uses(&self) -> bool {
@@ -15,6 +15,7 @@
 #![feature(or_patterns)]
 #![recursion_limit = "256"]
 
+pub use diagnostics::{ImportSuggestion, TypoSuggestion};
 pub use rustc_hir::def::{Namespace, PerNS};
 
 use Determinacy::*;
@@ -57,24 +58,32 @@ use rustc_span::{Span, DUMMY_SP};
 
 use log::debug;
 use std::cell::{Cell, RefCell};
-use std::collections::BTreeSet;
-use std::{cmp, fmt, iter, ptr};
+use std::collections::{BTreeSet, VecDeque};
+use std::{cmp, fmt, iter, mem, ptr};
 
 use diagnostics::{extend_span_to_previous_binding, find_span_of_binding_until_next_binding};
-use diagnostics::{ImportSuggestion, LabelSuggestion, Suggestion};
+use diagnostics::find_spans_collapsing_nested_use;
+use diagnostics::{LabelSuggestion, Suggestion};
 use imports::{Import, ImportKind, ImportResolver, NameResolution};
-use late::{HasGenericParams, PathSource, Rib, RibKind::*};
+use late::{ConstCandidateLocal, ConstantItemKind, HasGenericParams, NestedFnItem};
+use late::{PathSource, Rib, RibKind::*};
 use macros::{MacroRulesBinding, MacroRulesScope};
+use unresolved_names::UnresolvedName;
 
 type Res = def::Res<NodeId>;
 
+#[macro_use]
+mod messages;
+
 mod build_reduced_graph;
 mod check_unused;
 mod def_collector;
 mod diagnostics;
+mod heuristics;
 mod imports;
 mod late;
 mod macros;
+mod unresolved_names;
 
 enum Weak {
     Yes,
@@ -177,8 +186,9 @@ impl Ord for BindingError {
 }
 
 enum ResolutionError<'a> {
-    /// Error E0401: can't use type or const parameters from outer function.
-    GenericParamsFromOuterFunction(Res, HasGenericParams),
+    /// Error E0401: can't use type or const parameters from outer function. Carries the bounds
+    /// of a type parameter or the type of a const parameter.
+    GenericParamsFromOuterFunction(Res, HasGenericParams, Vec<Span>),
     /// Error E0403: the name is already used for a type or const parameter in this generic
     /// parameter list.
     NameAlreadyUsedInParameterList(Symbol, Span),
@@ -194,10 +204,16 @@ enum ResolutionError<'a> {
     VariableBoundWithDifferentMode(Symbol, Span),
     /// Error E0415: identifier is bound more than once in this parameter list.
     IdentifierBoundMoreThanOnceInParameterList(&'a str),
-    /// Error E0416: identifier is bound more than once in the same pattern.
-    IdentifierBoundMoreThanOnceInSamePattern(&'a str),
+    /// Error E0416: identifier is bound more than once in the same pattern, along with the
+    /// replacement of one of its shorthand field patterns binding the field to another name.
+    IdentifierBoundMoreThanOnceInSamePattern(&'a str, Option<(Span, String)>),
     /// Error E0426: use of undeclared label.
-    UndeclaredLabel { name: &'a str, suggestion: Option<LabelSuggestion> },
+    UndeclaredLabel {
+        name: &'a str,
+        suggestion: Option<LabelSuggestion>,
+        enclosing_loop: Option<Span>,
+        enclosing_block: Option<Span>,
+    },
     /// Error E0429: `self` imports are only allowed within a `{ }` list.
     SelfImportsOnlyAllowedWithin { root: bool, span_with_rename: Span },
     /// Error E0430: `self` import can only appear once in the list.
@@ -205,11 +221,15 @@ enum ResolutionError<'a> {
     /// Error E0431: `self` import can only appear in an import list with a non-empty prefix.
     SelfImportOnlyInImportListWithNonEmptyPrefix,
     /// Error E0433: failed to resolve.
-    FailedToResolve { label: String, suggestion: Option<Suggestion> },
+    FailedToResolve { failure: PathFailure, suggestion: Option<Suggestion> },
     /// Error E0434: can't capture dynamic environment in a fn item.
-    CannotCaptureDynamicEnvironmentInFnItem,
+    CannotCaptureDynamicEnvironmentInFnItem(Option<NestedFnItem>),
     /// Error E0435: attempt to use a non-constant value in a constant.
-    AttemptToUseNonConstantValueInConstant,
+    AttemptToUseNonConstantValueInConstant {
+        ident: Ident,
+        item_kind: Option<ConstantItemKind>,
+        candidate: Option<ConstCandidateLocal>,
+    },
     /// Error E0530: `X` bindings cannot shadow `Y`s.
     BindingShadowsSomethingUnacceptable(&'a str, Symbol, &'a NameBinding<'a>),
     /// Error E0128: type parameters with a default cannot use forward-declared identifiers.
@@ -217,13 +237,52 @@ enum ResolutionError<'a> {
     /// Error E0735: type parameters with a default cannot use `Self`
     SelfInTyParamDefault,
     /// Error E0767: use of unreachable label
-    UnreachableLabel { name: &'a str, definition_span: Span, suggestion: Option<LabelSuggestion> },
+    UnreachableLabel {
+        name: &'a str,
+        definition_span: Span,
+        suggestion: Option<LabelSuggestion>,
+        boundary: Option<(Span, &'static str)>,
+        control_flow_span: Span,
+    },
+}
+
+/// Why a path failed to resolve, kept apart from the wording of the error reporting it.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum PathFailure {
+    /// `super` went past the crate root.
+    TooManySupers,
+    /// A path segment keyword, like `self` or `crate`, after the start of the path, right after
+    /// the `::` of a global path if `global`.
+    MisplacedKeyword { keyword: Symbol, global: bool },
+    /// A segment before the last one resolved to something that isn't a module.
+    NotAModule { segment: Ident, res: Res },
+    /// The first segment of an import names no crate, but a module elsewhere in the crate has that
+    /// name, and the suggestion points to it.
+    UnresolvedImport,
+    /// The first segment of an import names no crate or module of the crate root.
+    MissingCrate { segment: Ident },
+    /// The first segment names nothing in scope in namespace `ns`.
+    UndeclaredTypeOrModule { segment: Ident, ns: Namespace },
+    /// A segment names nothing in namespace `ns` of the module named by `parent`.
+    NotFoundIn { segment: Ident, parent: Ident, ns: Namespace },
+    /// A segment names nothing in the namespace where it was expected in the module named by
+    /// `parent`, but something that is `found` in the other one.
+    FoundInOtherNamespace {
+        segment: Ident,
+        parent: Ident,
+        expected: Namespace,
+        found: &'static str,
+    },
+    /// A type-relative path in a position where only paths to items are supported.
+    TypeRelative,
+    /// The path of a macro resolved only partially.
+    PartiallyResolvedMacro(MacroKind),
 }
 
 enum VisResolutionError<'a> {
     Relative2018(Span, &'a ast::Path),
-    AncestorOnly(Span),
-    FailedToResolve(Span, String, Option<Suggestion>),
+    AncestorOnly(Span, &'a ast::Path, DefId),
+    FailedToResolve(Span, PathFailure, Option<Suggestion>, &'a ast::Path),
     ExpectedFound(Span, String, Res),
     Indeterminate(Span),
     ModuleOnly(Span),
@@ -262,6 +321,7 @@ impl<'a> From<&'a ast::PathSegment> for Segment {
 
 struct UsePlacementFinder {
     target_module: NodeId,
+    /// The last `use` item of the module if `found_use`, otherwise the position to insert at.
     span: Option<Span>,
     found_use: bool,
 }
@@ -289,23 +349,22 @@ impl<'tcx> Visitor<'tcx> for UsePlacementFinder {
             visit::walk_mod(self, module);
             return;
         }
-        // find a use statement
+        // find the last use statement
         for item in &module.items {
             match item.kind {
                 ItemKind::Use(..) => {
-                    // don't suggest placing a use before the prelude
+                    // don't suggest placing a use next to the prelude
                     // import or other generated ones
                     if !item.span.from_expansion() {
-                        self.span = Some(item.span.shrink_to_lo());
+                        self.span = Some(item.span);
                         self.found_use = true;
-                        return;
                     }
                 }
                 // don't place use before extern crate
                 ItemKind::ExternCrate(_) => {}
-                // but place them before the first other item
+                // but place them before the first other item if there is no use
                 _ => {
-                    if self.span.map_or(true, |span| item.span < span) {
+                    if !self.found_use && self.span.map_or(true, |span| item.span < span) {
                         if !item.span.from_expansion() {
                             // don't insert between attributes and an item
                             if item.attrs.is_empty() {
@@ -388,7 +447,7 @@ enum PathResult<'a> {
     Indeterminate,
     Failed {
         span: Span,
-        label: String,
+        failure: PathFailure,
         suggestion: Option<Suggestion>,
         is_error_from_last_segment: bool,
     },
@@ -463,6 +522,8 @@ pub struct ModuleData<'a> {
     unexpanded_invocations: RefCell<FxHashSet<ExpnId>>,
 
     no_implicit_prelude: bool,
+    // Note from `#[rustc_on_unresolved]` added to errors for names that fail to resolve here.
+    on_unresolved: Option<Symbol>,
 
     glob_importers: RefCell<Vec<&'a Import<'a>>>,
     globs: RefCell<Vec<&'a Import<'a>>>,
@@ -494,6 +555,7 @@ impl<'a> ModuleData<'a> {
             populate_on_access: Cell::new(!normal_ancestor_id.is_local()),
             unexpanded_invocations: Default::default(),
             no_implicit_prelude: false,
+            on_unresolved: None,
             glob_importers: RefCell::new(Vec::new()),
             globs: RefCell::new(Vec::new()),
             traits: RefCell::new(None),
@@ -610,7 +672,54 @@ impl<'a> NameBindingKind<'a> {
 struct PrivacyError<'a> {
     ident: Ident,
     binding: &'a NameBinding<'a>,
+    /// The module `binding` was used from.
+    module: Module<'a>,
     dedup_span: Span,
+    /// If `binding` is a private module that the failing path goes through, the rest of the path
+    /// when it names a public item, which the module's parent could re-export instead.
+    reexport_path: Option<Vec<Ident>>,
+}
+
+/// A path resolution made only to learn its result, without reporting, buffering or recording
+/// anything about it.
+#[derive(Clone, Copy)]
+struct SpeculativeResolution {
+    /// Whether crates from the extern prelude may be loaded, or only those already loaded found.
+    load_crates: bool,
+}
+
+/// A local `impl` with methods taking `self`, recorded before late resolution to suggest method
+/// call syntax where they're called like free functions.
+struct LocalImpl<'a> {
+    /// The module of the `impl`, which its paths are resolved from.
+    module: Module<'a>,
+    self_ty: Path,
+    of_trait: Option<Path>,
+    /// The names of the methods taking `self`.
+    methods: Vec<Symbol>,
+}
+
+/// A method taking `self` in a local `impl`, recorded to suggest method call syntax where it's
+/// called like a free function.
+#[derive(Clone, Copy)]
+struct ImplMethod {
+    /// The type the `impl` is for.
+    self_ty: DefId,
+    /// The trait the `impl` is of, if any.
+    of_trait: Option<DefId>,
+}
+
+/// A `new Type(<args>)` expression, recovered by the parser as an erroneous expression.
+struct NewOperatorError<'a> {
+    /// The error stashed by the parser.
+    err: DiagnosticBuilder<'a>,
+    new_span: Span,
+    /// The span of `Type`.
+    ty_span: Span,
+    call_span: Span,
+    /// The resolution of `Type`, if it resolved.
+    ty_res: Option<Res>,
+    arg_count: usize,
 }
 
 struct UseError<'a> {
@@ -622,7 +731,16 @@ struct UseError<'a> {
     /// Whether the diagnostic should say "instead" (as in `consider importing ... instead`).
     instead: bool,
     /// Extra free-form suggestion.
-    suggestion: Option<(Span, &'static str, String, Applicability)>,
+    suggestion: Option<(Span, String, String, Applicability)>,
+    /// Path of the enum whose variants could be glob-imported, as an alternative to the
+    /// candidates.
+    glob_import: Option<String>,
+    /// Path of the item of the `alloc` crate that was meant, if this `no_std` crate doesn't link
+    /// it yet.
+    alloc_import: Option<String>,
+    /// The span of the path along with the associated constants of primitive types it may refer
+    /// to, suggested after the candidates since they're less likely to be what was meant.
+    assoc_consts: Option<(Span, Vec<String>)>,
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -660,6 +778,26 @@ impl AmbiguityKind {
             }
         }
     }
+
+    /// Explains how names from glob imports are shadowed, for the ambiguities caused by those
+    /// rules.
+    fn glob_precedence(self) -> Option<&'static str> {
+        match self {
+            AmbiguityKind::GlobVsGlob => Some(
+                "names from different glob imports only conflict when used, and an explicit \
+                 import or an item with the same name takes precedence over both",
+            ),
+            AmbiguityKind::GlobVsExpanded => Some(
+                "explicit imports and items take precedence over glob imports, but not when \
+                 they are produced by a macro during import or macro resolution",
+            ),
+            AmbiguityKind::GlobVsOuter => Some(
+                "names from glob imports don't shadow names from outer scopes during import or \
+                 macro resolution",
+            ),
+            _ => None,
+        }
+    }
 }
 
 /// Miscellaneous bits of metadata for better ambiguity error reporting.
@@ -836,6 +974,34 @@ pub struct ExternPreludeEntry<'a> {
     pub introduced_by_item: bool,
 }
 
+/// What a path resolves to and the names in scope where it is written, recorded during late
+/// resolution with `-Z record-resolution-scopes` for tools to query.
+#[derive(Clone, Debug)]
+pub struct ScopeSnapshot {
+    pub span: Span,
+    /// What the path was resolved to, as far as it could be, in the namespace it was expected in.
+    pub resolved: Option<PartialRes>,
+    /// What the whole path resolves to in each namespace.
+    pub res: PerNS<Option<Res>>,
+    /// The module the path is written in. The items in scope through it, including imports and
+    /// the preludes, are not recorded since they don't change within the module.
+    pub module: DefId,
+    /// The ribs around the path, innermost first, up to the one of the module.
+    pub ribs: PerNS<Vec<RibSnapshot>>,
+    /// The labels that can be used where the path is written, innermost first.
+    pub labels: Vec<Ident>,
+}
+
+/// The names bound by a rib, a scope of local names like a block or the generic parameters of an
+/// item, in a `ScopeSnapshot`.
+#[derive(Clone, Debug)]
+pub struct RibSnapshot {
+    /// What binds the names, e.g. "the parameters of a function".
+    pub kind: String,
+    /// The bound names, in the order they are written.
+    pub bindings: Vec<(Ident, Res)>,
+}
+
 /// The main resolver class.
 ///
 /// This is the visitor that walks the whole crate.
@@ -856,6 +1022,26 @@ pub struct Resolver<'a> {
     /// Used for hints during error reporting.
     field_names: FxHashMap<DefId, Vec<Spanned<Symbol>>>,
 
+    /// Local `type Alias = dyn Trait;` items, with their rewrite into a trait alias.
+    /// Used for hints during error reporting.
+    trait_object_aliases: FxHashMap<DefId, Vec<(Span, String)>>,
+
+    /// Local `impl`s with methods taking `self`, whose paths are resolved into `impl_methods`
+    /// before late resolution.
+    local_impls: Vec<LocalImpl<'a>>,
+    /// Methods of the local `impl`s, by name.
+    /// Used for hints during error reporting.
+    impl_methods: FxHashMap<Symbol, Vec<ImplMethod>>,
+
+    /// Numbers of parameters of the associated `new` functions without `self` in the inherent
+    /// impls of local types.
+    /// Used for hints during error reporting.
+    new_fn_param_counts: FxHashMap<DefId, Vec<usize>>,
+
+    /// Uses of generic parameters of an outer item, left for late resolution to report along
+    /// with the bounds of the parameters.
+    outer_generic_param_uses: Vec<(Span, Res, HasGenericParams)>,
+
     /// All imports known to succeed or fail.
     determined_imports: Vec<&'a Import<'a>>,
 
@@ -868,6 +1054,13 @@ pub struct Resolver<'a> {
     /// This binding should be ignored during in-module resolution, so that we don't get
     /// "self-confirming" import resolutions during import validation.
     unusable_binding: Option<&'a NameBinding<'a>>,
+    /// Set while probing what a path would resolve to, see `speculative_resolve_path`.
+    speculative_resolution: Option<SpeculativeResolution>,
+    /// The name given with `-Z track-resolution`.
+    tracked_resolution: Option<Symbol>,
+    /// Notes on how the tracked name has been looked up since the path being resolved started
+    /// resolving, attached to the error if it fails to.
+    resolution_trace: Vec<String>,
 
     /// The idents for the primitive types.
     primitive_type_table: PrimitiveTypeTable,
@@ -878,6 +1071,10 @@ pub struct Resolver<'a> {
     import_res_map: NodeMap<PerNS<Option<Res>>>,
     /// Resolutions for labels (node IDs of their corresponding blocks or loops).
     label_res_map: NodeMap<NodeId>,
+    /// The scopes of paths, with `-Z record-resolution-scopes`.
+    scope_snapshots: Option<NodeMap<ScopeSnapshot>>,
+    /// Why the paths reported as failing to resolve failed, at the span of each error.
+    path_failures: RefCell<Vec<(Span, PathFailure)>>,
 
     /// `CrateNum` resolutions of `extern crate` items.
     extern_crate_map: FxHashMap<LocalDefId, CrateNum>,
@@ -921,8 +1118,12 @@ pub struct Resolver<'a> {
     ambiguity_errors: Vec<AmbiguityError<'a>>,
     /// `use` injections are delayed for better placement and deduplication.
     use_injections: Vec<UseError<'a>>,
+    /// `new Type(<args>)` errors are delayed until the constructors of local types are known.
+    new_operator_errors: Vec<NewOperatorError<'a>>,
     /// Crate-local macro expanded `macro_export` referred to by a module-relative path.
     macro_expanded_macro_export_errors: BTreeSet<(Span, Span)>,
+    /// Paths that failed to resolve, collected for `-Z emit-unresolved-names`.
+    unresolved_names: Vec<UnresolvedName>,
 
     arenas: &'a ResolverArenas<'a>,
     dummy_binding: &'a NameBinding<'a>,
@@ -958,6 +1159,9 @@ pub struct Resolver<'a> {
     /// `macro_rules` scopes *produced* by expanding the macro invocations,
     /// include all the `macro_rules` items and other invocations generated by them.
     output_macro_rules_scopes: FxHashMap<ExpnId, MacroRulesScope<'a>>,
+    /// `macro_rules` scopes in which the items are defined, for the suggestions of late
+    /// resolution, where the `macro_rules` items aren't in scope.
+    item_macro_rules_scopes: FxHashMap<LocalDefId, MacroRulesScope<'a>>,
     /// Helper attributes that are in scope for the given expansion.
     helper_attrs: FxHashMap<ExpnId, Vec<Ident>>,
 
@@ -970,6 +1174,10 @@ pub struct Resolver<'a> {
     /// it's not used during normal resolution, only for better error reporting.
     struct_constructors: DefIdMap<(Res, ty::Visibility)>,
 
+    /// `let` bindings that could have been `const` items, keyed by their pattern's `NodeId`.
+    /// Only used to suggest so when they're referred to from a constant.
+    const_candidate_locals: NodeMap<ConstCandidateLocal>,
+
     /// Features enabled for this crate.
     active_features: FxHashSet<Symbol>,
 
@@ -1191,6 +1399,10 @@ impl<'a> Resolver<'a> {
         let root_module_kind = ModuleKind::Def(DefKind::Mod, root_def_id, kw::Invalid);
         let graph_root = arenas.alloc_module(ModuleData {
             no_implicit_prelude: attr::contains_name(&krate.attrs, sym::no_implicit_prelude),
+            on_unresolved: attr::first_attr_value_str_by_name(
+                &krate.attrs,
+                sym::rustc_on_unresolved,
+            ),
             ..ModuleData::new(None, root_module_kind, root_def_id, ExpnId::root(), krate.span)
         });
         let empty_module_kind = ModuleKind::Def(DefKind::Mod, root_def_id, kw::Invalid);
@@ -1261,18 +1473,37 @@ impl<'a> Resolver<'a> {
 
             has_self: FxHashSet::default(),
             field_names: FxHashMap::default(),
+            trait_object_aliases: FxHashMap::default(),
+            local_impls: Vec::new(),
+            impl_methods: FxHashMap::default(),
+            new_fn_param_counts: FxHashMap::default(),
+            outer_generic_param_uses: Vec::new(),
 
             determined_imports: Vec::new(),
             indeterminate_imports: Vec::new(),
 
             last_import_segment: false,
             unusable_binding: None,
+            speculative_resolution: None,
+            tracked_resolution: session
+                .opts
+                .debugging_opts
+                .track_resolution
+                .as_ref()
+                .map(|name| Symbol::intern(name)),
+            resolution_trace: Vec::new(),
 
             primitive_type_table: PrimitiveTypeTable::new(),
 
             partial_res_map: Default::default(),
             import_res_map: Default::default(),
             label_res_map: Default::default(),
+            scope_snapshots: if session.opts.debugging_opts.record_resolution_scopes {
+                Some(Default::default())
+            } else {
+                None
+            },
+            path_failures: Default::default(),
             extern_crate_map: Default::default(),
             export_map: FxHashMap::default(),
             trait_map: Default::default(),
@@ -1293,7 +1524,9 @@ impl<'a> Resolver<'a> {
             privacy_errors: Vec::new(),
             ambiguity_errors: Vec::new(),
             use_injections: Vec::new(),
+            new_operator_errors: Vec::new(),
             macro_expanded_macro_export_errors: BTreeSet::new(),
+            unresolved_names: Vec::new(),
 
             arenas,
             dummy_binding: arenas.alloc_name_binding(NameBinding {
@@ -1317,11 +1550,13 @@ impl<'a> Resolver<'a> {
             non_macro_attrs: [non_macro_attr(false), non_macro_attr(true)],
             invocation_parent_scopes,
             output_macro_rules_scopes: Default::default(),
+            item_macro_rules_scopes: Default::default(),
             helper_attrs: Default::default(),
             local_macro_def_scopes: FxHashMap::default(),
             name_already_seen: FxHashMap::default(),
             potentially_unused_imports: Vec::new(),
             struct_constructors: Default::default(),
+            const_candidate_locals: Default::default(),
             unused_macros: Default::default(),
             proc_macro_stubs: Default::default(),
             single_segment_macro_resolutions: Default::default(),
@@ -1365,6 +1600,7 @@ impl<'a> Resolver<'a> {
     }
 
     pub fn into_outputs(self) -> ResolverOutputs {
+        let import_candidates = self.public_import_candidates();
         let definitions = self.definitions;
         let extern_crate_map = self.extern_crate_map;
         let export_map = self.export_map;
@@ -1384,6 +1620,7 @@ impl<'a> Resolver<'a> {
                 .iter()
                 .map(|(ident, entry)| (ident.name, entry.introduced_by_item))
                 .collect(),
+            import_candidates,
         }
     }
 
@@ -1401,6 +1638,7 @@ impl<'a> Resolver<'a> {
                 .iter()
                 .map(|(ident, entry)| (ident.name, entry.introduced_by_item))
                 .collect(),
+            import_candidates: self.public_import_candidates(),
         }
     }
 
@@ -1451,6 +1689,9 @@ impl<'a> Resolver<'a> {
 
         self.check_unused(krate);
         self.report_errors(krate);
+        if let Some(path) = &self.session.opts.debugging_opts.emit_unresolved_names {
+            self.emit_unresolved_names(path);
+        }
         self.crate_loader.postprocess(krate);
     }
 
@@ -1741,6 +1982,9 @@ impl<'a> Resolver<'a> {
         };
         ident.span = general_span;
         let normalized_ident = Ident { span: normalized_span, ..ident };
+        self.trace_resolution(ident, || {
+            format!("looking up `{}` in the {} namespace", ident, ns.descr())
+        });
 
         // Walk backwards up the ribs in scope.
         let record_used = record_used_id.is_some();
@@ -1761,6 +2005,17 @@ impl<'a> Resolver<'a> {
                     ribs,
                 )));
             }
+            if !matches!(ribs[i].kind, ModuleRibKind(_) | MacroDefinition(_)) {
+                self.trace_resolution(ident, || {
+                    let why = if ribs[i].bindings.keys().any(|bound| bound.name == ident.name) {
+                        "is hidden from here by macro hygiene"
+                    } else {
+                        "isn't bound"
+                    };
+                    let descr = ribs[i].kind.descr(self.session);
+                    format!("skipped {}, where `{}` {}", descr, ident, why)
+                });
+            }
 
             module = match ribs[i].kind {
                 ModuleRibKind(module) => module,
@@ -1768,6 +2023,12 @@ impl<'a> Resolver<'a> {
                     // If an invocation of this macro created `ident`, give up on `ident`
                     // and switch to `ident`'s source from the macro definition.
                     ident.span.remove_mark();
+                    self.trace_resolution(ident, || {
+                        format!(
+                            "continuing from the `macro_rules!` definition that created `{}`",
+                            ident,
+                        )
+                    });
                     continue;
                 }
                 _ => continue,
@@ -1781,6 +2042,7 @@ impl<'a> Resolver<'a> {
                 record_used,
                 path_span,
             );
+            self.trace_module_probe(module, ident, ns, item.ok());
             if let Ok(binding) = item {
                 // The ident resolves to an item.
                 return Some(LexicalScopeBinding::Item(binding));
@@ -1815,10 +2077,11 @@ impl<'a> Resolver<'a> {
                 record_used,
                 path_span,
             );
+            self.trace_module_probe(module, ident, ns, result.ok());
 
             match result {
                 Ok(binding) => {
-                    if let Some(node_id) = poisoned {
+                    if let (Some(node_id), None) = (poisoned, self.speculative_resolution) {
                         self.lint_buffer.buffer_lint_with_diagnostic(
                             lint::builtin::PROC_MACRO_DERIVE_RESOLUTION_FALLBACK,
                             node_id,
@@ -1842,6 +2105,9 @@ impl<'a> Resolver<'a> {
                 if let Some(binding) = self.extern_prelude_get(ident, !record_used) {
                     return Some(LexicalScopeBinding::Item(binding));
                 }
+                self.trace_resolution(ident, || {
+                    format!("`{}` isn't the name of a crate in the extern prelude", ident)
+                });
                 if let Some(ident) = self.registered_tools.get(&ident) {
                     let binding =
                         (Res::ToolMod, ty::Visibility::Public, ident.span, ExpnId::root())
@@ -1850,17 +2116,23 @@ impl<'a> Resolver<'a> {
                 }
             }
             if let Some(prelude) = self.prelude {
-                if let Ok(binding) = self.resolve_ident_in_module_unadjusted(
+                let binding = self.resolve_ident_in_module_unadjusted(
                     ModuleOrUniformRoot::Module(prelude),
                     ident,
                     ns,
                     parent_scope,
                     false,
                     path_span,
-                ) {
+                );
+                self.trace_module_probe(prelude, ident, ns, binding.ok());
+                if let Ok(binding) = binding {
                     return Some(LexicalScopeBinding::Item(binding));
                 }
             }
+        } else {
+            self.trace_resolution(ident, || {
+                "the prelude isn't searched, because of `#![no_implicit_prelude]`".to_string()
+            });
         }
 
         if ns == TypeNS {
@@ -1875,6 +2147,80 @@ impl<'a> Resolver<'a> {
         None
     }
 
+    /// Records a note on how `ident` was looked up, for `-Z track-resolution`.
+    fn trace_resolution(&mut self, ident: Ident, note: impl FnOnce() -> String) {
+        if self.tracked_resolution == Some(ident.name) {
+            self.resolution_trace.push(note());
+        }
+    }
+
+    /// Records what looking up `ident` in `module` found, or why it found nothing, for
+    /// `-Z track-resolution`.
+    fn trace_module_probe(
+        &mut self,
+        module: Module<'a>,
+        ident: Ident,
+        ns: Namespace,
+        found: Option<&'a NameBinding<'a>>,
+    ) {
+        if self.tracked_resolution != Some(ident.name) {
+            return;
+        }
+        let module_descr = self.module_descr(module);
+        let key = BindingKey { ident: ident.normalize_to_macros_2_0(), ns, disambiguator: 0 };
+        let resolutions = self.resolutions(module).borrow();
+        let resolution = resolutions.get(&key).map(|resolution| resolution.borrow());
+        let note = match (found, resolution) {
+            (Some(binding), Some(resolution)) if resolution.shadowed_glob.is_some() => format!(
+                "found the {} `{}` in {}, shadowing the one imported by a glob",
+                binding.res().descr(),
+                ident,
+                module_descr,
+            ),
+            (Some(binding), _) if binding.is_glob_import() => format!(
+                "found the {} `{}` in {}, imported by a glob",
+                binding.res().descr(),
+                ident,
+                module_descr,
+            ),
+            (Some(binding), _) => {
+                format!("found the {} `{}` in {}", binding.res().descr(), ident, module_descr)
+            }
+            (None, Some(resolution)) if resolution.binding.is_some() => {
+                format!("`{}` in {} can't be used from here", ident, module_descr)
+            }
+            (None, _) => {
+                let hidden = resolutions.iter().any(|(other, resolution)| {
+                    other.ns == ns
+                        && other.ident.name == ident.name
+                        && resolution.borrow().binding.is_some()
+                });
+                let why = if hidden {
+                    "is hidden from here by macro hygiene"
+                } else {
+                    "isn't defined or imported"
+                };
+                format!("`{}` {} in {}", ident, why, module_descr)
+            }
+        };
+        drop(resolutions);
+        self.resolution_trace.push(note);
+    }
+
+    /// Describes the scope of the items of `module`, e.g. "module `a::b`".
+    crate fn module_descr(&self, module: Module<'a>) -> String {
+        match module.kind {
+            _ if self.prelude.map_or(false, |prelude| ptr::eq(prelude, module)) => {
+                msg!(self.session, "scope-std-prelude")
+            }
+            ModuleKind::Block(..) => msg!(self.session, "scope-block-items"),
+            ModuleKind::Def(..) => match module_to_string(module) {
+                Some(path) => msg!(self.session, "scope-module", path = path),
+                None => msg!(self.session, "the-crate-root"),
+            },
+        }
+    }
+
     fn hygienic_lexical_parent(
         &mut self,
         module: Module<'a>,
@@ -2068,6 +2414,7 @@ impl<'a> Resolver<'a> {
         let mut module = None;
         let mut allow_super = true;
         let mut second_binding = None;
+        let privacy_errors_len = self.privacy_errors.len();
 
         debug!(
             "resolve_path(path={:?}, opt_ns={:?}, record_used={:?}, \
@@ -2112,10 +2459,9 @@ impl<'a> Resolver<'a> {
                             continue;
                         }
                     }
-                    let msg = "there are too many leading `super` keywords".to_string();
                     return PathResult::Failed {
                         span: ident.span,
-                        label: msg,
+                        failure: PathFailure::TooManySupers,
                         suggestion: None,
                         is_error_from_last_segment: false,
                     };
@@ -2147,19 +2493,10 @@ impl<'a> Resolver<'a> {
 
             // Report special messages for path segment keywords in wrong positions.
             if ident.is_path_segment_keyword() && i != 0 {
-                let name_str = if name == kw::PathRoot {
-                    "crate root".to_string()
-                } else {
-                    format!("`{}`", name)
-                };
-                let label = if i == 1 && path[0].ident.name == kw::PathRoot {
-                    format!("global paths cannot start with {}", name_str)
-                } else {
-                    format!("{} in paths can only be used in start position", name_str)
-                };
+                let global = i == 1 && path[0].ident.name == kw::PathRoot;
                 return PathResult::Failed {
                     span: ident.span,
-                    label,
+                    failure: PathFailure::MisplacedKeyword { keyword: name, global },
                     suggestion: None,
                     is_error_from_last_segment: false,
                 };
@@ -2171,14 +2508,18 @@ impl<'a> Resolver<'a> {
             }
             let find_binding_in_ns = |this: &mut Self, ns| {
                 let binding = if let Some(module) = module {
-                    this.resolve_ident_in_module(
+                    let binding = this.resolve_ident_in_module(
                         module,
                         ident,
                         ns,
                         parent_scope,
                         record_used,
                         path_span,
-                    )
+                    );
+                    if let ModuleOrUniformRoot::Module(module) = module {
+                        this.trace_module_probe(module, ident, ns, binding.ok());
+                    }
+                    binding
                 } else if ribs.is_none() || opt_ns.is_none() || opt_ns == Some(MacroNS) {
                     let scopes = ScopeSet::All(ns, opt_ns.is_none());
                     this.early_resolve_ident_in_lexical_scope(
@@ -2234,7 +2575,7 @@ impl<'a> Resolver<'a> {
                         module = Some(ModuleOrUniformRoot::Module(next_module));
                         record_segment_res(self, res);
                     } else if res == Res::ToolMod && i + 1 != path.len() {
-                        if binding.is_import() {
+                        if binding.is_import() && self.speculative_resolution.is_none() {
                             self.session
                                 .struct_span_err(
                                     ident.span,
@@ -2248,6 +2589,14 @@ impl<'a> Resolver<'a> {
                     } else if res == Res::Err {
                         return PathResult::NonModule(PartialRes::new(Res::Err));
                     } else if opt_ns.is_some() && (is_last || maybe_assoc) {
+                        if is_last {
+                            self.record_reexport_path(
+                                privacy_errors_len,
+                                &path[..i],
+                                ident,
+                                binding,
+                            );
+                        }
                         self.lint_if_path_starts_with_module(
                             crate_lint,
                             path,
@@ -2259,16 +2608,9 @@ impl<'a> Resolver<'a> {
                             path.len() - i - 1,
                         ));
                     } else {
-                        let label = format!(
-                            "`{}` is {} {}, not a module",
-                            ident,
-                            res.article(),
-                            res.descr(),
-                        );
-
                         return PathResult::Failed {
                             span: ident.span,
-                            label,
+                            failure: PathFailure::NotAModule { segment: ident, res },
                             suggestion: None,
                             is_error_from_last_segment: is_last,
                         };
@@ -2288,7 +2630,7 @@ impl<'a> Resolver<'a> {
                         Some(ModuleOrUniformRoot::Module(module)) => module.res(),
                         _ => None,
                     };
-                    let (label, suggestion) = if module_res == self.graph_root.res() {
+                    let (failure, suggestion) = if module_res == self.graph_root.res() {
                         let is_mod = |res| match res {
                             Res::Def(DefKind::Mod, _) => true,
                             _ => false,
@@ -2300,7 +2642,7 @@ impl<'a> Resolver<'a> {
                         });
                         if let Some(candidate) = candidates.get(0) {
                             (
-                                String::from("unresolved import"),
+                                PathFailure::UnresolvedImport,
                                 Some((
                                     vec![(ident.span, pprust::path_to_string(&candidate.path))],
                                     String::from("a similar path exists"),
@@ -2308,42 +2650,39 @@ impl<'a> Resolver<'a> {
                                 )),
                             )
                         } else {
-                            (format!("maybe a missing crate `{}`?", ident), None)
+                            (PathFailure::MissingCrate { segment: ident }, None)
                         }
                     } else if i == 0 {
-                        (format!("use of undeclared type or module `{}`", ident), None)
+                        (PathFailure::UndeclaredTypeOrModule { segment: ident, ns }, None)
                     } else {
-                        let mut msg =
-                            format!("could not find `{}` in `{}`", ident, path[i - 1].ident);
+                        let parent = path[i - 1].ident;
+                        let mut failure = PathFailure::NotFoundIn { segment: ident, parent, ns };
                         if ns == TypeNS || ns == ValueNS {
                             let ns_to_try = if ns == TypeNS { ValueNS } else { TypeNS };
                             if let FindBindingResult::Binding(Ok(binding)) =
                                 find_binding_in_ns(self, ns_to_try)
                             {
-                                let mut found = |what| {
-                                    msg = format!(
-                                        "expected {}, found {} `{}` in `{}`",
-                                        ns.descr(),
-                                        what,
-                                        ident,
-                                        path[i - 1].ident
-                                    )
-                                };
-                                if binding.module().is_some() {
-                                    found("module")
+                                let found = if binding.module().is_some() {
+                                    "module"
                                 } else {
                                     match binding.res() {
-                                        def::Res::<NodeId>::Def(kind, id) => found(kind.descr(id)),
-                                        _ => found(ns_to_try.descr()),
+                                        def::Res::<NodeId>::Def(kind, id) => kind.descr(id),
+                                        _ => ns_to_try.descr(),
                                     }
-                                }
+                                };
+                                failure = PathFailure::FoundInOtherNamespace {
+                                    segment: ident,
+                                    parent,
+                                    expected: ns,
+                                    found,
+                                };
                             };
                         }
-                        (msg, None)
+                        (failure, None)
                     };
                     return PathResult::Failed {
                         span: ident.span,
-                        label,
+                        failure,
                         suggestion,
                         is_error_from_last_segment: is_last,
                     };
@@ -2360,6 +2699,38 @@ impl<'a> Resolver<'a> {
         })
     }
 
+    /// If the only privacy error reported since `privacy_errors_len` is for a module along
+    /// `module_path`, and the path goes on to name the public item `binding` as `ident`, records
+    /// the rest of the path so that the error can suggest re-exporting the item.
+    fn record_reexport_path(
+        &mut self,
+        privacy_errors_len: usize,
+        module_path: &[Segment],
+        ident: Ident,
+        binding: &NameBinding<'_>,
+    ) {
+        if binding.vis != ty::Visibility::Public {
+            return;
+        }
+        if let [error] = &mut self.privacy_errors[privacy_errors_len..] {
+            if !error.binding.module().map_or(false, |module| module.is_normal())
+                || error.binding.is_import()
+            {
+                return;
+            }
+            if let Some(i) = module_path.iter().position(|seg| seg.ident.span == error.ident.span)
+            {
+                error.reexport_path = Some(
+                    module_path[i + 1..]
+                        .iter()
+                        .map(|seg| seg.ident)
+                        .chain(iter::once(ident))
+                        .collect(),
+                );
+            }
+        }
+    }
+
     fn lint_if_path_starts_with_module(
         &mut self,
         crate_lint: CrateLint,
@@ -2451,6 +2822,7 @@ impl<'a> Resolver<'a> {
             Res::Local(_) => {
                 use ResolutionError::*;
                 let mut res_err = None;
+                let mut item_ribs = 0;
 
                 for rib in ribs {
                     match rib.kind {
@@ -2461,22 +2833,41 @@ impl<'a> Resolver<'a> {
                         | ForwardTyParamBanRibKind => {
                             // Nothing to do. Continue.
                         }
-                        ItemRibKind(_) | FnItemRibKind | AssocItemRibKind => {
+                        ItemRibKind(_) | FnItemRibKind(_) | AssocItemRibKind => {
                             // This was an attempt to access an upvar inside a
                             // named function item. This is not allowed, so we
                             // report an error.
+                            item_ribs += 1;
                             if record_used {
                                 // We don't immediately trigger a resolve error, because
                                 // we want certain other resolution errors (namely those
                                 // emitted for `ConstantItemRibKind` below) to take
                                 // precedence.
-                                res_err = Some(CannotCaptureDynamicEnvironmentInFnItem);
+                                // Turning the function into a closure only helps when it is
+                                // the single item (its generics rib and its own rib) between
+                                // the local and its use.
+                                let nested_fn = match rib.kind {
+                                    FnItemRibKind(nested_fn) if item_ribs == 2 => nested_fn,
+                                    _ => None,
+                                };
+                                res_err = Some(CannotCaptureDynamicEnvironmentInFnItem(nested_fn));
                             }
                         }
-                        ConstantItemRibKind => {
+                        ConstantItemRibKind(item_kind) => {
                             // Still doesn't deal with upvars
                             if record_used {
-                                self.report_error(span, AttemptToUseNonConstantValueInConstant);
+                                let candidate = match res {
+                                    Res::Local(id) => self.const_candidate_locals.get(&id).copied(),
+                                    _ => None,
+                                };
+                                self.report_error(
+                                    span,
+                                    AttemptToUseNonConstantValueInConstant {
+                                        ident: rib_ident,
+                                        item_kind,
+                                        candidate,
+                                    },
+                                );
                             }
                             return Res::Err;
                         }
@@ -2496,30 +2887,24 @@ impl<'a> Resolver<'a> {
                         | ModuleRibKind(..)
                         | MacroDefinition(..)
                         | ForwardTyParamBanRibKind
-                        | ConstantItemRibKind => {
+                        | ConstantItemRibKind(_) => {
                             // Nothing to do. Continue.
                             continue;
                         }
                         // This was an attempt to use a type parameter outside its scope.
                         ItemRibKind(has_generic_params) => has_generic_params,
-                        FnItemRibKind => HasGenericParams::Yes,
+                        FnItemRibKind(_) => HasGenericParams::Yes(DUMMY_SP),
                     };
 
                     if record_used {
-                        self.report_error(
-                            span,
-                            ResolutionError::GenericParamsFromOuterFunction(
-                                res,
-                                has_generic_params,
-                            ),
-                        );
+                        self.outer_generic_param_uses.push((span, res, has_generic_params));
                     }
                     return Res::Err;
                 }
             }
             Res::Def(DefKind::ConstParam, _) => {
                 let mut ribs = ribs.iter().peekable();
-                if let Some(Rib { kind: FnItemRibKind, .. }) = ribs.peek() {
+                if let Some(Rib { kind: FnItemRibKind(_), .. }) = ribs.peek() {
                     // When declaring const parameters inside function signatures, the first rib
                     // is always a `FnItemRibKind`. In this case, we can skip it, to avoid it
                     // (spuriously) conflicting with the const param.
@@ -2528,19 +2913,13 @@ impl<'a> Resolver<'a> {
                 for rib in ribs {
                     let has_generic_params = match rib.kind {
                         ItemRibKind(has_generic_params) => has_generic_params,
-                        FnItemRibKind => HasGenericParams::Yes,
+                        FnItemRibKind(_) => HasGenericParams::Yes(DUMMY_SP),
                         _ => continue,
                     };
 
                     // This was an attempt to use a const parameter outside its scope.
                     if record_used {
-                        self.report_error(
-                            span,
-                            ResolutionError::GenericParamsFromOuterFunction(
-                                res,
-                                has_generic_params,
-                            ),
-                        );
+                        self.outer_generic_param_uses.push((span, res, has_generic_params));
                     }
                     return Res::Err;
                 }
@@ -2555,6 +2934,9 @@ impl<'a> Resolver<'a> {
         if let Some(prev_res) = self.partial_res_map.insert(node_id, resolution) {
             panic!("path resolved multiple times ({:?} before, {:?} now)", prev_res, resolution);
         }
+        if let Some(snapshot) = self.scope_snapshots.as_mut().and_then(|s| s.get_mut(&node_id)) {
+            snapshot.resolved = Some(resolution);
+        }
     }
 
     fn is_accessible_from(&self, vis: ty::Visibility, module: Module<'a>) -> bool {
@@ -2592,6 +2974,10 @@ impl<'a> Resolver<'a> {
     fn report_errors(&mut self, krate: &Crate) {
         self.report_with_use_injections(krate);
 
+        for error in mem::take(&mut self.new_operator_errors) {
+            self.report_new_operator_error(error);
+        }
+
         for &(span_use, span_def) in &self.macro_expanded_macro_export_errors {
             let msg = "macro-expanded `macro_export` macros from the current crate \
                        cannot be referred to by absolute paths";
@@ -2611,29 +2997,107 @@ impl<'a> Resolver<'a> {
         let mut reported_spans = FxHashSet::default();
         for error in &self.privacy_errors {
             if reported_spans.insert(error.dedup_span) {
-                self.report_privacy_error(error);
+                self.report_privacy_error(krate, error);
             }
         }
     }
 
     fn report_with_use_injections(&mut self, krate: &Crate) {
-        for UseError { mut err, candidates, def_id, instead, suggestion } in
-            self.use_injections.drain(..)
+        for UseError {
+            mut err,
+            candidates,
+            def_id,
+            instead,
+            suggestion,
+            glob_import,
+            alloc_import,
+            assoc_consts,
+        } in self.use_injections.drain(..)
         {
-            let (span, found_use) = if let Some(def_id) = def_id.as_local() {
-                UsePlacementFinder::check(krate, self.def_id_to_node_id[def_id])
-            } else {
-                (None, false)
-            };
-            if !candidates.is_empty() {
-                diagnostics::show_candidates(&mut err, span, &candidates, instead, found_use);
+            let (use_placement, found_use) = self.use_placement(krate, def_id);
+            let paths_resolve = self.import_candidates_resolve(def_id, &candidates);
+            if let (Some(enum_path), Some((span, prefix))) = (&glob_import, &use_placement) {
+                let additional_newline = if found_use { "" } else { "\n" };
+                diagnostics::show_candidates(
+                    self.session,
+                    &mut err,
+                    use_placement.clone(),
+                    &candidates,
+                    instead,
+                    found_use,
+                    paths_resolve,
+                );
+                err.span_suggestion(
+                    *span,
+                    &msg!(self.session, "import-enum-variants", path = enum_path),
+                    format!("{}use {}::*;\n{}", prefix, enum_path, additional_newline),
+                    Applicability::MaybeIncorrect,
+                );
+            } else if !candidates.is_empty() {
+                diagnostics::show_candidates(
+                    self.session,
+                    &mut err,
+                    use_placement,
+                    &candidates,
+                    instead,
+                    found_use,
+                    paths_resolve,
+                );
+            } else if let (Some(path), Some((span, prefix))) = (&alloc_import, &use_placement) {
+                let placement = (*span, prefix.as_str());
+                self.add_suggestion_for_alloc_import(&mut err, krate, path, placement, found_use)
             } else if let Some((span, msg, sugg, appl)) = suggestion {
-                err.span_suggestion(span, msg, sugg, appl);
+                err.span_suggestion(span, &msg, sugg, appl);
+            }
+            if let Some((span, paths)) = assoc_consts {
+                let msg = match paths.len() {
+                    1 => msg!(self.session, "use-primitive-assoc-const"),
+                    _ => msg!(self.session, "use-primitive-assoc-consts"),
+                };
+                err.span_suggestions(span, &msg, paths.into_iter(), Applicability::MaybeIncorrect);
             }
             err.emit();
         }
     }
 
+    /// Suggests linking the `alloc` crate at the crate root along with importing `path` from it
+    /// at `span`, where a `use` item of the module with the error goes.
+    fn add_suggestion_for_alloc_import(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        krate: &Crate,
+        path: &str,
+        (span, prefix): (Span, &str),
+        found_use: bool,
+    ) {
+        let msg = msg!(self.session, "link-alloc", path = path);
+        let additional_newline = if found_use { "" } else { "\n" };
+        let import = format!("use {};\n{}", path, additional_newline);
+        match self.use_placement(krate, DefId::local(CRATE_DEF_INDEX)) {
+            (Some((root_span, root_prefix)), _) if root_span != span => {
+                err.multipart_suggestion(
+                    &msg,
+                    vec![
+                        (root_span, format!("{}extern crate alloc;\n", root_prefix)),
+                        (span, format!("{}{}", prefix, import)),
+                    ],
+                    Applicability::MaybeIncorrect,
+                );
+            }
+            (Some(_), _) => {
+                // Both items go to the same place, the second one only needs the indentation.
+                let indent = prefix.trim_start_matches('\n');
+                err.span_suggestion(
+                    span,
+                    &msg,
+                    format!("{}extern crate alloc;\n{}{}", prefix, indent, import),
+                    Applicability::MaybeIncorrect,
+                );
+            }
+            (None, _) => {}
+        }
+    }
+
     fn report_conflict<'b>(
         &mut self,
         parent: Module<'_>,
@@ -2748,22 +3212,42 @@ impl<'a> Resolver<'a> {
             && !has_dummy_span
             && ((new_binding.is_extern_crate() || old_binding.is_extern_crate()) || from_item);
 
+        // Removing an import is only certain to be correct if the binding that is kept is at
+        // least as visible as the one being removed, otherwise a re-export could be lost.
+        let removal_applicability = |span: Span| {
+            let (removed, kept) = if span == new_binding.span {
+                (new_binding, old_binding)
+            } else {
+                (old_binding, new_binding)
+            };
+            if kept.vis.is_at_least(removed.vis, &*self) {
+                Applicability::MachineApplicable
+            } else {
+                Applicability::MaybeIncorrect
+            }
+        };
+
         match import {
             Some((import, span, true)) if should_remove_import && import.is_nested() => {
-                self.add_suggestion_for_duplicate_nested_use(&mut err, import, span)
+                let applicability = removal_applicability(span);
+                self.add_suggestion_for_duplicate_nested_use(&mut err, import, span, applicability);
+                self.add_tool_only_rename_of_use(&mut err, name, import, span);
             }
-            Some((import, _, true)) if should_remove_import && !import.is_glob() => {
+            Some((import, span, true)) if should_remove_import && !import.is_glob() => {
                 // Simple case - remove the entire import. Due to the above match arm, this can
                 // only be a single use so just remove it entirely.
                 err.tool_only_span_suggestion(
                     import.use_span_with_attributes,
                     "remove unnecessary import",
                     String::new(),
-                    Applicability::MaybeIncorrect,
+                    removal_applicability(span),
                 );
+                self.add_tool_only_rename_of_use(&mut err, name, import, span);
             }
             Some((import, span, _)) => {
-                self.add_suggestion_for_rename_of_use(&mut err, name, import, span)
+                let binding = if span == new_binding.span { new_binding } else { old_binding };
+                let is_trait = binding.module().map_or(false, |module| module.is_trait());
+                self.add_suggestion_for_rename_of_use(&mut err, name, import, span, is_trait)
             }
             _ => {}
         }
@@ -2781,23 +3265,77 @@ impl<'a> Resolver<'a> {
     /// LL | use foo::bar as other_bar;
     ///    |     ^^^^^^^^^^^^^^^^^^^^^
     /// ```
+    ///
+    /// If the import is of a trait, which may only be needed to bring its methods into scope,
+    /// importing it as `_` is suggested as well, since that does not bind any name.
     fn add_suggestion_for_rename_of_use(
         &self,
         err: &mut DiagnosticBuilder<'_>,
         name: Symbol,
         import: &Import<'_>,
         binding_span: Span,
+        is_trait: bool,
     ) {
-        let suggested_name = if name.as_str().chars().next().unwrap().is_uppercase() {
-            format!("Other{}", name)
+        let suggestion = self.renamed_use(import, binding_span, &other_binding_name(name));
+
+        let rename_msg = "you can use `as` to change the binding name of the import";
+        if let Some(suggestion) = suggestion {
+            err.span_suggestion(
+                binding_span,
+                rename_msg,
+                suggestion,
+                Applicability::MaybeIncorrect,
+            );
         } else {
-            format!("other_{}", name)
-        };
+            err.span_label(binding_span, rename_msg);
+        }
+
+        if is_trait {
+            if let ImportKind::Single { .. } = import.kind {
+                if let Some(suggestion) = self.renamed_use(import, binding_span, "_") {
+                    err.span_suggestion(
+                        binding_span,
+                        "if the trait is only needed for its methods, import it without a name",
+                        suggestion,
+                        Applicability::MaybeIncorrect,
+                    );
+                }
+            }
+        }
+    }
 
+    /// Offers tools renaming a duplicate import as an alternative to removing it, in case the name
+    /// was meant to refer to another item later on.
+    fn add_tool_only_rename_of_use(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        name: Symbol,
+        import: &Import<'_>,
+        binding_span: Span,
+    ) {
+        let suggestion = self.renamed_use(import, binding_span, &other_binding_name(name));
+        if let Some(suggestion) = suggestion {
+            err.tool_only_span_suggestion(
+                binding_span,
+                "you can use `as` to change the binding name of the import",
+                suggestion,
+                Applicability::MaybeIncorrect,
+            );
+        }
+    }
+
+    /// Returns the replacement for `binding_span` that imports the same item as `import` under
+    /// the name `new_name`.
+    fn renamed_use(
+        &self,
+        import: &Import<'_>,
+        binding_span: Span,
+        new_name: &str,
+    ) -> Option<String> {
         let mut suggestion = None;
         match import.kind {
             ImportKind::Single { type_ns_only: true, .. } => {
-                suggestion = Some(format!("self as {}", suggested_name))
+                suggestion = Some(format!("self as {}", new_name))
             }
             ImportKind::Single { source, .. } => {
                 if let Some(pos) =
@@ -2808,7 +3346,7 @@ impl<'a> Resolver<'a> {
                             suggestion = Some(format!(
                                 "{} as {}{}",
                                 &snippet[..pos],
-                                suggested_name,
+                                new_name,
                                 if snippet.ends_with(';') { ";" } else { "" }
                             ))
                         }
@@ -2819,23 +3357,12 @@ impl<'a> Resolver<'a> {
                 suggestion = Some(format!(
                     "extern crate {} as {};",
                     source.unwrap_or(target.name),
-                    suggested_name,
+                    new_name,
                 ))
             }
             _ => unreachable!(),
         }
-
-        let rename_msg = "you can use `as` to change the binding name of the import";
-        if let Some(suggestion) = suggestion {
-            err.span_suggestion(
-                binding_span,
-                rename_msg,
-                suggestion,
-                Applicability::MaybeIncorrect,
-            );
-        } else {
-            err.span_label(binding_span, rename_msg);
-        }
+        suggestion
     }
 
     /// This function adds a suggestion to remove a unnecessary binding from an import that is
@@ -2855,7 +3382,12 @@ impl<'a> Resolver<'a> {
     /// ```
     ///
     /// If the nested use contains only one import then the suggestion will remove the entire
-    /// line.
+    /// line, and if it would be left with a single import then its braces are removed too:
+    ///
+    /// ```ignore (diagnostic)
+    /// use issue_52891::{a, b};
+    ///                  ^^^^ ^ help: remove unnecessary import
+    /// ```
     ///
     /// It is expected that the provided import is nested - this isn't checked by the
     /// function. If this invariant is not upheld, this function's behaviour will be unexpected
@@ -2865,6 +3397,7 @@ impl<'a> Resolver<'a> {
         err: &mut DiagnosticBuilder<'_>,
         import: &Import<'_>,
         binding_span: Span,
+        applicability: Applicability,
     ) {
         assert!(import.is_nested());
         let message = "remove unnecessary import";
@@ -2881,29 +3414,42 @@ impl<'a> Resolver<'a> {
 
         // If there was a closing brace then identify the span to remove any trailing commas from
         // previous imports.
-        if found_closing_brace {
-            if let Some(span) = extend_span_to_previous_binding(self.session, span) {
-                err.tool_only_span_suggestion(
-                    span,
-                    message,
-                    String::new(),
-                    Applicability::MaybeIncorrect,
-                );
-            } else {
-                // Remove the entire line if we cannot extend the span back, this indicates a
-                // `issue_52891::{self}` case.
-                err.span_suggestion(
-                    import.use_span_with_attributes,
-                    message,
-                    String::new(),
-                    Applicability::MaybeIncorrect,
-                );
+        let span = if found_closing_brace {
+            match extend_span_to_previous_binding(self.session, span) {
+                Some(span) => span,
+                None => {
+                    // Remove the entire line if we cannot extend the span back, this indicates a
+                    // `issue_52891::{self}` case. This is only certain to be correct if the group
+                    // isn't itself nested in another one.
+                    let source_map = self.session.source_map();
+                    let applicability = match source_map.span_to_snippet(import.use_span) {
+                        Ok(snippet) if snippet.matches('{').count() == 1 => applicability,
+                        _ => Applicability::MaybeIncorrect,
+                    };
+                    err.span_suggestion(
+                        import.use_span_with_attributes,
+                        message,
+                        String::new(),
+                        applicability,
+                    );
+                    return;
+                }
             }
+        } else {
+            span
+        };
 
-            return;
+        // Don't leave a single import in braces behind.
+        if let Some(brace_spans) =
+            find_spans_collapsing_nested_use(self.session, span, import.use_span)
+        {
+            let parts = brace_spans.into_iter().map(|span| (span, String::new())).collect();
+            err.multipart_suggestion(message, parts, applicability);
+        } else if found_closing_brace {
+            err.tool_only_span_suggestion(span, message, String::new(), applicability);
+        } else {
+            err.span_suggestion(span, message, String::new(), applicability);
         }
-
-        err.span_suggestion(span, message, String::new(), Applicability::MachineApplicable);
     }
 
     fn extern_prelude_get(
@@ -2922,10 +3468,14 @@ impl<'a> Resolver<'a> {
                 }
                 Some(binding)
             } else {
-                let crate_id = if !speculative {
-                    self.crate_loader.process_path_extern(ident.name, ident.span)
-                } else {
-                    self.crate_loader.maybe_process_path_extern(ident.name, ident.span)?
+                let crate_id = match self.speculative_resolution {
+                    Some(SpeculativeResolution { load_crates: false }) => {
+                        self.crate_loader.loaded_path_extern(ident.name)?
+                    }
+                    _ if !speculative => {
+                        self.crate_loader.process_path_extern(ident.name, ident.span)
+                    }
+                    _ => self.crate_loader.maybe_process_path_extern(ident.name, ident.span)?,
                 };
                 let crate_root = self.get_module(DefId { krate: crate_id, index: CRATE_DEF_INDEX });
                 Some(
@@ -2972,6 +3522,65 @@ impl<'a> Resolver<'a> {
         Ok((path, res))
     }
 
+    /// Resolves `path_str` in the module `module_id` without side effects: nothing is reported,
+    /// buffered or recorded, and crates which aren't loaded yet are loaded only if `load_crates`
+    /// is set. Tools can use this to probe whether a path would resolve.
+    pub fn speculative_resolve_str_path(
+        &mut self,
+        span: Span,
+        path_str: &str,
+        ns: Namespace,
+        module_id: LocalDefId,
+        load_crates: bool,
+    ) -> Option<Res> {
+        let path = if path_str.starts_with("::") {
+            iter::once(kw::PathRoot)
+                .chain(path_str.split("::").skip(1).map(Symbol::intern))
+                .map(|name| Segment::from_ident(Ident::new(name, span)))
+                .collect::<Vec<_>>()
+        } else {
+            path_str
+                .split("::")
+                .map(|name| Segment::from_ident(Ident::new(Symbol::intern(name), span)))
+                .collect::<Vec<_>>()
+        };
+        let module = self.module_map.get(&module_id).copied().unwrap_or(self.graph_root);
+        let parent_scope = &ParentScope::module(module);
+        self.speculative_resolve_path(&path, ns, parent_scope, span, None, load_crates)
+    }
+
+    /// Resolves `path` like `resolve_path_with_ribs` would, but without side effects, see
+    /// `speculative_resolve_str_path`.
+    crate fn speculative_resolve_path(
+        &mut self,
+        path: &[Segment],
+        ns: Namespace,
+        parent_scope: &ParentScope<'a>,
+        path_span: Span,
+        ribs: Option<&PerNS<Vec<Rib<'a>>>>,
+        load_crates: bool,
+    ) -> Option<Res> {
+        let speculative_resolution =
+            self.speculative_resolution.replace(SpeculativeResolution { load_crates });
+        let result = self.resolve_path_with_ribs(
+            path,
+            Some(ns),
+            parent_scope,
+            false,
+            path_span,
+            CrateLint::No,
+            ribs,
+        );
+        self.speculative_resolution = speculative_resolution;
+        match result {
+            PathResult::Module(ModuleOrUniformRoot::Module(module)) => module.res(),
+            PathResult::NonModule(path_res) if path_res.unresolved_segments() == 0 => {
+                Some(path_res.base_res())
+            }
+            _ => None,
+        }
+    }
+
     // Resolve a path passed from rustdoc or HIR lowering.
     fn resolve_ast_path(
         &mut self,
@@ -2994,13 +3603,13 @@ impl<'a> Resolver<'a> {
             PathResult::NonModule(..) => Err((
                 path.span,
                 ResolutionError::FailedToResolve {
-                    label: String::from("type-relative paths are not supported in this context"),
+                    failure: PathFailure::TypeRelative,
                     suggestion: None,
                 },
             )),
             PathResult::Module(..) | PathResult::Indeterminate => unreachable!(),
-            PathResult::Failed { span, label, suggestion, .. } => {
-                Err((span, ResolutionError::FailedToResolve { label, suggestion }))
+            PathResult::Failed { span, failure, suggestion, .. } => {
+                Err((span, ResolutionError::FailedToResolve { failure, suggestion }))
             }
         }
     }
@@ -3026,6 +3635,171 @@ impl<'a> Resolver<'a> {
     pub fn opt_span(&self, def_id: DefId) -> Option<Span> {
         if let Some(def_id) = def_id.as_local() { Some(self.def_id_to_span[def_id]) } else { None }
     }
+
+    // The following queries expose the machinery behind resolution diagnostics to custom
+    // drivers and analysis tools, which can reach the resolver from the `rustc_interface`
+    // callbacks through `BoxedResolver::access` once the crate has been expanded. They only look
+    // into the extern crates that are already loaded, unless `-Z suggestions-load-crates` is set.
+
+    /// Runs `f` as a read-only query, see above.
+    fn query_without_side_effects<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let load_crates = self.session.opts.debugging_opts.suggestions_load_crates;
+        let speculative_resolution =
+            self.speculative_resolution.replace(SpeculativeResolution { load_crates });
+        let result = f(self);
+        self.speculative_resolution = speculative_resolution;
+        result
+    }
+
+    /// The crate that the extern prelude entry `ident` refers to, to look for suggestions in.
+    /// Within a speculative resolution, crates are only loaded if it allows it.
+    crate fn extern_prelude_crate_for_suggestions(&mut self, ident: Ident) -> Option<CrateNum> {
+        match self.speculative_resolution {
+            Some(SpeculativeResolution { load_crates: false }) => {
+                self.crate_loader.loaded_path_extern(ident.name)
+            }
+            _ => self.crate_loader.maybe_process_path_extern(ident.name, ident.span),
+        }
+    }
+
+    /// Looks up the items named `ident` in namespace `ns`, across all crates, that could be
+    /// imported into the module `module_id`. This is the lookup behind the "consider importing"
+    /// suggestions, so items defined in `module_id` itself are not returned.
+    ///
+    /// Accessible candidates are preferred: inaccessible ones are only returned if there is no
+    /// accessible one. Candidates with shorter paths come first.
+    pub fn import_candidates(
+        &mut self,
+        ident: Ident,
+        ns: Namespace,
+        module_id: LocalDefId,
+    ) -> Vec<ImportSuggestion> {
+        let module = self.module_map.get(&module_id).copied().unwrap_or(self.graph_root);
+        let parent_scope = &ParentScope::module(module);
+        let mut candidates = self.query_without_side_effects(|this| {
+            this.lookup_import_candidates(ident, ns, parent_scope, |_| true)
+        });
+        candidates.sort_by_cached_key(|candidate| {
+            (candidate.path.segments.len(), path_names_to_string(&candidate.path))
+        });
+        candidates
+    }
+
+    /// Finds the name in scope in the module `module_id` that is most similar to `ident`, the way
+    /// the "a similarly named item exists" suggestions do. Only names from namespace `ns` are
+    /// considered, and `ident` itself never is.
+    ///
+    /// Local variables, labels and generic parameters are not in scope anymore once the crate has
+    /// been resolved, so they are never suggested.
+    pub fn typo_candidate(
+        &mut self,
+        ident: Ident,
+        ns: Namespace,
+        module_id: LocalDefId,
+    ) -> Option<TypoSuggestion> {
+        let module = self.module_map.get(&module_id).copied().unwrap_or(self.graph_root);
+        let parent_scope = &ParentScope::module(module);
+        let scope_set = ScopeSet::All(ns, false);
+        self.query_without_side_effects(|this| {
+            this.early_lookup_typo_candidate(scope_set, parent_scope, ident, &|_| true)
+        })
+    }
+
+    /// The scope recorded for the path with the node `id`, if the session was started with
+    /// `-Z record-resolution-scopes`.
+    pub fn scope_snapshot(&self, id: NodeId) -> Option<&ScopeSnapshot> {
+        self.scope_snapshots.as_ref()?.get(&id)
+    }
+
+    /// The scopes recorded for the paths whose span contains `span`, e.g. the position of a
+    /// cursor, innermost first. Empty unless the session was started with
+    /// `-Z record-resolution-scopes`.
+    pub fn scope_snapshots_at(&self, span: Span) -> Vec<(NodeId, &ScopeSnapshot)> {
+        let mut snapshots = self
+            .scope_snapshots
+            .iter()
+            .flatten()
+            .filter(|(_, snapshot)| snapshot.span.contains(span))
+            .map(|(&id, snapshot)| (id, snapshot))
+            .collect::<Vec<_>>();
+        snapshots.sort_by_key(|&(id, snapshot)| (snapshot.span.hi() - snapshot.span.lo(), id));
+        snapshots
+    }
+
+    /// Why each of the paths reported as failing to resolve so far failed, along with the span the
+    /// error points at, in the order the errors were reported. Unlike the messages of the errors,
+    /// these say which segment failed and in which namespace it was looked up.
+    pub fn path_failures(&self) -> Vec<(Span, PathFailure)> {
+        self.path_failures.borrow().clone()
+    }
+
+    /// Computes the shortest path through which the item `def_id` can be named in namespace
+    /// `ns` from the module `module_id`, following re-exports. Returns `None` if no such path
+    /// exists, e.g. because the item is private.
+    ///
+    /// The path is absolute: it starts with `crate` or the name of an extern crate in the 2018
+    /// edition, and from the crate root in the 2015 edition.
+    pub fn shortest_visible_path(
+        &mut self,
+        def_id: DefId,
+        ns: Namespace,
+        module_id: LocalDefId,
+    ) -> Option<ast::Path> {
+        let from = self.module_map.get(&module_id).copied().unwrap_or(self.graph_root);
+
+        let mut worklist = VecDeque::new();
+        if self.session.rust_2018() {
+            let crate_segment = ast::PathSegment::from_ident(Ident::with_dummy_span(kw::Crate));
+            worklist.push_back((self.graph_root, vec![crate_segment]));
+            let extern_prelude_names: Vec<_> = self.extern_prelude.keys().copied().collect();
+            for ident in extern_prelude_names {
+                let crate_id = self.query_without_side_effects(|this| {
+                    this.extern_prelude_crate_for_suggestions(ident)
+                });
+                if let Some(crate_id) = crate_id {
+                    let crate_root =
+                        self.get_module(DefId { krate: crate_id, index: CRATE_DEF_INDEX });
+                    worklist.push_back((crate_root, vec![ast::PathSegment::from_ident(ident)]));
+                }
+            }
+        } else {
+            worklist.push_back((self.graph_root, Vec::new()));
+        }
+
+        // Modules are visited breadth-first, so the first path found is a shortest one.
+        let mut seen_modules = FxHashSet::default();
+        while let Some((module, path_segments)) = worklist.pop_front() {
+            if !seen_modules.insert(module.def_id().unwrap()) {
+                continue;
+            }
+            let mut found = None;
+            module.for_each_child(self, |this, ident, child_ns, binding| {
+                if found.is_some()
+                    || !binding.is_importable()
+                    || !this.is_accessible_from(binding.vis, from)
+                {
+                    return;
+                }
+
+                let mut path_segments = path_segments.clone();
+                path_segments.push(ast::PathSegment::from_ident(ident));
+                let res = binding.res();
+                let did = match res {
+                    Res::Def(DefKind::Ctor(..), did) => this.parent(did),
+                    _ => res.opt_def_id(),
+                };
+                if child_ns == ns && did == Some(def_id) {
+                    found = Some(path_segments);
+                } else if let Some(module) = binding.module() {
+                    worklist.push_back((module, path_segments));
+                }
+            });
+            if let Some(segments) = found {
+                return Some(ast::Path { span: DUMMY_SP, segments });
+            }
+        }
+        None
+    }
 }
 
 fn names_to_string(names: &[Symbol]) -> String {
@@ -3046,6 +3820,15 @@ fn path_names_to_string(path: &Path) -> String {
     names_to_string(&path.segments.iter().map(|seg| seg.ident.name).collect::<Vec<_>>())
 }
 
+/// The name suggested for an import whose name `name` conflicts with another binding.
+fn other_binding_name(name: Symbol) -> String {
+    if name.as_str().chars().next().unwrap().is_uppercase() {
+        format!("Other{}", name)
+    } else {
+        format!("other_{}", name)
+    }
+}
+
 /// A somewhat inefficient routine to obtain the name of a module.
 fn module_to_string(module: Module<'_>) -> Option<String> {
     let mut names = Vec::new();
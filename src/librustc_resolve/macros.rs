@@ -3,14 +3,15 @@
 
 use crate::imports::ImportResolver;
 use crate::Namespace::*;
-use crate::{AmbiguityError, AmbiguityErrorMisc, AmbiguityKind, Determinacy};
-use crate::{CrateLint, ParentScope, ResolutionError, Resolver, Scope, ScopeSet, Weak};
-use crate::{ModuleKind, ModuleOrUniformRoot, NameBinding, PathResult, Segment, ToNameBinding};
+use crate::{AmbiguityError, AmbiguityErrorMisc, AmbiguityKind, Determinacy, ToNameBinding};
+use crate::{CrateLint, ParentScope, ResolutionError, Resolver, Scope, ScopeSet, UseError, Weak};
+use crate::{ModuleKind, ModuleOrUniformRoot, NameBinding, PathFailure, PathResult, Segment};
 use rustc_ast::ast::{self, NodeId};
 use rustc_ast_lowering::ResolverAstLowering;
 use rustc_ast_pretty::pprust;
 use rustc_attr::{self as attr, StabilityLevel};
 use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::ptr_key::PtrKey;
 use rustc_expand::base::{Indeterminate, InvocationRes, ResolverExpand, SyntaxExtension};
 use rustc_expand::compile_declarative_macro;
 use rustc_expand::expand::{AstFragment, AstFragmentKind, Invocation, InvocationKind};
@@ -19,7 +20,8 @@ use rustc_hir::def::{self, DefKind, NonMacroAttrKind};
 use rustc_hir::def_id;
 use rustc_middle::middle::stability;
 use rustc_middle::{span_bug, ty};
-use rustc_session::lint::builtin::UNUSED_MACROS;
+use rustc_session::lint::builtin::{SHADOWED_MACRO_RULES, UNUSED_MACROS};
+use rustc_session::lint::BuiltinLintDiagnostics;
 use rustc_session::Session;
 use rustc_span::edition::Edition;
 use rustc_span::hygiene::{self, ExpnData, ExpnId, ExpnKind};
@@ -438,6 +440,9 @@ impl<'a> Resolver<'a> {
             _ => panic!("expected `DefKind::Macro` or `Res::NonMacroAttr`"),
         };
 
+        if let ([segment], MacroKind::Bang) = (&path.segments[..], kind) {
+            self.lint_shadowed_macro_rules(segment.ident, res, parent_scope, node_id);
+        }
         self.check_stability_and_deprecation(&ext, path, node_id);
 
         Ok(if ext.macro_kind() != kind {
@@ -929,22 +934,28 @@ impl<'a> Resolver<'a> {
                     check_consistency(self, &path, path_span, kind, initial_res, res);
                 }
                 path_res @ PathResult::NonModule(..) | path_res @ PathResult::Failed { .. } => {
-                    let (span, label) = if let PathResult::Failed { span, label, .. } = path_res {
-                        (span, label)
+                    let mut suggestion = None;
+                    let (span, failure) = if let PathResult::Failed {
+                        span,
+                        failure,
+                        is_error_from_last_segment,
+                        ..
+                    } = path_res
+                    {
+                        if is_error_from_last_segment {
+                            suggestion =
+                                self.macro_path_typo_suggestion(&path, kind, &parent_scope);
+                        }
+                        (span, failure)
                     } else {
-                        (
-                            path_span,
-                            format!(
-                                "partially resolved path in {} {}",
-                                kind.article(),
-                                kind.descr()
-                            ),
-                        )
+                        (path_span, PathFailure::PartiallyResolvedMacro(kind))
                     };
-                    self.report_error(
-                        span,
-                        ResolutionError::FailedToResolve { label, suggestion: None },
-                    );
+                    let error = ResolutionError::FailedToResolve { failure, suggestion };
+                    let mut err = self.into_struct_error(span, error);
+                    if kind == MacroKind::Attr && span == path[0].ident.span {
+                        self.add_tool_attribute_help(&mut err, path[0].ident);
+                    }
+                    err.emit();
                 }
                 PathResult::Module(..) | PathResult::Indeterminate => unreachable!(),
             }
@@ -974,7 +985,28 @@ impl<'a> Resolver<'a> {
                     let msg = format!("cannot find {} `{}` in this scope", expected, ident);
                     let mut err = self.session.struct_span_err(ident.span, &msg);
                     self.unresolved_macro_suggestions(&mut err, kind, &parent_scope, ident);
-                    err.emit();
+                    let candidates = self.unresolved_macro_import_candidates(
+                        &mut err,
+                        kind,
+                        &parent_scope,
+                        ident,
+                    );
+                    if candidates.is_empty() {
+                        err.emit();
+                    } else {
+                        // Suggesting imports needs the crate, so report this along with the
+                        // unresolved names of late resolution.
+                        self.use_injections.push(UseError {
+                            err,
+                            candidates,
+                            def_id: parent_scope.module.normal_ancestor_id,
+                            instead: false,
+                            suggestion: None,
+                            glob_import: None,
+                            alloc_import: None,
+                            assoc_consts: None,
+                        });
+                    }
                 }
             }
         }
@@ -992,6 +1024,76 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    /// Lints a textual invocation of a `macro_rules!` macro which shadows another `macro_rules!`
+    /// with the same name that was defined in a different module and is only in scope through
+    /// `#[macro_use]`, as it's easy to end up expanding the wrong one of them.
+    fn lint_shadowed_macro_rules(
+        &mut self,
+        ident: Ident,
+        res: Res,
+        parent_scope: &ParentScope<'a>,
+        node_id: NodeId,
+    ) {
+        let normalized_ident = ident.normalize_to_macros_2_0();
+        let mut used = None;
+        let mut macro_rules_scope = parent_scope.macro_rules;
+        let shadowed = loop {
+            macro_rules_scope = match macro_rules_scope {
+                MacroRulesScope::Binding(macro_rules_binding) => {
+                    if macro_rules_binding.ident == normalized_ident {
+                        match used {
+                            None if macro_rules_binding.binding.res() == res => {
+                                used = Some(macro_rules_binding.binding)
+                            }
+                            // The invocation didn't resolve to this `macro_rules`, or the shadowed
+                            // one is the same macro defined again.
+                            None => return,
+                            Some(_) if macro_rules_binding.binding.res() == res => return,
+                            Some(_) => break macro_rules_binding.binding,
+                        }
+                    }
+                    macro_rules_binding.parent_macro_rules_scope
+                }
+                MacroRulesScope::Invocation(invoc_id) => self
+                    .output_macro_rules_scopes
+                    .get(&invoc_id)
+                    .cloned()
+                    .unwrap_or(self.invocation_parent_scopes[&invoc_id].macro_rules),
+                MacroRulesScope::Empty => return,
+            }
+        };
+        let used = match used {
+            Some(used) => used,
+            None => return,
+        };
+
+        // Redefining a macro later in the same module is a common way to override it on purpose.
+        let used_module = self.binding_parent_modules.get(&PtrKey(used));
+        let shadowed_module = self.binding_parent_modules.get(&PtrKey(shadowed));
+        match (used_module, shadowed_module) {
+            (Some(used_module), Some(shadowed_module))
+                if !ptr::eq(*used_module, *shadowed_module) => {}
+            _ => return,
+        }
+
+        // At most one of them can be `#[macro_export]`ed, as they would conflict in the crate root.
+        let exported = match (used.vis, shadowed.vis) {
+            (ty::Visibility::Public, _) => Some(true),
+            (_, ty::Visibility::Public) => Some(false),
+            _ => None,
+        };
+        let source_map = self.session.source_map();
+        let (used_span, shadowed_span) =
+            (source_map.guess_head_span(used.span), source_map.guess_head_span(shadowed.span));
+        self.lint_buffer.buffer_lint_with_diagnostic(
+            SHADOWED_MACRO_RULES,
+            node_id,
+            ident.span,
+            &format!("macro `{}` shadows a `macro_rules!` definition from another module", ident),
+            BuiltinLintDiagnostics::ShadowedMacroRules(ident, used_span, shadowed_span, exported),
+        );
+    }
+
     fn check_stability_and_deprecation(
         &mut self,
         ext: &SyntaxExtension,
@@ -0,0 +1,422 @@
+//! The text of name resolution diagnostics.
+//!
+//! Every message emitted by late resolution and lifetime resolution, and the reason given for a
+//! path that failed to resolve, is looked up by a short slug in `MESSAGES` rather than being
+//! written inline, so that the whole set can be reviewed in one place and replaced with
+//! `-Z resolve-messages=<file>`. The file holds one `slug = message` pair per line; blank lines
+//! and lines starting with `#` are ignored, and slugs that aren't overridden keep their built-in
+//! text.
+//!
+//! Messages may refer to the arguments passed to `msg!` with `{name}` placeholders. Any other
+//! braces are left untouched.
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_middle::bug;
+use rustc_session::Session;
+
+use std::fmt;
+use std::fs;
+
+/// Formats the message `$slug`, substituting the given `name = value` arguments.
+macro_rules! msg {
+    ($sess:expr, $slug:literal $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::messages::format_message(
+            $sess,
+            $slug,
+            &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),*],
+        )
+    };
+}
+
+static MESSAGES: &[(&str, &str)] = &[
+    // Unresolved paths.
+    ("expected-found", "expected {expected}, found {found} `{path}`"),
+    ("not-a", "not a {expected}"),
+    ("this-scope", "this scope"),
+    ("the-crate-root", "the crate root"),
+    ("cannot-find", "cannot find {expected} `{name}` in {prefix}{scope}"),
+    ("not-found-in", "not found in {scope}"),
+    ("async-block-2018", "`async` blocks are only allowed in the 2018 edition"),
+    ("async-closure-2018", "`async` closures are only allowed in the 2018 edition"),
+    ("try-block-2018", "`try` blocks are only allowed in the 2018 edition"),
+    ("await-2018", "`.await` is only allowed in the 2018 edition"),
+    ("use-postfix-await", "write `.await` after the future"),
+    ("postfix-await-help", "`await` is written after the future it waits on, as in `future.await`"),
+    ("new-operator-call-new", "call `{path}::new` to create a value"),
+    ("new-operator-new-arity", "`{path}::new` takes {count} argument{s}"),
+    ("new-operator-struct-literal", "use a struct literal to create a value"),
+    (
+        "new-operator-note",
+        "values are created with struct literals and functions, such as an associated `new` \
+         function",
+    ),
+    ("dyn-2018", "in the 2015 edition, `dyn` is only a keyword when followed by a trait"),
+    ("enable-feature", "add `#![feature({feature})]` to the crate attributes to enable"),
+    ("unstable-candidate", "the {descr} `{path}` exists but is unstable"),
+    (
+        "tracking-issue",
+        "see issue #{issue} <https://github.com/rust-lang/rust/issues/{issue}> for more \
+         information",
+    ),
+    ("try-keyword-2018", "if you want the `try` keyword, you need to be in the 2018 edition"),
+    ("similar-name-exists", "a {descr} with a similar name exists"),
+    ("similar-item-exists", "{article} {descr} with a similar name exists"),
+    ("similar-item-defined-here", "similarly named {descr} `{name}` defined here"),
+    (
+        "use-print-macro",
+        "instead of `{name}`, Rust writes to {stream} with the `{mac}!` macro, as in \
+         `{mac}!(\"{} {}\", x, y)`",
+    ),
+    ("standard-output", "standard output"),
+    ("standard-error", "standard error"),
+    ("foreign-primitive", "perhaps you meant the Rust primitive"),
+    ("foreign-type", "perhaps you meant the Rust type"),
+    ("add-lifetime-apostrophe", "add an apostrophe to refer to the lifetime"),
+    ("lifetime-declared-here", "the lifetime `{lifetime}` is declared here"),
+    (
+        "imported-in-parent",
+        "`{name}` is imported in the parent module, but imports don't apply to its child modules",
+    ),
+    ("imported-privately", "`{name}` is imported in module `{module}`, but only privately"),
+    ("reexport-to-crate", "consider re-exporting it from `{module}` to the rest of the crate"),
+    ("renamed-import", "`{name}` is imported here, but renamed to `{alias}`"),
+    ("use-import-alias", "use the name it was imported as"),
+    ("remove-import-rename", "or import it under its own name"),
+    (
+        "shadowing-import",
+        "`{name}` refers to the {descr} imported here, as explicit imports take precedence over \
+         glob imports",
+    ),
+    (
+        "shadowing-item",
+        "`{name}` refers to the {descr} defined here, as items take precedence over glob imports",
+    ),
+    ("shadowed-glob", "the {descr} `{name}` from this glob import is shadowed"),
+    ("use-shadowed-glob-item", "use the path of the {descr} from the glob import"),
+    ("defined-here", "`{path}` defined here"),
+    ("call-module-fn", "you might have meant to call a function of module `{module}`"),
+    ("use-module-value", "you might have meant to use a constant or static of module `{module}`"),
+    ("missing-const-param", "you might be missing a const parameter"),
+    ("missing-type-param", "you might be missing a type parameter"),
+    (
+        "teach-scope-rules",
+        "A name is looked up in the scopes enclosing its use, from the innermost one outwards, \
+         and `{name}` wasn't found in any of them: {scopes}. Local variables are only in scope \
+         from the `let` statement declaring them to the end of the enclosing block, and \
+         parameters only within the function, closure or item that declares them. Items are \
+         only in scope within the module that defines them, unless they're imported into \
+         another one with a `use` declaration.",
+    ),
+    ("scope-local", "a local scope"),
+    ("scope-assoc-item-generics", "the generic parameters of an associated item"),
+    ("scope-closure-params", "the parameters of a closure or async block"),
+    ("scope-fn-params", "the parameters of a function"),
+    ("scope-item-generics", "the generic parameters of an item"),
+    ("scope-constant", "the scope of a constant"),
+    ("scope-module-items", "the items of a module"),
+    ("scope-macro-rules", "a `macro_rules!` definition"),
+    ("scope-later-ty-params", "the type parameters declared later"),
+    ("scope-std-prelude", "the standard library prelude"),
+    ("scope-block-items", "the items of a block"),
+    ("scope-module", "module `{path}`"),
+    ("scope-extern-prelude", "the extern prelude"),
+    ("list-and", "{rest} and {last}"),
+    // Why a path failed to resolve.
+    ("too-many-supers", "there are too many leading `super` keywords"),
+    ("crate-root-keyword", "crate root"),
+    ("global-path-keyword", "global paths cannot start with {keyword}"),
+    ("misplaced-path-keyword", "{keyword} in paths can only be used in start position"),
+    ("segment-not-a-module", "`{segment}` is {article} {descr}, not a module"),
+    ("unresolved-import", "unresolved import"),
+    ("maybe-missing-crate", "maybe a missing crate `{segment}`?"),
+    ("undeclared-type-or-module", "use of undeclared type or module `{segment}`"),
+    ("not-found-in-parent", "could not find `{segment}` in `{parent}`"),
+    ("found-in-other-namespace", "expected {expected}, found {found} `{segment}` in `{parent}`"),
+    ("type-relative-path", "type-relative paths are not supported in this context"),
+    ("partially-resolved-macro", "partially resolved path in {article} {descr}"),
+    // `self` and `Self`.
+    ("fake-self", "you might have meant to use `self` here instead"),
+    ("self-type-unavailable", "`Self` is only available in impls, traits, and type definitions"),
+    (
+        "self-value-in-pattern",
+        "`self` value is a keyword and may not be bound to variables or shadowed",
+    ),
+    (
+        "self-value-unavailable",
+        "`self` value is a keyword only available in methods with a `self` parameter",
+    ),
+    (
+        "self-param-hygiene",
+        "this function has a `self` parameter, but a macro invocation can only access identifiers \
+         it receives from parameters",
+    ),
+    ("no-self-param", "this function doesn't have a `self` parameter"),
+    ("use-available-field", "you might have meant to use the available field"),
+    ("field-exists-in-self", "a field by this name exists in `Self`"),
+    ("use-binding-field", "`{binding}` has a field with this name"),
+    ("try", "try"),
+    ("call-as-method", "try calling `{name}` as a method"),
+    ("call-binding-method", "try calling `{name}` as a method of `{binding}`"),
+    ("self-as-ctor", "can't use `Self` as a constructor, you must use the implemented struct"),
+    // Enums, structs and their constructors.
+    ("use-variant-enum", "try using the variant's enum"),
+    ("use-variant-path", "try using the variant's path"),
+    ("import-enum-variants", "alternatively, import all the variants of `{path}`"),
+    ("use-primitive-assoc-const", "consider using the associated constant of this primitive type"),
+    (
+        "use-primitive-assoc-consts",
+        "consider using the associated constant of one of these primitive types",
+    ),
+    ("link-alloc", "a `no_std` crate has to link `alloc` explicitly to import `{path}`"),
+    ("similar-scrutinee-variant", "`{enum_path}` has a variant with a similar name"),
+    ("closest-pattern-item", "the closest match in `{module}` is a {descr}"),
+    (
+        "match-local-with-const",
+        "`{name}` is a local binding, which can't be matched against; declare it as a `const` \
+         to match on its value",
+    ),
+    (
+        "prelude-disabled",
+        "`{name}` is in the prelude, which `#[no_implicit_prelude]` disables for this module",
+    ),
+    ("import-prelude", "the whole prelude can be imported with `use {prelude}::*;`"),
+    (
+        "variants-are-not-types",
+        "enum variants are not types, their values have the type of the enum",
+    ),
+    ("variant-exists", "there is an enum variant `{variant}`; try using the variant's enum"),
+    (
+        "variant-exists-among-others",
+        "there is an enum variant `{variant}` and {others} other{s}; try using the variant's enum",
+    ),
+    ("use-enum-variant", "try using the enum's variant"),
+    ("use-one-of-enum-variants", "try using one of the enum's variants"),
+    ("enum-variants-note", "did you mean to use one of the enum's variants?"),
+    ("use-variant-pat-syntax", "use the {kind} variant pattern syntax instead"),
+    ("tuple-variant-as-struct", "tuple variant `{path}` written as struct variant"),
+    ("use-tuple-variant-pat-syntax", "use the tuple variant pattern syntax instead"),
+    (
+        "binding-similar-to-variant",
+        "pattern binding `{name}` is named similarly to the variant `{variant}`",
+    ),
+    ("spelled-out-self-type", "the self type of this impl is written out in full"),
+    ("parenthesize-struct-literal", "surround the struct literal with parentheses"),
+    ("struct-literal-syntax", "did you mean `{path} { /* fields */ }`?"),
+    ("tuple-struct-syntax", "did you mean `{path}( /* fields */ )`?"),
+    ("private-ctor", "constructor is not visible here due to private fields"),
+    ("alias-as-ctor", "can't use a type alias as a constructor"),
+    ("alias-as-trait", "type aliases cannot be used as traits"),
+    (
+        "use-trait-alias",
+        "you might have meant to use `#![feature(trait_alias)]` instead of a `type` alias",
+    ),
+    // Syntax that was probably meant to be something else.
+    ("assign-instead", "use `=` if you meant to assign"),
+    ("reachable-label", "a label with this name is reachable"),
+    ("label-tick", "labels start with a tick"),
+    ("introduce-binding", "you might have meant to introduce a new binding"),
+    ("binding-declared-later", "a binding with this name is declared later"),
+    ("declare-before-use", "consider declaring `{name}` before using it"),
+    ("binding-in-inner-block", "a binding with this name is declared in an inner block"),
+    ("inner-block-ends", "the block declaring `{name}` ends here"),
+    ("binding-out-of-scope", "`{name}` is only in scope until the end of the block declaring it"),
+    ("use-turbofish", "use `::<...>` instead of `<...>` to specify type arguments"),
+    ("parsed-as-comparison", "without `::`, the `<` is parsed as a comparison operator"),
+    ("use-path-separator", "use the path separator to refer to an item"),
+    ("invoke-macro", "use `!` to invoke the macro"),
+    ("semicolon-instead", "did you mean to use `;` here instead?"),
+    ("path-separator-instead", "maybe you meant to write a path separator here"),
+    ("assignment-instead", "maybe you meant to write an assignment here"),
+    ("type-ascription-label", "expecting a type here because of type ascription"),
+    // Missing and undeclared lifetimes.
+    ("missing-lifetime-specifier", "missing lifetime specifier{s}"),
+    ("undeclared-lifetime", "use of undeclared lifetime name `{lifetime}`"),
+    ("undeclared-lifetime-label", "undeclared lifetime"),
+    ("type-param-declared-here", "the type parameter `{name}` is declared here"),
+    ("remove-lifetime-apostrophe", "remove the apostrophe to refer to the type parameter"),
+    (
+        "trait-object-borrows",
+        "if the trait object borrows from the arguments, use the elided lifetime",
+    ),
+    ("trait-object-owns", "if the trait object owns all of its data, use the `'static` lifetime"),
+    ("use-static-lifetime", "consider using the `'static` lifetime"),
+    (
+        "impl-lifetime-unnamed-note",
+        "a lifetime can only be introduced on an impl if its self type or trait names it",
+    ),
+    (
+        "static-only-in-item",
+        "only the `'static` lifetime is permitted in the type of a `{kind}` item",
+    ),
+    ("use-anonymous-lifetime", "consider using the anonymous lifetime, which will be inferred"),
+    ("introduce-lifetime", "consider introducing lifetime `{lifetime}` here"),
+    (
+        "use-single-use-anonymous-lifetime",
+        "consider using the anonymous lifetime, as `{lifetime}` is only used here",
+    ),
+    (
+        "make-lifetime-generic",
+        "consider making the {kind} lifetime-generic with a new `{lifetime}` lifetime",
+    ),
+    (
+        "higher-ranked-note",
+        "for more information on higher-ranked polymorphism, visit \
+         https://doc.rust-lang.org/nomicon/hrtb.html",
+    ),
+    (
+        "in-band-lifetimes-help",
+        "if you want to experiment with in-band lifetime bindings, add \
+         `#![feature(in_band_lifetimes)]` to the crate attributes",
+    ),
+    ("expected-named-lifetime", "expected named lifetime parameter"),
+    ("expected-lifetimes", "expected {count} lifetime parameters"),
+    ("use-existing-lifetime", "consider using the `{lifetime}` lifetime"),
+    ("introduce-named-lifetime", "consider introducing a named lifetime parameter"),
+    (
+        "name-borrowed-input-lifetime",
+        "consider naming the lifetime of the input that the output borrows from",
+    ),
+    ("other-lifetime-available", "this named lifetime is also available to use"),
+    ("other-lifetimes-available", "these named lifetimes are also available to use"),
+    ("lifetimes-available", "these named lifetimes are available to use"),
+    ("use-available-lifetime", "consider using one of the available lifetimes here"),
+    // Elision failures.
+    ("param-introduces-lifetime", "this parameter introduces a lifetime"),
+    ("param-introduces-lifetimes", "this parameter introduces {count} lifetimes"),
+    ("argument-n", "argument {index}"),
+    ("one-of-lifetimes", "one of {name}'s {count} {free}lifetimes"),
+    (
+        "no-value-to-borrow-from",
+        "this function's return type contains a borrowed value, but there is no value for it to \
+         be borrowed from",
+    ),
+    (
+        "lifetime-not-derivable",
+        "this function's return type contains a borrowed value with an elided lifetime, but the \
+         lifetime cannot be derived from the arguments",
+    ),
+    (
+        "borrowed-from-which",
+        "this function's return type contains a borrowed value, but the signature does not say \
+         which {candidates} it is borrowed from",
+    ),
+    (
+        "borrowed-from-whether",
+        "this function's return type contains a borrowed value, but the signature does not say \
+         whether it is borrowed from {candidates}",
+    ),
+    (
+        "async-fn-captures-lifetimes",
+        "the future returned by an `async fn` captures the lifetimes of all of its inputs, but \
+         its output can only borrow from an input whose lifetime is named",
+    ),
+    // Lifetime definitions.
+    (
+        "impl-trait-captures-nested",
+        "`impl Trait` can only capture lifetimes bound at the fn or impl level",
+    ),
+    ("nested-quantification", "nested quantification of lifetimes"),
+    ("mixed-in-band-and-explicit", "cannot mix in-band and explicit lifetime definitions"),
+    ("in-band-definition-here", "in-band lifetime definition here"),
+    ("explicit-definition-here", "explicit lifetime definition here"),
+    (
+        "in-band-in-fn-syntax",
+        "lifetimes used in `fn` or `Fn` syntax must be explicitly declared using `<...>` binders",
+    ),
+    ("in-band-definition", "in-band lifetime definition"),
+    ("shadowed-name", "{kind} name `{name}` shadows a {orig_kind} name that is already in scope"),
+    ("first-declared-here", "first declared here"),
+    ("lifetime-already-in-scope", "lifetime {name} already in scope"),
+    ("invalid-lifetime-name", "invalid lifetime parameter name: `{name}`"),
+    ("reserved-lifetime-name", "{name} is a reserved lifetime name"),
+    ("lifetime-declared-twice", "lifetime name `{name}` declared twice in the same scope"),
+    ("declared-twice", "declared twice"),
+    ("previous-declaration", "previous declaration here"),
+    ("unnecessary-lifetime", "unnecessary lifetime parameter `{name}`"),
+    ("use-static-directly", "you can use the `'static` lifetime directly, in place of `{name}`"),
+    // Lifetime lints.
+    ("single-use-lifetime", "lifetime parameter `{name}` only used once"),
+    ("lifetime-only-used-here", "this lifetime is only used here"),
+    ("this-lifetime", "this lifetime..."),
+    ("is-used-only-here", "...is used only here"),
+    ("elide-single-use-lifetime", "elide the single-use lifetime"),
+    ("unused-lifetime", "lifetime parameter `{name}` never used"),
+    ("elide-unused-lifetime", "elide the unused lifetime"),
+];
+
+crate fn format_message(sess: &Session, slug: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let overrides = sess.resolve_messages.get_or_init(|| load_overrides(sess));
+    let template = match overrides.get(slug) {
+        Some(template) => &template[..],
+        None => match MESSAGES.iter().find(|(s, _)| *s == slug) {
+            Some((_, template)) => template,
+            None => bug!("unknown resolver message `{}`", slug),
+        },
+    };
+    fill_placeholders(template, args)
+}
+
+/// Replaces each `{name}` in `template` for which `name` is one of `args`.
+crate fn fill_placeholders(template: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let arg = rest.find('}').and_then(|end| {
+            let name = &rest[1..end];
+            args.iter().find(|(n, _)| *n == name).map(|(_, value)| (end, value))
+        });
+        match arg {
+            Some((end, value)) => {
+                out.push_str(&value.to_string());
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn load_overrides(sess: &Session) -> FxHashMap<String, String> {
+    let mut overrides = FxHashMap::default();
+    let path = match sess.opts.debugging_opts.resolve_messages {
+        Some(ref path) => path,
+        None => return overrides,
+    };
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            sess.err(&format!("failed to read `{}`: {}", path.display(), err));
+            return overrides;
+        }
+    };
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (slug, template) = match line.find('=') {
+            Some(eq) => (line[..eq].trim(), line[eq + 1..].trim()),
+            None => {
+                sess.err(&format!(
+                    "{}:{}: expected `slug = message`, found `{}`",
+                    path.display(),
+                    i + 1,
+                    line
+                ));
+                continue;
+            }
+        };
+        if !MESSAGES.iter().any(|(s, _)| *s == slug) {
+            sess.warn(&format!("{}:{}: unknown message `{}`", path.display(), i + 1, slug));
+            continue;
+        }
+        overrides.insert(slug.to_string(), template.to_string());
+    }
+    overrides
+}
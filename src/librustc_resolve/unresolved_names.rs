@@ -0,0 +1,137 @@
+//! A machine-readable report of name resolution failures, written with
+//! `-Z emit-unresolved-names=<path>`.
+//!
+//! Every path that fails to resolve during late resolution is recorded together with the
+//! import candidates the diagnostics considered for it, and the whole list is written out as
+//! JSON once resolution is finished. This lets external tools consume resolution failures
+//! without scraping the rendered diagnostics.
+//!
+//! The format of the report should be considered *unstable*.
+
+use crate::diagnostics::ImportSuggestion;
+use crate::{path_names_to_string, Resolver, Segment};
+
+use rustc_hir::def::Namespace;
+use rustc_serialize::json::as_pretty_json;
+use rustc_span::Span;
+
+use std::fs;
+use std::path::Path;
+
+// The following data types are provided just for serialisation.
+
+#[derive(RustcEncodable)]
+crate struct UnresolvedName {
+    /// The path as written, e.g. `collections::HashMapp`.
+    path: String,
+    /// The namespace the path was resolved in (`type`, `value` or `macro`).
+    namespace: &'static str,
+    span: ReportSpan,
+    /// Index of the first segment of `path` that failed to resolve.
+    failing_segment: usize,
+    /// Items the path might have been meant to refer to, most likely first.
+    candidates: Vec<Candidate>,
+}
+
+#[derive(RustcEncodable)]
+struct ReportSpan {
+    file_name: String,
+    byte_start: u32,
+    byte_end: u32,
+    /// 1-based.
+    line_start: usize,
+    line_end: usize,
+    /// 1-based, character offset.
+    column_start: usize,
+    column_end: usize,
+}
+
+#[derive(RustcEncodable)]
+struct Candidate {
+    /// The path through which the item can be imported.
+    path: String,
+    /// What kind of item this is, e.g. `struct`.
+    descr: &'static str,
+    def_id: Option<ReportDefId>,
+    /// Whether the item is accessible from where the failing path was written.
+    accessible: bool,
+}
+
+#[derive(RustcEncodable)]
+struct ReportDefId {
+    krate: u32,
+    index: u32,
+}
+
+impl<'a> Resolver<'a> {
+    /// Records a path that failed to resolve, if `-Z emit-unresolved-names` was requested.
+    crate fn record_unresolved_name(
+        &mut self,
+        path: &[Segment],
+        span: Span,
+        ns: Namespace,
+        failing_segment: usize,
+        candidates: &[ImportSuggestion],
+    ) {
+        if self.session.opts.debugging_opts.emit_unresolved_names.is_none() {
+            return;
+        }
+
+        // Accessible items first, then the ones with the shortest paths. Ties are broken by the
+        // path itself so that the report is stable across runs.
+        let mut candidates: Vec<_> = candidates
+            .iter()
+            .map(|candidate| {
+                let path = path_names_to_string(&candidate.path);
+                (!candidate.accessible, candidate.path.segments.len(), path, candidate)
+            })
+            .collect();
+        candidates.sort_by(|a, b| (a.0, a.1, &a.2).cmp(&(b.0, b.1, &b.2)));
+        candidates.dedup_by(|a, b| a.2 == b.2);
+        let candidates = candidates
+            .into_iter()
+            .map(|(_, _, path, candidate)| Candidate {
+                path,
+                descr: candidate.descr,
+                def_id: candidate.did.map(|did| ReportDefId {
+                    krate: did.krate.as_u32(),
+                    index: did.index.as_u32(),
+                }),
+                accessible: candidate.accessible,
+            })
+            .collect();
+
+        let sm = self.session.source_map();
+        let start = sm.lookup_char_pos(span.lo());
+        let end = sm.lookup_char_pos(span.hi());
+        let span = ReportSpan {
+            file_name: start.file.name.to_string(),
+            byte_start: start.file.original_relative_byte_pos(span.lo()).0,
+            byte_end: start.file.original_relative_byte_pos(span.hi()).0,
+            line_start: start.line,
+            line_end: end.line,
+            column_start: start.col.0 + 1,
+            column_end: end.col.0 + 1,
+        };
+
+        self.unresolved_names.push(UnresolvedName {
+            path: Segment::names_to_string(path),
+            namespace: ns.descr(),
+            span,
+            failing_segment,
+            candidates,
+        });
+    }
+
+    /// Writes the failures collected by `record_unresolved_name` to `path`.
+    crate fn emit_unresolved_names(&self, path: &Path) {
+        let report = as_pretty_json(&self.unresolved_names).to_string();
+        if let Err(err) = fs::write(path, report) {
+            self.session.err(&format!(
+                "failed to write unresolved names to `{}`: {}",
+                path.display(),
+                err
+            ));
+        }
+    }
+}
@@ -659,6 +659,7 @@ impl DebuggingOptions {
             report_delayed_bugs: self.report_delayed_bugs,
             macro_backtrace: self.macro_backtrace,
             deduplicate_diagnostics: self.deduplicate_diagnostics,
+            suggestion_applicability: self.suggestion_applicability,
         }
     }
 }
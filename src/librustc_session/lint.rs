@@ -184,12 +184,24 @@ impl<HCX> ToStableHashKey<HCX> for LintId {
     }
 }
 
+/// Where a trait object type without `dyn` was written, used to tailor the suggestions of the
+/// `bare_trait_objects` lint.
+#[derive(PartialEq, Clone, Copy)]
+pub enum BareTraitObjectPosition {
+    /// The whole type of a function parameter, e.g. `fn foo(x: Trait)`.
+    FnParam,
+    /// The whole return type of a function, e.g. `fn foo() -> Trait`.
+    FnReturn,
+    /// Anywhere else, e.g. `&Trait` or `Box<Trait>`.
+    Other,
+}
+
 // This could be a closure, but then implementing derive trait
 // becomes hacky (and it gets allocated).
 #[derive(PartialEq)]
 pub enum BuiltinLintDiagnostics {
     Normal,
-    BareTraitObject(Span, /* is_global */ bool),
+    BareTraitObject(Span, /* is_global */ bool, BareTraitObjectPosition),
     AbsPathWithModule(Span),
     ProcMacroDeriveResolutionFallback(Span),
     MacroExpandedMacroExportsAccessedByAbsolutePaths(Span),
@@ -199,6 +211,9 @@ pub enum BuiltinLintDiagnostics {
     RedundantImport(Vec<(Span, bool)>, Ident),
     DeprecatedMacro(Option<Symbol>, Span),
     UnusedDocComment(Span),
+    BindingSimilarToVariant(Span, String),
+    SpelledOutSelfType(Span),
+    ShadowedMacroRules(Ident, Span, Span, /* exported */ Option<bool>),
 }
 
 /// Lints that are buffered up early on in the `Session` before the
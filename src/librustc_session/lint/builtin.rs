@@ -86,6 +86,12 @@ declare_lint! {
     "detects unnecessarily qualified names"
 }
 
+declare_lint! {
+    pub SPELLED_OUT_SELF_TYPES,
+    Allow,
+    "detects the self type of an impl written out in full where `Self` could be used"
+}
+
 declare_lint! {
     pub UNKNOWN_LINTS,
     Warn,
@@ -141,6 +147,12 @@ declare_lint! {
     "detects pattern bindings with the same name as one of the matched variants"
 }
 
+declare_lint! {
+    pub BINDINGS_SIMILAR_TO_VARIANT_NAME,
+    Allow,
+    "detects match arm bindings named similarly to a unit variant in scope"
+}
+
 declare_lint! {
     pub UNUSED_MACROS,
     Warn,
@@ -454,6 +466,13 @@ declare_lint! {
      via the module system"
 }
 
+declare_lint! {
+    pub SHADOWED_MACRO_RULES,
+    Allow,
+    "detects `macro_rules!` invocations resolving to a definition that shadows \
+     one from another module"
+}
+
 declare_lint! {
     pub MACRO_EXPANDED_MACRO_EXPORTS_ACCESSED_BY_ABSOLUTE_PATHS,
     Deny,
@@ -560,6 +579,7 @@ declare_lint_pass! {
         UNUSED_EXTERN_CRATES,
         UNUSED_CRATE_DEPENDENCIES,
         UNUSED_QUALIFICATIONS,
+        SPELLED_OUT_SELF_TYPES,
         UNKNOWN_LINTS,
         UNUSED_VARIABLES,
         UNUSED_ASSIGNMENTS,
@@ -568,6 +588,7 @@ declare_lint_pass! {
         UNREACHABLE_PATTERNS,
         OVERLAPPING_PATTERNS,
         BINDINGS_WITH_VARIANT_NAME,
+        BINDINGS_SIMILAR_TO_VARIANT_NAME,
         UNUSED_MACROS,
         WARNINGS,
         UNUSED_FEATURES,
@@ -609,6 +630,7 @@ declare_lint_pass! {
         WHERE_CLAUSES_OBJECT_SAFETY,
         PROC_MACRO_DERIVE_RESOLUTION_FALLBACK,
         MACRO_USE_EXTERN_CRATE,
+        SHADOWED_MACRO_RULES,
         MACRO_EXPANDED_MACRO_EXPORTS_ACCESSED_BY_ABSOLUTE_PATHS,
         ILL_FORMED_ATTRIBUTE_INPUT,
         CONFLICTING_REPR_HINTS,
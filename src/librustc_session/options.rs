@@ -8,6 +8,7 @@ use crate::utils::NativeLibKind;
 use rustc_target::spec::{CodeModel, LinkerFlavor, MergeFunctions, PanicStrategy};
 use rustc_target::spec::{RelocModel, RelroLevel, TargetTriple, TlsModel};
 
+use rustc_errors::Applicability;
 use rustc_feature::UnstableFeatures;
 use rustc_span::edition::Edition;
 use rustc_span::SourceFileHashAlgorithm;
@@ -266,6 +267,8 @@ macro_rules! options {
         pub const parse_merge_functions: &str = "one of: `disabled`, `trampolines`, or `aliases`";
         pub const parse_symbol_mangling_version: &str = "either `legacy` or `v0` (RFC 2603)";
         pub const parse_src_file_hash: &str = "either `md5` or `sha1`";
+        pub const parse_suggestion_applicability: &str =
+            "one of: `machine-applicable`, `maybe-incorrect`, `has-placeholders`, or `unspecified`";
         pub const parse_relocation_model: &str =
             "one of supported relocation models (`rustc --print relocation-models`)";
         pub const parse_code_model: &str =
@@ -661,6 +664,20 @@ macro_rules! options {
             true
         }
 
+        fn parse_suggestion_applicability(
+            slot: &mut Option<Applicability>,
+            v: Option<&str>,
+        ) -> bool {
+            *slot = Some(match v {
+                Some("machine-applicable") => Applicability::MachineApplicable,
+                Some("maybe-incorrect") => Applicability::MaybeIncorrect,
+                Some("has-placeholders") => Applicability::HasPlaceholders,
+                Some("unspecified") => Applicability::Unspecified,
+                _ => return false,
+            });
+            true
+        }
+
         fn parse_target_feature(slot: &mut String, v: Option<&str>) -> bool {
             match v {
                 Some(s) => {
@@ -849,6 +866,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "in addition to `.mir` files, create graphviz `.dot` files (default: no)"),
     emit_stack_sizes: bool = (false, parse_bool, [UNTRACKED],
         "emit a section containing stack size metadata (default: no)"),
+    emit_unresolved_names: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write a JSON report of every path that failed to resolve to the given file"),
     fewer_names: bool = (false, parse_bool, [TRACKED],
         "reduce memory use by retaining fewer names within compilation artifacts (LLVM-IR) \
         (default: no)"),
@@ -970,10 +989,16 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "enable queries of the dependency graph for regression testing (default: no)"),
     query_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some statistics about the query system (default: no)"),
+    record_resolution_scopes: bool = (false, parse_bool, [UNTRACKED],
+        "record what each path resolves to in every namespace and the names in scope where it \
+        is written, for tools to query (default: no)"),
     relro_level: Option<RelroLevel> = (None, parse_relro_level, [TRACKED],
         "choose which RELRO level to use"),
     report_delayed_bugs: bool = (false, parse_bool, [TRACKED],
         "immediately print bugs registered with `delay_span_bug` (default: no)"),
+    resolve_messages: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "replace the text of name resolution diagnostics with the `slug = message` lines \
+        of the given file"),
     // The default historical behavior was to always run dsymutil, so we're
     // preserving that temporarily, but we're likely to switch the default
     // soon.
@@ -1013,6 +1038,12 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "hash algorithm of source files in debug info (`md5`, or `sha1`)"),
     strip: Strip = (Strip::None, parse_strip, [UNTRACKED],
         "tell the linker which information to strip (`none` (default), `debuginfo` or `symbols`)"),
+    suggestion_applicability: Option<Applicability> = (None,
+        parse_suggestion_applicability, [UNTRACKED],
+        "drop the suggestions of diagnostics that are less certain than the given applicability \
+        (default: keep all suggestions)"),
+    suggestions_load_crates: bool = (false, parse_bool, [UNTRACKED],
+        "load extern crates just to suggest similarly named ones in errors (default: no)"),
     symbol_mangling_version: SymbolManglingVersion = (SymbolManglingVersion::Legacy,
         parse_symbol_mangling_version, [TRACKED],
         "which mangling version to use for symbol names"),
@@ -1038,6 +1069,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "choose the TLS model to use (`rustc --print tls-models` for details)"),
     trace_macros: bool = (false, parse_bool, [UNTRACKED],
         "for every macro invocation, print its name and arguments (default: no)"),
+    track_resolution: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "explain how a path with a segment of this name was looked up if it fails to resolve"),
     treat_err_as_bug: Option<usize> = (None, parse_treat_err_as_bug, [TRACKED],
         "treat error number `val` that occurs as bug"),
     ui_testing: bool = (false, parse_bool, [UNTRACKED],
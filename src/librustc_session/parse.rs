@@ -11,6 +11,7 @@ use rustc_feature::{find_feature_issue, GateIssue, UnstableFeatures};
 use rustc_span::edition::Edition;
 use rustc_span::hygiene::ExpnId;
 use rustc_span::source_map::{FilePathMapping, SourceMap};
+use rustc_span::symbol::Ident;
 use rustc_span::{MultiSpan, Span, Symbol};
 
 use std::collections::BTreeMap;
@@ -75,6 +76,33 @@ impl SymbolGallery {
     }
 }
 
+/// A type ascription `<expr>: <type>` as the parser saw it, used to tell when something else was
+/// meant by the `:`.
+#[derive(Clone, Copy, Debug)]
+pub struct TypeAscription {
+    /// The span of the `:`.
+    pub colon_span: Span,
+    /// Whether the type directly follows the `:`, as a path segment would follow `::`.
+    pub type_adjacent: bool,
+    /// Whether the type starts on a later line than the `:`, as a statement would after `;`.
+    pub type_on_next_line: bool,
+    /// Whether an `=` follows the type, as in a `let` statement.
+    pub followed_by_eq: bool,
+}
+
+/// A `new Type(<args>)` expression, as it would be written in Java or C#, that the parser
+/// recovered as an erroneous expression.
+#[derive(Clone, Debug)]
+pub struct NewExpr {
+    /// The span of `new`.
+    pub new_span: Span,
+    /// The segments of `Type`, without their generic arguments.
+    pub ty_path: Vec<Ident>,
+    /// The span of `Type`.
+    pub ty_span: Span,
+    pub arg_count: usize,
+}
+
 /// Construct a diagnostic for a language feature error due to the given `span`.
 /// The `feature`'s `Symbol` is the one you used in `active.rs` and `rustc_span::symbols`.
 pub fn feature_err<'a>(
@@ -131,6 +159,14 @@ pub struct ParseSess {
     /// operation token that followed it, but that the parser cannot identify without further
     /// analysis.
     pub ambiguous_block_expr_parse: Lock<FxHashMap<Span, Span>>,
+    /// Contains the spans of paths parsed as expressions where struct literals aren't allowed,
+    /// like the condition of an `if`, that were followed by braces that could only have been meant
+    /// as the fields of a struct literal, mapped to the span of that struct literal.
+    pub banned_struct_literals: Lock<FxHashMap<Span, Span>>,
+    /// Contains the type ascriptions that were parsed, by the span of the ascribed expression.
+    pub type_ascriptions: Lock<FxHashMap<Span, TypeAscription>>,
+    /// Contains the `new Type(<args>)` expressions that were recovered, by their span.
+    pub new_exprs: Lock<FxHashMap<Span, NewExpr>>,
     pub injected_crate_name: OnceCell<Symbol>,
     pub gated_spans: GatedSpans,
     pub symbol_gallery: SymbolGallery,
@@ -159,6 +195,9 @@ impl ParseSess {
             source_map,
             buffered_lints: Lock::new(vec![]),
             ambiguous_block_expr_parse: Lock::new(FxHashMap::default()),
+            banned_struct_literals: Lock::new(FxHashMap::default()),
+            type_ascriptions: Lock::new(FxHashMap::default()),
+            new_exprs: Lock::new(FxHashMap::default()),
             injected_crate_name: OnceCell::new(),
             gated_spans: GatedSpans::default(),
             symbol_gallery: SymbolGallery::default(),
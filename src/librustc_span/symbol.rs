@@ -131,6 +131,7 @@ symbols! {
         align,
         alignstack,
         all,
+        alloc,
         allocator,
         allocator_internals,
         alloc_error_handler,
@@ -745,6 +746,7 @@ symbols! {
         rustc_nonnull_optimization_guaranteed,
         rustc_object_lifetime_default,
         rustc_on_unimplemented,
+        rustc_on_unresolved,
         rustc_outlives,
         rustc_paren_sugar,
         rustc_partition_codegened,
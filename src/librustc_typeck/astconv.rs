@@ -5,16 +5,18 @@
 
 // ignore-tidy-filelength
 
+use crate::check::method::UsePlacementFinder;
 use crate::collect::PlaceholderHirTyCollector;
 use crate::middle::resolve_lifetime as rl;
 use crate::require_c_abi_if_c_variadic;
 use rustc_ast::{ast::ParamKindOrd, util::lev_distance::find_best_match_for_name};
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_errors::ErrorReported;
-use rustc_errors::{pluralize, struct_span_err, Applicability, DiagnosticId, FatalError};
+use rustc_errors::{pluralize, struct_span_err, Applicability, DiagnosticBuilder, DiagnosticId};
+use rustc_errors::FatalError;
 use rustc_hir as hir;
 use rustc_hir::def::{CtorOf, DefKind, Namespace, Res};
-use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_hir::def_id::{DefId, LocalDefId, LOCAL_CRATE};
 use rustc_hir::intravisit::{walk_generics, Visitor as _};
 use rustc_hir::lang_items::SizedTraitLangItem;
 use rustc_hir::{Constness, GenericArg, GenericArgs};
@@ -22,6 +24,7 @@ use rustc_middle::ty::subst::{self, InternalSubsts, Subst, SubstsRef};
 use rustc_middle::ty::{
     self, Const, DefIdTree, ToPredicate, Ty, TyCtxt, TypeFoldable, WithConstness,
 };
+use rustc_middle::ty::print::with_crate_prefix;
 use rustc_middle::ty::{GenericParamDef, GenericParamDefKind};
 use rustc_session::lint::builtin::{AMBIGUOUS_ASSOCIATED_ITEMS, LATE_BOUND_LIFETIME_ARGUMENTS};
 use rustc_session::parse::feature_err;
@@ -1521,6 +1524,7 @@ impl<'o, 'tcx> dyn AstConv<'tcx> + 'o {
                         ConvertedBindingKind::Equality(ty) => Some(ty.to_string()),
                         _ => None,
                     },
+                    None,
                 )?
             };
 
@@ -2075,6 +2079,7 @@ impl<'o, 'tcx> dyn AstConv<'tcx> + 'o {
         ty_param_def_id: LocalDefId,
         assoc_name: Ident,
         span: Span,
+        hir_ref_id: hir::HirId,
     ) -> Result<ty::PolyTraitRef<'tcx>, ErrorReported> {
         let tcx = self.tcx();
 
@@ -2101,11 +2106,13 @@ impl<'o, 'tcx> dyn AstConv<'tcx> + 'o {
             assoc_name,
             span,
             || None,
+            Some((ty_param_def_id, hir_ref_id)),
         )
     }
 
     // Checks that `bounds` contains exactly one element and reports appropriate
-    // errors otherwise.
+    // errors otherwise. `ty_param` is the type parameter whose bounds these are, if any, along
+    // with the path looking up the associated type on it.
     fn one_bound_for_assoc_type<I>(
         &self,
         all_candidates: impl Fn() -> I,
@@ -2113,6 +2120,7 @@ impl<'o, 'tcx> dyn AstConv<'tcx> + 'o {
         assoc_name: Ident,
         span: Span,
         is_equality: impl Fn() -> Option<String>,
+        ty_param: Option<(LocalDefId, hir::HirId)>,
     ) -> Result<ty::PolyTraitRef<'tcx>, ErrorReported>
     where
         I: Iterator<Item = ty::PolyTraitRef<'tcx>>,
@@ -2128,6 +2136,7 @@ impl<'o, 'tcx> dyn AstConv<'tcx> + 'o {
                     &ty_param_name(),
                     assoc_name,
                     span,
+                    ty_param,
                 );
                 return Err(ErrorReported);
             }
@@ -2230,6 +2239,7 @@ impl<'o, 'tcx> dyn AstConv<'tcx> + 'o {
         ty_param_name: &str,
         assoc_name: Ident,
         span: Span,
+        ty_param: Option<(LocalDefId, hir::HirId)>,
     ) where
         I: Iterator<Item = ty::PolyTraitRef<'tcx>>,
     {
@@ -2265,11 +2275,166 @@ impl<'o, 'tcx> dyn AstConv<'tcx> + 'o {
             );
         } else {
             err.span_label(span, format!("associated type `{}` not found", assoc_name));
+            if let Some((ty_param_def_id, hir_ref_id)) = ty_param {
+                self.suggest_bound_defining_assoc_type(
+                    &mut err,
+                    ty_param_def_id,
+                    hir_ref_id,
+                    assoc_name,
+                );
+            }
         }
 
         err.emit();
     }
 
+    /// Suggests restricting a type parameter with a trait defining an associated type named
+    /// `assoc_name`, as only the associated types of its bounds can be named on it. The traits in
+    /// scope where `hir_ref_id` names the associated type are preferred, other traits are
+    /// suggested along with their import.
+    fn suggest_bound_defining_assoc_type(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        ty_param_def_id: LocalDefId,
+        hir_ref_id: hir::HirId,
+        assoc_name: Ident,
+    ) {
+        let tcx = self.tcx();
+        let hir = tcx.hir();
+        let param = match hir.get(hir.as_local_hir_id(ty_param_def_id)) {
+            hir::Node::GenericParam(param) => param,
+            // The `Self` parameter of a trait.
+            _ => return,
+        };
+
+        // We only want to suggest public or local traits (#45781).
+        let mut candidates: Vec<_> = tcx
+            .all_traits(LOCAL_CRATE)
+            .iter()
+            .copied()
+            .filter(|def_id| def_id.is_local() || tcx.visibility(*def_id) == ty::Visibility::Public)
+            .filter(|def_id| self.trait_defines_associated_type_named(*def_id, assoc_name))
+            .collect();
+        if candidates.is_empty() {
+            return;
+        }
+        candidates.sort();
+        candidates.dedup();
+        let in_scope: Vec<_> = candidates
+            .iter()
+            .copied()
+            .filter(|def_id| self.trait_in_scope(*def_id, hir_ref_id))
+            .collect();
+        let needs_import = in_scope.is_empty();
+        if !needs_import {
+            candidates = in_scope;
+        }
+
+        let one_of_them = if candidates.len() == 1 { "it" } else { "one of them" };
+        // `T` becomes `T: Trait`, and `T: Bound` becomes `T: Trait + Bound`.
+        let (span, rest) = match param.bounds.first() {
+            Some(bound) => (param.span.until(bound.span()), " + "),
+            None => (param.span, ""),
+        };
+        err.span_suggestions(
+            span,
+            &format!(
+                "the following {traits_define} an associated type `{name}`, perhaps you need to \
+                 restrict type parameter `{param}` with {one_of_them}:",
+                traits_define =
+                    if candidates.len() == 1 { "trait defines" } else { "traits define" },
+                name = assoc_name,
+                param = param.name.ident(),
+                one_of_them = one_of_them,
+            ),
+            candidates.iter().map(|&def_id| {
+                format!("{}: {}{}", param.name.ident(), tcx.item_name(def_id), rest)
+            }),
+            Applicability::MaybeIncorrect,
+        );
+
+        if needs_import {
+            let module_id = hir.as_local_hir_id(tcx.parent_module(hir_ref_id));
+            let (span, found_use) = UsePlacementFinder::check(tcx, hir.krate(), module_id);
+            if let Some(span) = span {
+                // Separate the new `use` statement from the directly following item.
+                let additional_newline = if found_use { "" } else { "\n" };
+                err.span_suggestions(
+                    span,
+                    &format!(
+                        "the following {traits_are} not in scope; perhaps add a `use` for \
+                         {one_of_them}:",
+                        traits_are = if candidates.len() == 1 { "trait is" } else { "traits are" },
+                        one_of_them = one_of_them,
+                    ),
+                    candidates.iter().map(|&def_id| {
+                        let path = with_crate_prefix(|| tcx.def_path_str(def_id));
+                        format!("use {};\n{}", path, additional_newline)
+                    }),
+                    Applicability::MaybeIncorrect,
+                );
+            }
+        }
+    }
+
+    /// Returns whether the trait `trait_def_id` is defined or imported in the blocks or the module
+    /// containing `hir_id`, or is in the prelude.
+    fn trait_in_scope(&self, trait_def_id: DefId, hir_id: hir::HirId) -> bool {
+        let tcx = self.tcx();
+        let hir = tcx.hir();
+        let block_items = hir
+            .parent_iter(hir_id)
+            .filter_map(|(_, node)| match node {
+                hir::Node::Block(block) => Some(block.stmts),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|stmt| match stmt.kind {
+                hir::StmtKind::Item(item_id) => Some(item_id),
+                _ => None,
+            });
+        let (module, ..) = hir.get_module(tcx.parent_module(hir_id));
+        // The prelude is glob imported at the crate root, with `#[prelude_import]`.
+        let prelude_import = hir.krate().item.module.item_ids.iter().find(|item_id| {
+            hir.expect_item(item_id.id).attrs.iter().any(|attr| attr.has_name(sym::prelude_import))
+        });
+        block_items
+            .chain(module.item_ids.iter().copied())
+            .chain(prelude_import.copied())
+            .any(|item_id| self.brings_trait_into_scope(hir.expect_item(item_id.id), trait_def_id))
+    }
+
+    /// Returns whether `item` defines the trait `trait_def_id` or imports it, directly, through a
+    /// glob or through a re-export.
+    fn brings_trait_into_scope(&self, item: &hir::Item<'_>, trait_def_id: DefId) -> bool {
+        let tcx = self.tcx();
+        let is_trait = |def_id: Option<DefId>| def_id == Some(trait_def_id);
+        match item.kind {
+            hir::ItemKind::Trait(..) => {
+                tcx.hir().local_def_id(item.hir_id).to_def_id() == trait_def_id
+            }
+            hir::ItemKind::Use(path, hir::UseKind::Single) => {
+                path.res == Res::Def(DefKind::Trait, trait_def_id)
+            }
+            hir::ItemKind::Use(path, hir::UseKind::Glob) => match path.res {
+                // Only the re-exports of local modules are in their exports.
+                Res::Def(DefKind::Mod, module) => match module.as_local() {
+                    Some(local) => {
+                        let exports = tcx.module_exports(local).unwrap_or(&[]);
+                        tcx.parent(trait_def_id) == Some(module)
+                            || exports.iter().any(|export| is_trait(export.res.opt_def_id()))
+                    }
+                    None => {
+                        let children = tcx.item_children(module);
+                        children.iter().any(|export| is_trait(export.res.opt_def_id()))
+                    }
+                },
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
     // Create a type from a path to an associated type.
     // For a path `A::B::C::D`, `qself_ty` and `qself_def` are the type and def for `A::B::C`
     // and item_segment is the path segment for `D`. We return a type and a def for
@@ -2330,12 +2495,18 @@ impl<'o, 'tcx> dyn AstConv<'tcx> + 'o {
                     assoc_ident,
                     span,
                     || None,
+                    None,
                 )?
             }
             (
                 &ty::Param(_),
                 Res::SelfTy(Some(param_did), None) | Res::Def(DefKind::TyParam, param_did),
-            ) => self.find_bound_for_assoc_item(param_did.expect_local(), assoc_ident, span)?,
+            ) => self.find_bound_for_assoc_item(
+                param_did.expect_local(),
+                assoc_ident,
+                span,
+                hir_ref_id,
+            )?,
             _ => {
                 if variant_resolution.is_some() {
                     // Variant in type position
@@ -6,7 +6,7 @@ mod confirm;
 pub mod probe;
 mod suggest;
 
-pub use self::suggest::{SelfSource, TraitInfo};
+pub use self::suggest::{SelfSource, TraitInfo, UsePlacementFinder};
 pub use self::CandidateSource::*;
 pub use self::MethodError::*;
 
@@ -1299,7 +1299,8 @@ pub fn provide(providers: &mut ty::query::Providers) {
     }
 }
 
-struct UsePlacementFinder<'tcx> {
+/// Finds where to insert a `use` statement in a module.
+pub struct UsePlacementFinder<'tcx> {
     target_module: hir::HirId,
     span: Option<Span>,
     found_use: bool,
@@ -1307,7 +1308,9 @@ struct UsePlacementFinder<'tcx> {
 }
 
 impl UsePlacementFinder<'tcx> {
-    fn check(
+    /// Returns where to insert a `use` statement in `target_module`, and whether that's before
+    /// another `use` statement.
+    pub fn check(
         tcx: TyCtxt<'tcx>,
         krate: &'tcx hir::Crate<'tcx>,
         target_module: hir::HirId,
@@ -0,0 +1,8 @@
+mod shapes {
+    pub struct Circle;
+}
+
+fn main() {
+    let _ = Circle;
+    let _ = collections::HashMap::<u8, u8>::new();
+}
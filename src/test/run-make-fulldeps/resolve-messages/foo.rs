@@ -0,0 +1,4 @@
+fn main() {
+    crate::Frob;
+    Frob;
+}
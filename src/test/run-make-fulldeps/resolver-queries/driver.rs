@@ -0,0 +1,118 @@
+#![feature(rustc_private)]
+
+extern crate rustc_ast;
+extern crate rustc_ast_pretty;
+extern crate rustc_driver;
+extern crate rustc_hir;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_resolve;
+extern crate rustc_span;
+
+use rustc_ast::ast::NodeId;
+use rustc_ast_pretty::pprust;
+use rustc_driver::Compilation;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::{LocalDefId, CRATE_DEF_INDEX};
+use rustc_interface::{interface, Queries};
+use rustc_middle::middle::cstore::CrateStore;
+use rustc_resolve::Namespace::TypeNS;
+use rustc_resolve::Resolver;
+use rustc_span::symbol::Ident;
+use rustc_span::{BytePos, Span, DUMMY_SP};
+
+use std::path::PathBuf;
+
+struct QueryResolver;
+
+impl rustc_driver::Callbacks for QueryResolver {
+    fn after_expansion<'tcx>(
+        &mut self,
+        compiler: &interface::Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        let parts = queries.expansion().unwrap().peek();
+        let resolver = parts.1.borrow();
+        resolver.borrow_mut().access(|resolver| {
+            let root = LocalDefId { local_def_index: CRATE_DEF_INDEX };
+            let loaded_crates = resolver.cstore().crates_untracked().len();
+
+            let candidates = resolver.import_candidates(Ident::from_str("Circle"), TypeNS, root);
+            let paths: Vec<_> = candidates
+                .iter()
+                .map(|candidate| pprust::path_to_string(&candidate.path))
+                .collect();
+            // The `Circle` of the `unloaded` crate isn't found, as that crate isn't loaded.
+            assert_eq!(paths, ["crate::shapes::Circle"]);
+
+            let typo = resolver.typo_candidate(Ident::from_str("shape"), TypeNS, root).unwrap();
+            assert_eq!(typo.candidate.as_str(), "shapes");
+
+            let circle = candidates[0].did.unwrap();
+            let path = resolver.shortest_visible_path(circle, TypeNS, root).unwrap();
+            assert_eq!(pprust::path_to_string(&path), "crate::Round");
+
+            assert_eq!(resolve(resolver, "Round", false), Some(Res::Def(DefKind::Struct, circle)));
+            assert_eq!(resolve(resolver, "shapes::Square", false), None);
+            // Paths that fail to resolve speculatively aren't reported.
+            assert!(resolver.path_failures().is_empty());
+            // The `unloaded` crate is only found once it may be loaded.
+            assert_eq!(resolve(resolver, "unloaded::Circle", false), None);
+
+            assert_eq!(resolver.cstore().crates_untracked().len(), loaded_crates);
+
+            let res = resolve(resolver, "unloaded::Circle", true);
+            assert!(matches!(res, Some(Res::Def(DefKind::Struct, _))));
+            assert_eq!(resolver.cstore().crates_untracked().len(), loaded_crates + 1);
+
+            // The input is the first file of the source map.
+            let input = compiler.session().source_map().files()[0].clone();
+            let offset = input.src.as_ref().unwrap().find("* scale").unwrap() + "* ".len();
+            let cursor = input.start_pos + BytePos(offset as u32);
+            let snapshots = resolver.scope_snapshots_at(Span::with_root_ctxt(cursor, cursor));
+            let (id, snapshot) = snapshots[0];
+            assert_eq!(resolver.scope_snapshot(id).unwrap().span, snapshot.span);
+            let local = snapshot.resolved.unwrap().base_res();
+            assert!(matches!(local, Res::Local(_)));
+            assert_eq!(snapshot.res.value_ns, Some(local));
+            assert_eq!(snapshot.res.type_ns, None);
+            let bindings: Vec<_> = snapshot
+                .ribs
+                .value_ns
+                .iter()
+                .flat_map(|rib| rib.bindings.iter().map(|(ident, _)| ident.to_string()))
+                .collect();
+            assert_eq!(bindings, ["scale", "radius"]);
+        });
+        Compilation::Stop
+    }
+}
+
+fn resolve(resolver: &mut Resolver<'_>, path: &str, load_crates: bool) -> Option<Res<NodeId>> {
+    let root = LocalDefId { local_def_index: CRATE_DEF_INDEX };
+    resolver.speculative_resolve_str_path(DUMMY_SP, path, TypeNS, root, load_crates)
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let tmpdir = PathBuf::from(&args[1]);
+    let mut sysroot = PathBuf::from(&args[3]);
+    sysroot.pop();
+    sysroot.pop();
+
+    let args = vec![
+        "driver".to_string(),
+        "input.rs".to_string(),
+        "--crate-type=lib".to_string(),
+        "--edition=2018".to_string(),
+        "-Zrecord-resolution-scopes".to_string(),
+        "--sysroot".to_string(),
+        sysroot.display().to_string(),
+        "--extern".to_string(),
+        format!("unloaded={}", tmpdir.join("libunloaded.rlib").display()),
+    ];
+    rustc_driver::catch_fatal_errors(|| {
+        rustc_driver::run_compiler(&args, &mut QueryResolver, None, None).unwrap();
+    })
+    .unwrap();
+}
@@ -0,0 +1,10 @@
+pub mod shapes {
+    pub struct Circle;
+}
+
+pub use shapes::Circle as Round;
+
+pub fn area(radius: f64) -> f64 {
+    let scale = 3.14;
+    radius * radius * scale
+}
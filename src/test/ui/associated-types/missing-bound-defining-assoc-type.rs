@@ -0,0 +1,24 @@
+mod shapes {
+    pub trait Shape {
+        type Unit;
+    }
+}
+
+fn area<S>(_shape: &S) -> S::Unit {
+    //~^ ERROR associated type `Unit` not found for `S`
+    loop {}
+}
+
+fn perimeter<S>(_shape: &S) {
+    use shapes::Shape;
+    let _: Option<S::Unit> = None;
+    //~^ ERROR associated type `Unit` not found for `S`
+}
+
+// The traits of the prelude are in scope.
+fn first<I>(_iter: I) -> I::Item {
+    //~^ ERROR associated type `Item` not found for `I`
+    loop {}
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+// edition:2018
+
+// Check that an elision failure in the return type of an `async fn` explains that the returned
+// future captures all of the input lifetimes, and suggests naming only one of the inputs.
+
+async fn longest(first: &str, second: &str) -> &str {
+    //~^ ERROR missing lifetime specifier
+    first
+}
+
+fn main() {}
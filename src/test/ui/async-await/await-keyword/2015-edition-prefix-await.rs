@@ -0,0 +1,15 @@
+// `await` is an ordinary identifier in Rust 2015, so prefix uses of it fail to resolve.
+
+fn take<T>(_: T) {}
+
+fn main() {
+    let x = 1;
+    take(await(x + 1));
+    //~^ ERROR cannot find function `await` in this scope
+    //~| NOTE `.await` is only allowed in the 2018 edition
+    //~| HELP write `.await` after the future
+    let _ = await;
+    //~^ ERROR cannot find value `await` in this scope
+    //~| NOTE `.await` is only allowed in the 2018 edition
+    //~| HELP `await` is written after the future it waits on, as in `future.await`
+}
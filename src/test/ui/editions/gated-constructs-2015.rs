@@ -0,0 +1,11 @@
+// Keywords of the 2018 edition parse as plain paths in Rust 2015; point out what was meant.
+
+fn main() {
+    let f = async || true;
+    //~^ ERROR cannot find value `async` in this scope
+    //~| NOTE `async` closures are only allowed in the 2018 edition
+    //~| HELP add `#![feature(async_closure)]` to the crate attributes to enable
+    let x: Box<dyn> = Box::new(0);
+    //~^ ERROR cannot find type `dyn` in this scope
+    //~| NOTE in the 2015 edition, `dyn` is only a keyword when followed by a trait
+}
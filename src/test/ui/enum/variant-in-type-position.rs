@@ -0,0 +1,14 @@
+// A variant written in type position is replaced by the path to its enum, as written.
+
+enum Shape {
+    Circle(f64),
+    Square(f64),
+}
+
+fn area(shape: Shape::Circle) -> f64 { //~ ERROR expected type, found variant `Shape::Circle`
+    0.0
+}
+
+fn first(value: Option<u8>::Some) {} //~ ERROR expected type, found variant `Option::Some`
+
+fn main() {}
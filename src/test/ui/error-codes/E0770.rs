@@ -0,0 +1,11 @@
+// check-pass
+
+fn foo<'a>(_: &'a i32) {
+    'a: loop { //~ WARN label name `'a` shadows a lifetime name that is already in scope
+        break 'a;
+    }
+}
+
+fn main() {
+    foo(&0);
+}
@@ -0,0 +1,7 @@
+// check-pass
+
+fn foo<'a: 'static>(_: &'a i32) {} //~ WARN unnecessary lifetime parameter `'a`
+
+fn main() {
+    foo(&0);
+}
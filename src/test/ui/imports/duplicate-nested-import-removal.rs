@@ -0,0 +1,18 @@
+// run-rustfix
+
+#![allow(unused_imports)]
+
+mod foo {
+    pub struct A;
+    pub struct B;
+    pub struct C;
+    pub struct D;
+}
+
+use foo::A;
+use foo::{A, B}; //~ ERROR the name `A` is defined multiple times
+
+use foo::C;
+use foo::{D, C}; //~ ERROR the name `C` is defined multiple times
+
+fn main() {}
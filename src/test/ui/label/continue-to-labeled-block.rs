@@ -0,0 +1,23 @@
+#![feature(label_break_value)]
+
+fn labeled_loop(items: &[u32]) {
+    'items: for &item in items {
+        'check: {
+            if item == 0 {
+                continue 'check; //~ ERROR `continue` pointing to a labeled block
+            }
+        }
+    }
+}
+
+fn unlabeled_loop(items: &[u32]) {
+    for &item in items {
+        'check: {
+            if item == 0 {
+                continue 'check; //~ ERROR `continue` pointing to a labeled block
+            }
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+// Check that `break ident` suggests the tick when `ident` names a label in scope.
+
+fn main() {
+    'outer: loop {
+        loop {
+            break outer;
+            //~^ ERROR cannot find value `outer` in this scope
+        }
+    }
+}
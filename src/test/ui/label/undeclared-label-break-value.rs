@@ -0,0 +1,26 @@
+// Suggest labeling the enclosing block for a `break` with a value outside of any loop, and never
+// suggest labeling a `while` loop for one.
+
+fn parse(input: &str) -> Option<u32> {
+    let value = {
+        if input.is_empty() {
+            break 'parsed None; //~ ERROR use of undeclared label `'parsed`
+            //~| ERROR `break` outside of a loop
+        }
+        input.parse().ok()
+    };
+    value
+}
+
+fn find(items: &[u32]) -> u32 {
+    let mut i = 0;
+    while i < items.len() {
+        if items[i] > 10 {
+            break 'search items[i]; //~ ERROR use of undeclared label `'search`
+        }
+        i += 1;
+    }
+    0
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+// Suggest introducing an undeclared label on the nearest enclosing loop that can be labeled.
+
+fn main() {
+    let mut n = 0;
+    while n < 10 {
+        for i in 0..n {
+            if i == 3 {
+                continue 'rows; //~ ERROR use of undeclared label `'rows`
+            }
+        }
+        n += 1;
+    }
+
+    'search: for row in 0..n {
+        if row == 3 {
+            continue 'search;
+        }
+        break 'found; //~ ERROR use of undeclared label `'found`
+    }
+}
@@ -0,0 +1,12 @@
+// Point at the closure making a label unreachable and suggest leaving the closure instead.
+
+fn main() {
+    'rows: for row in 0..3 {
+        (0..row).for_each(|col| {
+            if col == 1 {
+                continue 'rows; //~ ERROR use of unreachable label `'rows`
+                //~| ERROR `continue` inside of a closure
+            }
+        });
+    }
+}
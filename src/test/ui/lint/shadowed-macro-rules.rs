@@ -0,0 +1,19 @@
+#![deny(shadowed_macro_rules)]
+
+#[macro_use]
+mod a {
+    macro_rules! m {
+        () => { 1 };
+    }
+}
+
+#[macro_use]
+mod b {
+    macro_rules! m {
+        () => { 2 };
+    }
+}
+
+fn main() {
+    let _ = m!(); //~ ERROR macro `m` shadows a `macro_rules!` definition from another module
+}
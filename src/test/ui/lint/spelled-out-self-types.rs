@@ -0,0 +1,41 @@
+#![deny(spelled_out_self_types)]
+
+use std::collections::HashMap;
+
+struct Cache<K, V> {
+    entries: HashMap<K, V>,
+}
+
+impl<K, V> Cache<K, V> {
+    fn new() -> Cache<K, V> { //~ ERROR the self type of this impl is written out in full
+        Cache::<K, V>::with_entries(HashMap::new())
+        //~^ ERROR the self type of this impl is written out in full
+    }
+
+    fn with_entries(entries: HashMap<K, V>) -> Self {
+        Cache { entries }
+    }
+
+    fn flipped(self) -> Cache<V, K> {
+        Cache { entries: HashMap::new() }
+    }
+
+    // `K` here is the method's own parameter, not the impl's.
+    fn rekeyed<K>(self) -> Cache<K, V> {
+        Cache { entries: HashMap::new() }
+    }
+}
+
+struct Unit;
+
+impl Unit {
+    fn make() -> Self {
+        // `Self` can't be used within nested items.
+        fn helper() -> Unit {
+            Unit
+        }
+        helper()
+    }
+}
+
+fn main() {}
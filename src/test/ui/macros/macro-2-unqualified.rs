@@ -0,0 +1,10 @@
+// edition:2018
+#![feature(decl_macro)]
+
+mod foo {
+    pub macro m() {}
+}
+
+fn main() {
+    m!(); //~ ERROR cannot find macro `m` in this scope
+}
@@ -0,0 +1,28 @@
+// check-pass
+
+#![allow(unused_variables)]
+#![warn(bindings_similar_to_variant_name)]
+
+enum Color {
+    Red,
+    Green,
+}
+
+fn describe(color: Color) -> &'static str {
+    match color {
+        Color::Green => "green",
+        red => "red", //~ WARN pattern binding `red` is named similarly to the variant `Color::Red`
+    }
+}
+
+fn first(values: &[u8]) -> u8 {
+    match values.first() {
+        Some(&value) => value,
+        none => 0, //~ WARN pattern binding `none` is named similarly to the variant `None`
+    }
+}
+
+fn main() {
+    describe(Color::Red);
+    first(&[]);
+}
@@ -0,0 +1,13 @@
+// A field pattern written in shorthand that binds a name again is suggested to bind the field to
+// a name that isn't in scope yet.
+
+struct Point { x: i32, y: i32 }
+
+fn main() {
+    let x_2 = 0;
+    let (x, Point { x, y }) = (1, Point { x: 2, y: 3 });
+    //~^ ERROR identifier `x` is bound more than once in the same pattern
+    let (Point { x: _, ref mut y }, y) = (Point { x: 4, y: 5 }, 6);
+    //~^ ERROR identifier `y` is bound more than once in the same pattern
+    let _ = (x, x_2, y);
+}
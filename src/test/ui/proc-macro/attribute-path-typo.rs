@@ -0,0 +1,9 @@
+// aux-build:test-macros.rs
+
+extern crate test_macros;
+
+#[test_macros::identity_atr]
+//~^ ERROR failed to resolve: could not find `identity_atr` in `test_macros`
+struct S;
+
+fn main() {}
@@ -0,0 +1,18 @@
+// force-host
+// no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+use proc_macro::*;
+
+#[proc_macro]
+pub fn mixed_site_input(input: TokenStream) -> TokenStream {
+    input
+        .into_iter()
+        .map(|mut tt| {
+            tt.set_span(tt.span().resolved_at(Span::mixed_site()));
+            tt
+        })
+        .collect()
+}
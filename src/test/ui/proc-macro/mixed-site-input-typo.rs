@@ -0,0 +1,14 @@
+// Suggestions on input tokens respanned into the expansion are made at the invocation.
+
+// run-rustfix
+// aux-build:mixed-site-input.rs
+
+#[macro_use]
+extern crate mixed_site_input;
+
+const LIMIT: u8 = 10;
+
+fn main() {
+    let _ = mixed_site_input!(LIMT);
+    //~^ ERROR cannot find value `LIMT` in this scope
+}
@@ -0,0 +1,12 @@
+// Check that undeclared lifetimes in `const` and `static` items suggest `'static` instead of
+// introducing a lifetime parameter on the item itself.
+
+struct Config;
+
+const DEFAULT_NAME: &'a str = "config"; //~ ERROR use of undeclared lifetime name `'a`
+
+impl Config {
+    const PREFIX: &'a str = "cfg"; //~ ERROR use of undeclared lifetime name `'a`
+}
+
+fn main() {}
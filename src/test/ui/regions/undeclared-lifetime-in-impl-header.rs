@@ -0,0 +1,10 @@
+// Check that an undeclared lifetime in an impl header is suggested to be introduced in the
+// impl's own generics.
+
+struct Request;
+
+trait Handler {}
+
+impl Handler for fn(&'req Request) {} //~ ERROR use of undeclared lifetime name `'req`
+
+fn main() {}
@@ -0,0 +1,15 @@
+// Check that an undeclared lifetime in a default method can be introduced on the trait as well
+// as on the method itself.
+
+trait Reader {
+    fn read(&self) -> &'buf [u8] { //~ ERROR use of undeclared lifetime name `'buf`
+        &[]
+    }
+
+    fn peek(&self) -> u8 {
+        let byte: &'buf u8 = &0; //~ ERROR use of undeclared lifetime name `'buf`
+        *byte
+    }
+}
+
+fn main() {}
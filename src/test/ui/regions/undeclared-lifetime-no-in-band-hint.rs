@@ -0,0 +1,13 @@
+// Check that the `in_band_lifetimes` hint is only given where the feature would define the
+// lifetime, which it can't do next to explicitly declared lifetimes.
+
+struct Parser;
+
+impl Parser {
+    fn parse<'src>(&self, input: &'src str) -> &'out str {
+        //~^ ERROR use of undeclared lifetime name `'out`
+        input
+    }
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+// The fixes proposed by different heuristics are offered as alternatives when they are equally
+// certain.
+
+struct Config {
+    limit: u32,
+}
+
+fn check(config: &Config, limits: u32) -> bool {
+    limit > limits
+    //~^ ERROR cannot find value `limit` in this scope
+}
+
+impl Config {
+    fn reset(&mut self) {}
+}
+
+fn restart(config: &mut Config, resets: u32) {
+    reset(config);
+    //~^ ERROR cannot find function `reset` in this scope
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+// Associated types of the current trait are suggested for types, but not for patterns.
+
+trait Container {
+    type Item;
+
+    fn check(&self, value: Option<u8>) {
+        let _: Item; //~ ERROR cannot find type `Item` in this scope
+        if let Item(..) = value {}
+        //~^ ERROR cannot find tuple struct or tuple variant `Item` in this scope
+    }
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+pub const VERSION: u32 = 1;
+
+pub mod geometry {
+    pub mod shapes {
+        pub struct Circle {
+            pub radius: f64,
+        }
+    }
+}
+
+pub use geometry::shapes;
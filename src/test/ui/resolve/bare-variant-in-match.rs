@@ -0,0 +1,20 @@
+enum Shape {
+    Circle(f64),
+    Square { side: f64 },
+    Empty,
+}
+
+fn area(shape: Shape) -> f64 {
+    match shape {
+        Circle(radius) => radius * radius * 3.14,
+        //~^ ERROR cannot find tuple struct or tuple variant `Circle` in this scope
+        Square { side } => side * side,
+        //~^ ERROR cannot find struct, variant or union type `Square` in this scope
+        _ => 0.0,
+    }
+}
+
+fn main() {
+    let _ = Empty;
+    //~^ ERROR cannot find value `Empty` in this scope
+}
@@ -0,0 +1,18 @@
+fn main() {
+    let doubled = total * 2; //~ ERROR cannot find value `total` in this scope
+    let total = 10;
+    assert_eq!(doubled, 20);
+}
+
+fn nested() {
+    if true {
+        println!("{}", count); //~ ERROR cannot find value `count` in this scope
+    }
+    let count = 1;
+}
+
+fn dependent() {
+    let start = end - 1; //~ ERROR cannot find value `end` in this scope
+    let len = 4;
+    let end = len;
+}
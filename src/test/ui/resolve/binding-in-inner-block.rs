@@ -0,0 +1,9 @@
+fn main() {
+    let ready = true;
+    if ready {
+        let message = "done";
+        drop(message);
+    }
+    drop(message);
+    //~^ ERROR cannot find value `message` in this scope
+}
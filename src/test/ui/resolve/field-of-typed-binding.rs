@@ -0,0 +1,20 @@
+struct Circle {
+    radius: f64,
+}
+
+struct Square {
+    side: f64,
+}
+
+fn area(circle: &Circle) -> f64 {
+    radius * radius * 3.14
+    //~^ ERROR cannot find value `radius` in this scope
+    //~| ERROR cannot find value `radius` in this scope
+}
+
+fn main() {
+    let square: Square = Square { side: 2.0 };
+    let _ = side * side;
+    //~^ ERROR cannot find value `side` in this scope
+    //~| ERROR cannot find value `side` in this scope
+}
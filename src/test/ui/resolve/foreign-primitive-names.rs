@@ -0,0 +1,13 @@
+fn area(width: int, height: size_t) -> float64 {
+    //~^ ERROR cannot find type `int` in this scope
+    //~| ERROR cannot find type `size_t` in this scope
+    //~| ERROR cannot find type `float64` in this scope
+    0.0
+}
+
+struct Person {
+    name: string, //~ ERROR cannot find type `string` in this scope
+    adult: boolean, //~ ERROR cannot find type `boolean` in this scope
+}
+
+fn main() {}
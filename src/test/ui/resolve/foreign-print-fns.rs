@@ -0,0 +1,7 @@
+// Point C and C++ programmers to the printing macros.
+
+fn main() {
+    printf("%d\n", 1); //~ ERROR cannot find function `printf` in this scope
+    cout << "hello"; //~ ERROR cannot find value `cout` in this scope
+    perror("oops"); //~ ERROR cannot find function `perror` in this scope
+}
@@ -0,0 +1,13 @@
+// Associated types declared in an impl are suggested as `Self::Name` within that impl.
+
+struct Counter;
+
+impl Iterator for Counter {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Item> { //~ ERROR cannot find type `Item` in this scope
+        None
+    }
+}
+
+fn main() {}
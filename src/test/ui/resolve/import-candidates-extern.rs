@@ -0,0 +1,12 @@
+// aux-crate:shapes_aux=import-candidates-extern.rs
+// edition:2018
+// Items of extern crates are suggested by the shortest path naming them, looked up in the index
+// of public items in the crate metadata.
+
+use shapes_aux::VERSION;
+
+fn area(_: Circle) -> u32 { //~ ERROR cannot find type `Circle` in this scope
+    VERSION
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+// Point at an import in the parent module or in a sibling module when a name isn't in scope.
+
+#![allow(unused)]
+
+use std::collections::HashMap;
+
+mod shapes {
+    pub struct Circle;
+}
+
+mod render {
+    use shapes::Circle;
+
+    pub fn draw(_: &Circle) {}
+}
+
+mod layout {
+    pub fn place(_: Circle) {}
+    //~^ ERROR cannot find type `Circle` in this scope
+
+    pub fn index(_: &HashMap<u32, u32>) {}
+    //~^ ERROR cannot find type `HashMap` in this scope
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+struct Wrapper<'a, T = ()> {
+    value: &'a T,
+}
+
+fn wrap<'a>(value: &'a ()) -> Wrapper<a> {
+    //~^ ERROR cannot find type `a` in this scope
+    Wrapper { value }
+}
+
+struct Stack<T> {
+    top: T,
+    rest: Vec<'T>,
+    //~^ ERROR use of undeclared lifetime name `'T`
+    //~| ERROR wrong number of lifetime arguments: expected 0, found 1
+    //~| ERROR wrong number of type arguments: expected 1, found 0
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+// Suggest invoking a `macro_rules!` macro of this crate called like a function, only if it's in
+// scope at the call.
+
+#![allow(unused_macros)]
+
+macro_rules! double {
+    ($e:expr) => { $e * 2 };
+}
+
+#[macro_use]
+mod macros {
+    macro_rules! halve {
+        ($e:expr) => { $e / 2 };
+    }
+}
+
+fn main() {
+    let _ = double(2); //~ ERROR cannot find function `double` in this scope
+    let _ = halve(2); //~ ERROR cannot find function `halve` in this scope
+    let _ = triple(2); //~ ERROR cannot find function `triple` in this scope
+}
+
+macro_rules! triple {
+    ($e:expr) => { $e * 3 };
+}
@@ -0,0 +1,23 @@
+mod shapes {
+    pub enum Shape {
+        Circle(f64),
+        Square { side: f64 },
+    }
+}
+
+mod units {
+    pub struct Circle(pub f64);
+}
+
+use shapes::Shape;
+
+fn area(shape: &Shape) -> f64 {
+    match shape {
+        Circle(radius) => radius * radius * 3.14,
+        //~^ ERROR cannot find tuple struct or tuple variant `Circle` in this scope
+        Squar { side } => side * side,
+        //~^ ERROR cannot find struct, variant or union type `Squar` in this scope
+    }
+}
+
+fn main() {}
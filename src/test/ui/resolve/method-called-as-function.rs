@@ -0,0 +1,56 @@
+mod shapes {
+    pub struct Circle {
+        pub radius: f64,
+    }
+
+    impl Circle {
+        pub fn area(&self) -> f64 {
+            self.radius * self.radius * 3.14
+        }
+    }
+
+    pub trait Scale {
+        fn scale(&mut self, factor: f64);
+    }
+
+    impl Scale for Circle {
+        fn scale(&mut self, factor: f64) {
+            self.radius *= factor;
+        }
+    }
+}
+
+use shapes::Circle;
+
+fn grow(circle: &mut Circle) -> f64 {
+    scale(circle, 2.0);
+    //~^ ERROR cannot find function `scale` in this scope
+    area(circle)
+    //~^ ERROR cannot find function `area` in this scope
+}
+
+fn name_len(name: &String) -> usize {
+    len(name)
+    //~^ ERROR cannot find function `len` in this scope
+}
+
+fn main() {
+    let circle: Circle = Circle { radius: 1.0 };
+    let _ = area(&circle);
+    //~^ ERROR cannot find function `area` in this scope
+}
+
+struct Counter {
+    count: u32,
+}
+
+fn tick(counter: &mut Counter) {
+    increment(counter);
+    //~^ ERROR cannot find function `increment` in this scope
+}
+
+impl Counter {
+    fn increment(&mut self) {
+        self.count += 1;
+    }
+}
@@ -0,0 +1,14 @@
+#![feature(const_generics)]
+#![allow(incomplete_features)]
+
+struct Buffer {
+    data: [u8; N],
+    //~^ ERROR cannot find value `N` in this scope
+}
+
+fn fill<T>(value: T) -> [T; N] {
+    //~^ ERROR cannot find value `N` in this scope
+    unimplemented!()
+}
+
+fn main() {}
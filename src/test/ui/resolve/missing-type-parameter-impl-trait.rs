@@ -0,0 +1,15 @@
+struct Wrapper<T>(T);
+
+impl Clone for Wrapper<T> {
+    //~^ ERROR cannot find type `T` in this scope
+    fn clone(&self) -> Self {
+        unimplemented!()
+    }
+}
+
+trait Container {
+    fn get(&self) -> Option<T>;
+    //~^ ERROR cannot find type `T` in this scope
+}
+
+fn main() {}
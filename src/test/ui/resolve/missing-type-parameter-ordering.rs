@@ -0,0 +1,21 @@
+#![feature(const_generics)]
+#![allow(incomplete_features)]
+
+struct Grid<Cell, const SIZE: usize> {
+    cells: [Cell; SIZE],
+    origin: P,
+    //~^ ERROR cannot find type `P` in this scope
+}
+
+struct Cache<Key, Value = String> {
+    entries: Vec<(Key, Value)>,
+    hasher: H,
+    //~^ ERROR cannot find type `H` in this scope
+}
+
+struct Ring<Item = u8> {
+    slots: [Item; N],
+    //~^ ERROR cannot find value `N` in this scope
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+// Suggest the functions or constants of a module that is used as a value, the few most similar to
+// the written name first.
+
+#![allow(dead_code, unused_imports)]
+
+mod shapes {
+    pub fn area() -> u32 { 1 }
+    pub fn perimeter() -> u32 { 4 }
+    pub fn scale() {}
+    pub fn shear() {}
+    pub fn shift() {}
+    fn secret() {}
+    pub const SIDES: u32 = 4;
+}
+
+use shapes as sh;
+
+fn main() {
+    let _ = shapes(); //~ ERROR expected function, found module `shapes`
+    let _ = sh(); //~ ERROR expected function, found module `sh`
+    let _ = shapes; //~ ERROR expected value, found module `shapes`
+}
@@ -0,0 +1,14 @@
+fn main() {
+    let offset = 10;
+    fn add_offset(value: i32, scale: i32) -> i32 {
+        value * scale + offset //~ ERROR can't capture dynamic environment in a fn item
+    }
+    println!("{}", add_offset(1, 2));
+}
+
+fn generic() {
+    let base = 1;
+    fn scaled<T>(_: T) -> i32 {
+        base //~ ERROR can't capture dynamic environment in a fn item
+    }
+}
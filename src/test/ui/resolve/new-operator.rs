@@ -0,0 +1,30 @@
+// Recover from `new Type()` as it would be written in Java or C#, and suggest how values of the
+// type are created instead.
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+struct Meters(f64);
+
+impl Meters {
+    fn new(value: f64) -> Self {
+        Meters(value)
+    }
+}
+
+fn main() {
+    let _ = new String();
+    //~^ ERROR `new` is not an operator
+    //~| HELP call `String::new` to create a value
+    let _ = new std::vec::Vec(1, 2);
+    //~^ ERROR `new` is not an operator
+    //~| HELP `std::vec::Vec::new` takes 0 arguments
+    let _ = new Meters(1.0);
+    //~^ ERROR `new` is not an operator
+    //~| HELP call `Meters::new` to create a value
+    let _ = new Point();
+    //~^ ERROR `new` is not an operator
+    //~| HELP use a struct literal to create a value
+}
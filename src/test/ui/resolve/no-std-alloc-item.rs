@@ -0,0 +1,8 @@
+#![no_std]
+#![crate_type = "lib"]
+
+pub fn names() -> Vec<String> {
+    //~^ ERROR cannot find type `Vec` in this scope
+    //~| ERROR cannot find type `String` in this scope
+    Vec::new() //~ ERROR failed to resolve: use of undeclared type or module `Vec`
+}
@@ -0,0 +1,19 @@
+// Generic parameters of an outer item are suggested on the nested item, along with their bounds.
+
+use std::fmt::Debug;
+
+fn outer<T: Clone + Debug, U>(t: T, u: U)
+where
+    U: Default,
+{
+    fn show(t: T) {}
+    //~^ ERROR can't use generic parameters from outer function
+
+    struct Wrapper<V> {
+        value: V,
+        default: U,
+        //~^ ERROR can't use generic parameters from outer function
+    }
+}
+
+fn main() {}
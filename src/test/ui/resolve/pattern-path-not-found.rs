@@ -0,0 +1,22 @@
+// Unresolved paths in patterns suggest the closest constant of the module they were looked up in,
+// or a `const` in place of a local of the same name.
+
+mod limits {
+    pub const MAXIMUM: u32 = 100;
+    pub const MINIMUM: u32 = 0;
+}
+
+fn check(value: u32) {
+    let threshold = 50;
+    match value {
+        limits::Maximum => {}
+        //~^ ERROR cannot find unit struct, unit variant or constant `Maximum` in module `limits`
+        limits::threshold => {}
+        //~^ ERROR cannot find unit struct, unit variant or constant `threshold` in module `limits`
+        threshold(..) => {}
+        //~^ ERROR expected tuple struct or tuple variant, found local variable `threshold`
+        _ => {}
+    }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+mod bounds {
+    pub const EPSILON: f64 = 1e-9;
+}
+
+fn nearly_equal(a: f64, b: f64) -> bool {
+    (a - b).abs() < EPSILON //~ ERROR cannot find value `EPSILON` in this scope
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+#![feature(rustc_attrs)]
+#![rustc_on_unresolved = "did you forget `use engine::prelude::*`?"]
+
+mod engine {
+    pub mod prelude {}
+}
+
+fn main() {
+    let _: Sprite = load();
+    //~^ ERROR cannot find type `Sprite` in this scope
+    //~| ERROR cannot find function `load` in this scope
+}
+
+#[rustc_on_unresolved = "`{name}` is not part of the physics API"]
+mod physics {
+    fn step() {
+        integrate();
+        //~^ ERROR cannot find function `integrate` in this scope
+    }
+}
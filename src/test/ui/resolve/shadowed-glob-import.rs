@@ -0,0 +1,19 @@
+// edition:2018
+
+mod traits {
+    pub trait Shape {}
+}
+
+mod shapes {
+    pub struct Shape;
+}
+
+use traits::*;
+use shapes::Shape;
+
+struct Square;
+
+impl Shape for Square {}
+//~^ ERROR expected trait, found struct `Shape`
+
+fn main() {}
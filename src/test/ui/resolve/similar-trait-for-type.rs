@@ -0,0 +1,18 @@
+mod graphics {
+    pub trait Render {
+        fn render(&self);
+    }
+}
+
+struct Renderer;
+struct Canvas;
+
+impl Renderer for Canvas {
+    //~^ ERROR expected trait, found struct `Renderer`
+    fn render(&self) {}
+}
+
+fn draw<T: Renderer>(_: T) {}
+//~^ ERROR expected trait, found struct `Renderer`
+
+fn main() {}
@@ -0,0 +1,4 @@
+fn main() {
+    let base = std::env::args().count();
+    static LIMIT: usize = base * 2; //~ ERROR attempt to use a non-constant value in a constant
+}
@@ -0,0 +1,18 @@
+struct Empty {}
+
+struct Marker {
+    unit: (),
+}
+
+fn main() {
+    let unit = ();
+    while Marker { unit } {}
+    //~^ ERROR expected value, found struct `Marker`
+    if let Some(_) = Marker { unit } {}
+    //~^ ERROR expected value, found struct `Marker`
+    // Empty braces are as likely the body, parentheses are only suggested for fields.
+    match Empty {} {}
+    //~^ ERROR expected value, found struct `Empty`
+    if Empty {}
+    //~^ ERROR expected value, found struct `Empty`
+}
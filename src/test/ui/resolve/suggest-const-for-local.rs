@@ -0,0 +1,9 @@
+const BASE: usize = 4;
+
+fn main() {
+    let len: usize = BASE * 2;
+    let _array = [0u8; len]; //~ ERROR attempt to use a non-constant value in a constant
+    let mut count = 3;
+    count += 1;
+    let _other = [0u8; count]; //~ ERROR attempt to use a non-constant value in a constant
+}
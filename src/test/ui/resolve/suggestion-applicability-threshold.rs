@@ -0,0 +1,20 @@
+// compile-flags: -Z suggestion-applicability=machine-applicable
+// Only the suggestions that are certain enough to be applied automatically are kept.
+
+struct Counter { count: u32 }
+
+impl Counter {
+    fn bump(&mut self) {
+        count += 1;
+        //~^ ERROR cannot find value `count` in this scope
+    }
+}
+
+fn main() {
+    let counter = Counter { count: 0 };
+    let _ = countr;
+    //~^ ERROR cannot find value `countr` in this scope
+    let (total, totes) = (1, 2);
+    let _ = totel;
+    //~^ ERROR cannot find value `totel` in this scope
+}
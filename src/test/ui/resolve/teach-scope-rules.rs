@@ -0,0 +1,10 @@
+// compile-flags: -Z teach
+
+fn scaled(radius: f64) -> f64 {
+    let scale: Factor = 2.0;
+    //~^ ERROR cannot find type `Factor` in this scope
+    radius * scale * ratio
+    //~^ ERROR cannot find value `ratio` in this scope
+}
+
+fn main() {}
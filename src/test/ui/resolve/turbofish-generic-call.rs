@@ -0,0 +1,12 @@
+struct Config { verbose: bool }
+struct ParseError { line: usize }
+
+fn parse<T, E>(_input: &str) -> Result<T, E> {
+    unimplemented!()
+}
+
+fn main() {
+    let _ = (parse<Config, ParseError>("verbose"));
+    //~^ ERROR expected value, found struct `Config`
+    //~| ERROR expected value, found struct `ParseError`
+}
@@ -0,0 +1,8 @@
+trait Shape {}
+
+type Figure = dyn Shape;
+
+fn describe<T: Figure>(_shape: &T) {}
+//~^ ERROR expected trait, found type alias `Figure`
+
+fn main() {}
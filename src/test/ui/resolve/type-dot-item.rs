@@ -0,0 +1,12 @@
+// Suggest the path separator when a type is used as the receiver of a field access or method
+// call, as in `Type.method()`.
+
+fn make<T: Default>() -> T {
+    T.default() //~ ERROR expected value, found type parameter `T`
+}
+
+fn main() {
+    let _ = i32.max_value(); //~ ERROR expected value, found builtin type `i32`
+    let _: Option<u8> = Option.None; //~ ERROR expected value, found enum `Option`
+    let _: u8 = Default.default(); //~ ERROR expected value, found trait `Default`
+}
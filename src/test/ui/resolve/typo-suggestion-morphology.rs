@@ -0,0 +1,13 @@
+// Names only differing in the inflection of their words are suggested, even when they are too
+// far apart to be considered typos of each other.
+
+struct Tax;
+
+fn stop_entry() {}
+
+fn main() {
+    stopping_entries();
+    //~^ ERROR cannot find function `stopping_entries` in this scope
+    let _: Taxes = Tax;
+    //~^ ERROR cannot find type `Taxes` in this scope
+}
@@ -0,0 +1,10 @@
+// compile-flags: -Z suggestion-applicability=machine-applicable
+// A similarly named type parameter of an outer item can't be applied automatically, it isn't
+// usable in the nested item.
+
+fn outer<Tx>(_: Tx) {
+    fn inner(_: Ty) {}
+    //~^ ERROR cannot find type `Ty` in this scope
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+// Check that an unresolved local is only reported once per scope, and that later uses of the
+// same name don't produce follow-on errors.
+
+fn main() {
+    let count = 1;
+    let total = missing + count; //~ ERROR cannot find value `missing` in this scope
+    let doubled = missing * 2;
+    println!("{} {} {}", total, doubled, missing);
+}
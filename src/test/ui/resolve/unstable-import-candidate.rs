@@ -0,0 +1,6 @@
+// An item that could be imported but is gated behind a feature is reported along with the gate.
+
+fn count<T: Step>(_: T) {}
+//~^ ERROR cannot find trait `Step` in this scope
+
+fn main() {}
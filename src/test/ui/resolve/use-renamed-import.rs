@@ -0,0 +1,14 @@
+mod foo {
+    pub fn bar() {}
+    pub struct Qux;
+}
+
+use foo::bar as baz;
+use foo::{Qux as Quux};
+
+fn main() {
+    bar();
+    //~^ ERROR cannot find function `bar` in this scope
+    let _: Qux = Quux;
+    //~^ ERROR cannot find type `Qux` in this scope
+}
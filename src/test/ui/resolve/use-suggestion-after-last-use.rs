@@ -0,0 +1,18 @@
+// run-rustfix
+// Suggest importing a missing item after the last `use` of the module.
+
+#![allow(unused)]
+
+mod shapes {
+    pub struct Circle;
+}
+
+mod render {
+    use std::fmt::Debug;
+    use std::fmt::Display;
+
+    pub fn describe(_: Circle) {}
+    //~^ ERROR cannot find type `Circle` in this scope
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+// run-rustfix
+
+#![allow(dead_code)]
+
+mod shapes {
+    pub enum Shape {
+        Circle(u32),
+    }
+}
+
+fn make() -> Circle { //~ ERROR cannot find type `Circle` in this scope
+    shapes::Shape::Circle(1)
+}
+
+fn main() {}
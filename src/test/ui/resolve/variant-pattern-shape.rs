@@ -0,0 +1,26 @@
+enum Shape {
+    Point,
+    Circle(f32),
+    Rect { w: f32, h: f32 },
+}
+
+fn area(shape: Shape) -> f32 {
+    match shape {
+        Shape::Point() => 0.0,
+        //~^ ERROR expected tuple struct or tuple variant, found unit variant `Shape::Point`
+        Shape::Circle => 1.0,
+        //~^ ERROR expected unit struct, unit variant or constant, found tuple variant
+        Shape::Rect(..) => 2.0,
+        //~^ ERROR expected tuple struct or tuple variant, found struct variant `Shape::Rect`
+    }
+}
+
+fn unwrap_or_zero(value: Option<u32>) -> u32 {
+    match value {
+        Some { x } => x,
+        //~^ ERROR tuple variant `Some` written as struct variant
+        None => 0,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+// edition:2018
+
+mod other {}
+
+mod outer {
+    mod sibling {}
+
+    mod inner {
+        pub(in crate::outer::iner) struct A;
+        //~^ ERROR failed to resolve
+        pub(in crate::other) struct B;
+        //~^ ERROR visibilities can only be restricted to ancestor modules
+        pub(in crate::outer::sibling) struct C;
+        //~^ ERROR visibilities can only be restricted to ancestor modules
+    }
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+trait Canvas {
+    fn draw(&self, shape: Shape);
+    //~^ WARN trait objects without an explicit `dyn` are deprecated
+}
+
+fn unit_square() -> Shape {
+    //~^ WARN trait objects without an explicit `dyn` are deprecated
+    //~| ERROR return type cannot have an unboxed trait object
+    unimplemented!()
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+// Check that a missing lifetime in an associated type isn't suggested to be introduced on the
+// impl when its header can't name it, as the lifetime would be unconstrained, but `'static` is.
+
+trait Project {
+    type Output;
+}
+
+impl<T> Project for Vec<T> {
+    type Output = &T; //~ ERROR missing lifetime specifier
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+// Check that the higher-ranked rewrite of a fn pointer type also threads the new lifetime into
+// paths in the return type that are missing it.
+
+struct Ref<'a, T>(&'a T);
+
+type Select = fn(&u8, &u8) -> Ref<u8>;
+//~^ ERROR missing lifetime specifier
+
+fn main() {}
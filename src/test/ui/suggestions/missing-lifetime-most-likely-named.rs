@@ -0,0 +1,14 @@
+// When several named lifetimes are in scope, suggest the one of the argument that the function
+// returns a borrow of.
+
+fn longest_prefix<'src, 'buf>(text: &'src str, prefix: &'src str) -> &str {
+    //~^ ERROR missing lifetime specifier
+    &text[..prefix.len()]
+}
+
+fn first_word<'a, 'b>(text: &'a str, sep: &'b str, _end: &'b str) -> &str {
+    //~^ ERROR missing lifetime specifier
+    text.split(sep).next().unwrap()
+}
+
+fn main() {}
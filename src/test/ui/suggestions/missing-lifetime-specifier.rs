@@ -23,8 +23,6 @@ thread_local! {
     static b: RefCell<HashMap<i32, Vec<Vec<&Bar>>>> = RefCell::new(HashMap::new());
     //~^ ERROR missing lifetime specifier
     //~| ERROR missing lifetime specifier
-    //~| ERROR missing lifetime specifier
-    //~| ERROR missing lifetime specifier
     //~| ERROR the lifetime bound for this object type cannot be deduced from context
     //~| ERROR the lifetime bound for this object type cannot be deduced from context
 }
@@ -37,8 +35,6 @@ thread_local! {
     static d: RefCell<HashMap<i32, Vec<Vec<&Tar<i32>>>>> = RefCell::new(HashMap::new());
     //~^ ERROR missing lifetime specifier
     //~| ERROR missing lifetime specifier
-    //~| ERROR missing lifetime specifier
-    //~| ERROR missing lifetime specifier
     //~| ERROR the lifetime bound for this object type cannot be deduced from context
     //~| ERROR the lifetime bound for this object type cannot be deduced from context
 }
@@ -0,0 +1,14 @@
+// Check that introducing a named lifetime for a borrowed type parameter also suggests the bound
+// that the type parameter has to satisfy.
+
+struct Wrapper<T> {
+    value: &T,
+    //~^ ERROR missing lifetime specifier
+}
+
+fn pick<T>(first: &T, second: &T) -> &T {
+    //~^ ERROR missing lifetime specifier
+    first
+}
+
+fn main() {}
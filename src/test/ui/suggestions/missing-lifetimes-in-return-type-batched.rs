@@ -0,0 +1,11 @@
+// Check that all of the lifetimes missing from a return type are reported together, with a
+// single suggestion that introduces one new lifetime for all of them.
+
+struct Pair<'a, T>(&'a T, &'a T);
+
+fn split(left: &u8, right: &u8) -> (&u8, Pair<u8>) {
+    //~^ ERROR missing lifetime specifiers
+    unimplemented!()
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+// Check that an undeclared lifetime used as the bound of a returned trait object suggests the
+// elided and `'static` bounds instead of introducing a new lifetime.
+
+struct Tokens(Vec<String>);
+
+impl Tokens {
+    fn iter(&self) -> Box<dyn Iterator<Item = &String> + 'a> {
+        //~^ ERROR use of undeclared lifetime name `'a`
+        Box::new(self.0.iter())
+    }
+}
+
+fn main() {}
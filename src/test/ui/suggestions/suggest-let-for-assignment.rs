@@ -0,0 +1,5 @@
+fn main() {
+    value = 42; //~ ERROR cannot find value `value` in this scope
+    //~^ HELP you might have meant to introduce a new binding
+    println!("{}", value);
+}
@@ -0,0 +1,6 @@
+fn main() {
+    let count = 0;
+    total: u32
+        = count + 1;
+    //~^^ ERROR cannot find value `total` in this scope
+}
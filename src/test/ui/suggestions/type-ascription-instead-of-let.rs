@@ -5,6 +5,5 @@ fn main() {
         temp: i32 = fun(5i32);
         //~^ ERROR cannot find value `temp` in this scope
         temp + value + 1
-        //~^ ERROR cannot find value `temp` in this scope
     };
 }
@@ -0,0 +1,12 @@
+// Check that an undeclared lifetime in a type written in a function body gets a suggestion to
+// leave it to inference.
+
+use std::cell::{Ref, RefCell};
+
+fn first<T: Copy>(cell: &RefCell<Vec<T>>) -> T {
+    let items: Ref<'a, Vec<T>> = cell.borrow();
+    //~^ ERROR use of undeclared lifetime name `'a`
+    items[0]
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+// Check that a lifetime used only once, in a position where `'_` is accepted, can also be
+// replaced by the anonymous lifetime.
+
+struct Wrapper<'a>(&'a str);
+
+fn len(s: &'a str) -> usize { //~ ERROR use of undeclared lifetime name `'a`
+    s.len()
+}
+
+fn twice(x: &'b u8) -> &'b u8 { //~ ERROR use of undeclared lifetime name `'b`
+    //~^ ERROR use of undeclared lifetime name `'b`
+    x
+}
+
+impl Wrapper<'w> {} //~ ERROR use of undeclared lifetime name `'w`
+
+fn main() {}
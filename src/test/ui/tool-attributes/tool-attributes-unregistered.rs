@@ -0,0 +1,8 @@
+#![feature(register_tool)]
+#![register_tool(tool)]
+
+#[rustfm::skip] //~ ERROR failed to resolve: use of undeclared type or module `rustfm`
+fn f() {}
+
+#[myfmt::skip] //~ ERROR failed to resolve: use of undeclared type or module `myfmt`
+fn main() {}
@@ -4,7 +4,6 @@ enum X {
 
 fn main() {
     match X::Y(0) {
-        X::Y { number } => {} //~ ERROR does not have a field named `number`
-        //~^ ERROR pattern does not mention field `0`
+        X::Y { number } => {} //~ ERROR tuple variant `X::Y` written as struct variant
     }
 }
@@ -0,0 +1,12 @@
+// Check that an undeclared lifetime in a where-clause predicate also suggests
+// making the predicate higher-ranked.
+
+trait Visitor<'a> {}
+
+fn walk<V>(_visitor: V)
+where
+    V: Visitor<'ast>, //~ ERROR use of undeclared lifetime name `'ast`
+{
+}
+
+fn main() {}